@@ -0,0 +1,32 @@
+//! Measures the cost of executing a block made of many statements, each of them
+//! reading and reassigning the same variable. Run with
+//! `cargo bench --bench block_statements`.
+//! The block is typechecked and executed once per run, so the time includes both
+//! passes over its statements.
+
+use std::time::Instant;
+
+use jinko::{io_trait::JkStdReader, Context, JkInt, ToObjectInstance};
+
+const STATEMENTS: usize = 1_000;
+const RUNS: usize = 5;
+
+fn main() {
+    let mut code = String::from("mut x = 0;\n");
+    (0..STATEMENTS).for_each(|_| code.push_str("x = x + 1;\n"));
+    code.push('x');
+
+    for run in 0..RUNS {
+        let mut ctx = Context::new(Box::new(JkStdReader));
+
+        let start = Instant::now();
+        let result = ctx.eval(&code).expect("benchmark code should not fail");
+        let elapsed = start.elapsed();
+
+        assert_eq!(result, Some(JkInt::from(STATEMENTS as i64).to_instance()));
+        println!(
+            "run {run}: block of {STATEMENTS} statements in {elapsed:?} ({:?}/statement)",
+            elapsed / STATEMENTS as u32
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Measures the cost of calling functions repeatedly from a tight loop, which is
+//! dominated by function lookups. Run with `cargo bench --bench function_calls`.
+
+use std::time::Instant;
+
+use jinko::{io_trait::JkStdReader, Context, JkInt, ToObjectInstance};
+
+const ITERATIONS: usize = 10_000;
+const RUNS: usize = 5;
+
+fn main() {
+    let code = format!(
+        "func add_one(x: int) -> int {{ x + 1 }}
+        func nested(x: int) -> int {{ add_one(add_one(x)) }}
+        mut acc = 0;
+        for i in 0..{ITERATIONS} {{
+            acc = nested(acc);
+        }}
+        acc"
+    );
+
+    for run in 0..RUNS {
+        let mut ctx = Context::new(Box::new(JkStdReader));
+
+        let start = Instant::now();
+        let result = ctx.eval(&code).expect("benchmark code should not fail");
+        let elapsed = start.elapsed();
+
+        assert_eq!(
+            result,
+            Some(JkInt::from(2 * ITERATIONS as i64).to_instance())
+        );
+        println!(
+            "run {run}: {ITERATIONS} iterations in {elapsed:?} ({:?}/iteration)",
+            elapsed / ITERATIONS as u32
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Measures the cost of looking up variables by name from a tight loop, where every
+//! iteration reads and writes several identifiers. Run with
+//! `cargo bench --bench identifier_lookups`.
+
+use std::time::Instant;
+
+use jinko::{io_trait::JkStdReader, Context, JkInt, ToObjectInstance};
+
+const ITERATIONS: usize = 10_000;
+// Five reads and two assignments
+const LOOKUPS_PER_ITERATION: usize = 7;
+const RUNS: usize = 5;
+
+fn main() {
+    let code = format!(
+        "mut first_accumulator = 0;
+        mut second_accumulator = 0;
+        for i in 0..{ITERATIONS} {{
+            first_accumulator = first_accumulator + i;
+            second_accumulator = second_accumulator + first_accumulator - i;
+        }}
+        second_accumulator"
+    );
+    let expected = (0..ITERATIONS as i64)
+        .scan(0, |first, i| {
+            *first += i;
+            Some(*first - i)
+        })
+        .sum::<i64>();
+
+    for run in 0..RUNS {
+        let mut ctx = Context::new(Box::new(JkStdReader));
+
+        let start = Instant::now();
+        let result = ctx.eval(&code).expect("benchmark code should not fail");
+        let elapsed = start.elapsed();
+
+        assert_eq!(result, Some(JkInt::from(expected).to_instance()));
+        println!(
+            "run {run}: {} identifier lookups in {elapsed:?} ({:?}/lookup)",
+            ITERATIONS * LOOKUPS_PER_ITERATION,
+            elapsed / (ITERATIONS * LOOKUPS_PER_ITERATION) as u32
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Measures the cost of reading a large string variable repeatedly from a tight
+//! loop. Run with `cargo bench --bench variable_reads`.
+//! The string is defined by the host rather than written in the source code, so that
+//! the size of the program does not get in the way of the measurements.
+
+use std::time::Instant;
+
+use jinko::{io_trait::JkStdReader, Context, JkString, ToObjectInstance};
+
+const STRING_SIZE: usize = 1 << 20;
+const ITERATIONS: usize = 10_000;
+const RUNS: usize = 5;
+
+fn main() {
+    let large = JkString::from("a".repeat(STRING_SIZE));
+    let code = format!(
+        "mut last = \"\";
+        for i in 0..{ITERATIONS} {{
+            last = large;
+        }}
+        last"
+    );
+
+    for run in 0..RUNS {
+        let mut ctx = Context::new(Box::new(JkStdReader));
+        ctx.define_constant("large", large.clone()).unwrap();
+
+        let start = Instant::now();
+        let result = ctx.eval(&code).expect("benchmark code should not fail");
+        let elapsed = start.elapsed();
+
+        assert_eq!(result, Some(large.to_instance()));
+        println!(
+            "run {run}: {ITERATIONS} reads of a {STRING_SIZE} bytes string in {elapsed:?} ({:?}/read)",
+            elapsed / ITERATIONS as u32
+        );
+    }
+}
@@ -305,8 +305,8 @@ impl From<io::Error> for Error {
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         write!(f, "{}", self.kind.as_str())?;
-        if self.msg.is_some() {
-            write!(f, ": {}", self.msg.as_ref().unwrap())?;
+        if let Some(msg) = &self.msg {
+            write!(f, ": {}", msg)?;
         }
 
         if let Some(loc) = &self.loc {
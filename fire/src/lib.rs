@@ -115,7 +115,6 @@ impl<'ast, 'fir> Fire<'ast, 'fir> {
         &self.fir.nodes[&r.expect_resolved()]
     }
 
-    #[must_use]
     fn fire_block(
         &mut self,
         node: &Node<FlattenData<'_>>,
@@ -135,7 +134,6 @@ impl<'ast, 'fir> Fire<'ast, 'fir> {
     }
 
     // FIXME: Does this need "_c"?
-    #[must_use]
     fn fire_constant(
         &mut self,
         node: &Node<FlattenData<'_>>,
@@ -159,7 +157,6 @@ impl<'ast, 'fir> Fire<'ast, 'fir> {
         KeepGoing
     }
 
-    #[must_use]
     fn fire_call(
         &mut self,
         node: &Node<FlattenData<'_>>,
@@ -177,7 +174,7 @@ impl<'ast, 'fir> Fire<'ast, 'fir> {
             self.gc.transfer(arg, def_args[i].expect_resolved());
 
             KeepGoing
-        });
+        })?;
 
         // FIXME: We need to add bindings here between the function's variables and the arguments given to the call
         match block {
@@ -206,7 +203,6 @@ impl<'ast, 'fir> Fire<'ast, 'fir> {
         }
     }
 
-    #[must_use]
     fn fire_binding(
         &mut self,
         node: &Node<FlattenData<'_>>,
@@ -221,7 +217,6 @@ impl<'ast, 'fir> Fire<'ast, 'fir> {
         KeepGoing
     }
 
-    #[must_use]
     fn fire_return(
         &mut self,
         node: &Node<FlattenData<'_>>,
@@ -236,12 +231,10 @@ impl<'ast, 'fir> Fire<'ast, 'fir> {
         ControlFlow::Break(EarlyExit::Return(node.origin))
     }
 
-    #[must_use]
     fn fire_reference(&mut self, node_ref: &RefIdx) -> ControlFlow<EarlyExit> {
         self.fire_node(self.access(node_ref))
     }
 
-    #[must_use]
     fn fire_condition(
         &mut self,
         node: &Node<FlattenData<'_>>,
@@ -284,7 +277,6 @@ impl<'ast, 'fir> Fire<'ast, 'fir> {
         KeepGoing
     }
 
-    #[must_use]
     fn fire_node(&mut self, node: &Node<FlattenData<'_>>) -> ControlFlow<EarlyExit> {
         match &node.kind {
             Kind::Constant(c) => self.fire_constant(node, c),
@@ -928,7 +928,7 @@ impl<'ast> Ctx<'ast> {
 }
 
 impl FlattenAst for ast::Ast {
-    fn flatten(&self) -> Fir<FlattenData> {
+    fn flatten(&self) -> Fir<FlattenData<'_>> {
         let ctx = Ctx {
             fir: Fir::new(),
             origin: OriginIdx::default(),
@@ -945,7 +945,7 @@ impl FlattenAst for ast::Ast {
 }
 
 pub trait FlattenAst: Sized {
-    fn flatten(&self) -> Fir<FlattenData>;
+    fn flatten(&self) -> Fir<FlattenData<'_>>;
 }
 
 #[cfg(test)]
@@ -28,6 +28,9 @@ pub struct Args {
     #[structopt(short, long)]
     debug: bool,
 
+    #[structopt(long = "strict", help = "Reject expressions whose values are not used")]
+    strict: bool,
+
     #[structopt(parse(from_os_str))]
     input: Option<PathBuf>,
 
@@ -82,6 +85,11 @@ impl Args {
         self.check
     }
 
+    /// Is the interpreter launched in strict mode
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+
     /// Arguments given to the program
     pub fn project_args(&self) -> Vec<String> {
         self.arguments.clone()
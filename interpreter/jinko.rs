@@ -63,12 +63,27 @@ fn handle_exit_code(result: Option<ObjectInstance>) -> ! {
     }
 }
 
+/// Show the state of the context when reaching a breakpoint, and wait for the user
+/// to resume execution
+fn breakpoint_prompt(ctx: &Context) {
+    eprintln!("[{}] breakpoint reached", "BRKP".purple());
+    eprintln!("{}", ctx.print());
+    eprint!("press enter to continue...");
+
+    let mut line = String::new();
+    let _ = std::io::stdin().read_line(&mut line);
+}
+
 fn run_tests(ctx: &mut Context) -> Result<Option<ObjectInstance>, Error> {
     let res = ctx.execute()?;
+    if ctx.quit_code().is_some() {
+        return Ok(res);
+    }
+
     let tests: Vec<String> = ctx
         .tests()
-        .iter()
-        .map(|(k, _)| k.into())
+        .keys()
+        .map(|k| k.into())
         .filter(|test_name| {
             if !ctx.args().is_empty() {
                 ctx.args().contains(test_name)
@@ -193,7 +208,14 @@ fn handle_input(args: &Args, file: &Path) -> InteractResult {
     if args.experimental {
         experimental_pipeline(&input, file)
     } else {
-        let mut ctx = Context::new(Box::new(jinko::io_trait::JkStdReader));
+        // The prelude is part of the standard library
+        let mut ctx = match args.nostdlib() {
+            true => Context::without_prelude(Box::new(jinko::io_trait::JkStdReader)),
+            false => Context::new(Box::new(jinko::io_trait::JkStdReader)),
+        };
+        ctx.set_strict(args.strict());
+        ctx.debug_mode = args.debug();
+        ctx.set_breakpoint_callback(breakpoint_prompt);
 
         if !args.nostdlib() {
             ctx.init_stdlib()?;
@@ -270,5 +292,9 @@ fn main() -> anyhow::Result<()> {
         .map(|filename| handle_input(&args, filename))
         .unwrap()?;
 
+    if let Some(code) = result.1.quit_code() {
+        std::process::exit(code);
+    }
+
     handle_exit_code(result.0)
 }
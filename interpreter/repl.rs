@@ -80,13 +80,14 @@ impl Repl {
         self.reader.set_prompt(&Prompt::get(&ctx))?;
 
         while let ReadResult::Input(input) = self.reader.read_line()? {
-            ctx.set_code(input.clone());
-
-            if let Ok(Some(res)) = ctx.eval(&input) {
+            // Errors are emitted when executing the line
+            if let Ok(Some(res)) = ctx.run_line(&input) {
                 println!("{}", ReplInstance(res));
             }
 
-            ctx.clear_errors();
+            if ctx.quit_code().is_some() {
+                break;
+            }
 
             self.reader.set_prompt(&Prompt::get(&ctx))?;
         }
@@ -261,6 +261,9 @@ impl SpanTuple {
             }
             // Last line, for which we only push up to end.column characters
             else if self.start.line() + i == self.end.line() {
+                // The end of a span can point right after the last character of
+                // its line
+                let end_col = end_col.min(line.len());
                 result.push_str(&self.format_line(separator, i, &line[..end_col]));
                 break;
             } else if self.start.line() == self.end.line() {
@@ -374,4 +377,17 @@ mod tests {
           ---"#
         );
     }
+
+    #[test]
+    fn multi_line_span_ending_after_last_char() {
+        let s = Location::new(1, 1);
+        let e = Location::new(2, 3);
+        let span = SpanTuple::with_source(SourceOwned::Input(String::from("a +\nv2")), s, e);
+
+        assert_eq!(
+            span.to_string(&'>', &'-'),
+            r#"    1 > a +
+    2 > v2"#
+        );
+    }
 }
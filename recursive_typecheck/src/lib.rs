@@ -441,6 +441,7 @@ impl Pass<FlattenData<'_>, TypeData, Error> for TypeCtx {
 }
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod tests {
     use super::*;
     use flatten::FlattenAst;
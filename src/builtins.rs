@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::context::Context;
+use crate::error::{ErrKind, Error};
 #[cfg(feature = "ffi")]
 use crate::ffi;
 use crate::instance::{FromObjectInstance, ObjectInstance, ToObjectInstance};
@@ -13,6 +14,55 @@ use crate::value::{JkBool, JkChar, JkFloat, JkInt, JkString};
 type Args = Vec<Box<dyn Instruction>>;
 type BuiltinFn = fn(&mut Context, Args) -> Option<ObjectInstance>;
 
+/// Builtins which do not need to be declared before being called, and which accept
+/// a single value of any primitive type
+const PRIMITIVE_PRINTERS: [&str; 2] = ["print", "println"];
+
+/// Signature of a builtin function. The interpreter's [`Builtins`] and the builtins
+/// known to the typechecker are both derived from the [`BUILTINS`] table
+pub(crate) struct BuiltinSignature {
+    pub(crate) name: &'static str,
+    pub(crate) generics: &'static [&'static str],
+    pub(crate) args: &'static [(&'static str, &'static str)],
+    pub(crate) return_ty: Option<&'static str>,
+    function: BuiltinFn,
+}
+
+macro_rules! builtin {
+    (@ret) => { None };
+    (@ret $ret_ty:ident) => { Some(stringify!($ret_ty)) };
+    ($name:ident $([$($generic:ident),*])? ($($arg:ident: $arg_ty:ident),*) $(-> $ret_ty:ident)? => $function:ident) => {
+        BuiltinSignature {
+            name: stringify!($name),
+            generics: &[$($(stringify!($generic)),*)?],
+            args: &[$((stringify!($arg), stringify!($arg_ty))),*],
+            return_ty: builtin!(@ret $($ret_ty)?),
+            function: $function,
+        }
+    };
+}
+
+/// Signatures of all the builtin functions, along with their implementation
+pub(crate) const BUILTINS: &[BuiltinSignature] = &[
+    builtin!(__builtin_string_len(s: string) -> int => string_len),
+    builtin!(__builtin_string_concat(a: string, b: string) -> string => string_concat),
+    builtin!(__builtin_string_display(s: string, add_newline: bool) => string_display),
+    builtin!(__builtin_string_display_err(s: string, add_newline: bool) => string_display_err),
+    builtin!(__builtin_string_is_empty(s: string) -> bool => string_is_empty),
+    builtin!(__builtin_string_equals(lhs: string, rhs: string) -> bool => string_equals),
+    builtin!(__builtin_ffi_link_with(path: string) => ffi_link_with),
+    builtin!(__builtin_fmt_char(value: char) -> string => fmt_char),
+    builtin!(__builtin_fmt_float(value: float) -> string => fmt_float),
+    builtin!(__builtin_arg_get(arg_index: int) -> string => arg_get),
+    builtin!(__builtin_arg_amount() -> int => arg_amount),
+    builtin!(__builtin_exit(exit_code: int) => exit),
+    builtin!(size_of[T](value: T) -> int => size_of),
+    builtin!(type_of[T](value: T) -> string => type_of),
+    // The printers accept a value of any primitive type, which is checked separately
+    builtin!(print[T](value: T) => print),
+    builtin!(println[T](value: T) => println),
+];
+
 /// Contains the various components declared during the interpreter's initialization
 pub struct Builtins {
     functions: HashMap<String, BuiltinFn>,
@@ -45,7 +95,7 @@ fn string_display(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
         println!()
     }
 
-    None
+    Some(ObjectInstance::void())
 }
 
 fn string_display_err(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
@@ -58,7 +108,7 @@ fn string_display_err(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
         eprintln!()
     }
 
-    None
+    Some(ObjectInstance::void())
 }
 
 fn string_is_empty(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
@@ -74,6 +124,60 @@ fn string_equals(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
     Some(JkBool::from(lhs == rhs).to_instance())
 }
 
+/// Display a primitive value on the context's standard output. Strings are
+/// displayed without their surrounding quotes
+fn primitive_display(ctx: &mut Context, args: Args, add_newline: bool) -> Option<ObjectInstance> {
+    let instance = args[0].execute(ctx)?;
+    let value = match instance
+        .downcast::<JkString>()
+        .map(|s| s.0)
+        .or_else(|| instance.downcast::<JkInt>().map(|v| v.print()))
+        .or_else(|| {
+            instance
+                .downcast::<JkFloat>()
+                .map(|v| v.format(ctx.float_format()))
+        })
+        .or_else(|| instance.downcast::<JkBool>().map(|v| v.print()))
+        .or_else(|| instance.downcast::<JkChar>().map(|v| v.print()))
+    {
+        Some(value) => value,
+        None => {
+            ctx.error(
+                Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!(
+                        "cannot display a value of type `{}`: expected a primitive type",
+                        instance.ty()
+                    ))
+                    .with_loc(args[0].location().cloned()),
+            );
+            return None;
+        }
+    };
+
+    let out = ctx.stdout();
+    let written = match add_newline {
+        true => writeln!(out, "{value}"),
+        false => write!(out, "{value}"),
+    };
+
+    if let Err(e) = written {
+        ctx.error(Error::new(ErrKind::Context).with_msg(format!("couldn't display value: {e}")));
+        return None;
+    }
+
+    Some(ObjectInstance::void())
+}
+
+/// Display a primitive value on stdout
+fn print(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
+    primitive_display(ctx, args, false)
+}
+
+/// Display a primitive value and a newline on stdout
+fn println(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
+    primitive_display(ctx, args, true)
+}
+
 /// Link with a given library at runtime
 fn ffi_link_with(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
     #[cfg(feature = "ffi")]
@@ -82,15 +186,14 @@ fn ffi_link_with(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
 
         if let Err(e) = ffi::link_with(ctx, PathBuf::from(&lib_path)) {
             ctx.error(e.with_msg(format!("couldn't link with library `{}`", &lib_path)));
+            return None;
         }
 
-        None
+        Some(ObjectInstance::void())
     }
 
     #[cfg(not(feature = "ffi"))]
     {
-        use crate::{ErrKind, Error};
-
         ctx.error(Error::new(ErrKind::Context).with_msg(format!(
             "jinko is not compiled with FFI support. `link_with()` is disabled"
         )));
@@ -103,6 +206,15 @@ fn ffi_link_with(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
 fn arg_get(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
     let idx = JkInt::from_instance(&args[0].execute(ctx).unwrap()).0;
 
+    if idx < 0 {
+        ctx.error(
+            Error::new(ErrKind::IndexOutOfBounds)
+                .with_msg(format!("invalid argument index: `{idx}`"))
+                .with_loc(args[0].location().cloned()),
+        );
+        return None;
+    }
+
     let args = ctx.args();
 
     let result_string = match idx {
@@ -110,7 +222,6 @@ fn arg_get(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
             s.to_str().unwrap().to_string()
         }),
         _ => args
-            // FIXME: Is this cast valid?
             .get((idx - 1) as usize)
             .map(|s| s.to_owned())
             .unwrap_or_default(),
@@ -138,9 +249,9 @@ fn fmt_char(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
 }
 
 fn fmt_float(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
-    let value = JkFloat::from_instance(&args[0].execute(ctx).unwrap()).0;
+    let value = JkFloat::from_instance(&args[0].execute(ctx).unwrap());
 
-    Some(JkString::from(value.to_string()).to_instance())
+    Some(JkString::from(value.format(ctx.float_format())).to_instance())
 }
 
 fn size_of(ctx: &mut Context, args: Args) -> Option<ObjectInstance> {
@@ -167,20 +278,9 @@ impl Builtins {
             functions: HashMap::new(),
         };
 
-        builtins.add("__builtin_string_len", string_len);
-        builtins.add("__builtin_string_concat", string_concat);
-        builtins.add("__builtin_string_display", string_display);
-        builtins.add("__builtin_string_display_err", string_display_err);
-        builtins.add("__builtin_string_is_empty", string_is_empty);
-        builtins.add("__builtin_string_equals", string_equals);
-        builtins.add("__builtin_ffi_link_with", ffi_link_with);
-        builtins.add("__builtin_fmt_char", fmt_char);
-        builtins.add("__builtin_fmt_float", fmt_float);
-        builtins.add("__builtin_arg_get", arg_get);
-        builtins.add("__builtin_arg_amount", arg_amount);
-        builtins.add("__builtin_exit", exit);
-        builtins.add("size_of", size_of);
-        builtins.add("type_of", type_of);
+        BUILTINS
+            .iter()
+            .for_each(|builtin| builtins.add(builtin.name, builtin.function));
 
         builtins
     }
@@ -199,6 +299,12 @@ impl Builtins {
     }
 }
 
+/// Is the function a builtin printer, which can be called on any primitive value
+/// without being declared
+pub fn is_primitive_printer(name: &str) -> bool {
+    PRIMITIVE_PRINTERS.contains(&name)
+}
+
 impl Default for Builtins {
     fn default() -> Self {
         Self::new()
@@ -207,7 +313,10 @@ impl Default for Builtins {
 
 #[cfg(test)]
 mod tests {
-    use crate::jinko;
+    use crate::context::Context;
+    use crate::error::ErrKind;
+    use crate::utils::SharedBuffer;
+    use crate::{jinko, jinko_fail};
 
     #[test]
     fn t_string_builtins_are_valid() {
@@ -232,8 +341,6 @@ mod tests {
     #[test]
     #[cfg(not(feature = "ffi"))]
     fn t_ffi_builtins_are_valid_no_ffi() {
-        use crate::jinko_fail;
-
         jinko_fail! {
             __builtin_ffi_link_with("tests/fixtures/clib/lib.so");
         };
@@ -247,6 +354,19 @@ mod tests {
         };
     }
 
+    #[test]
+    fn t_args_builtins_negative_index() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let input = "__builtin_arg_get(0 - 1);";
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+
+        assert!(ctx.execute().is_err());
+
+        let err = &ctx.error_handler.errors()[0];
+        assert_eq!(err.kind(), ErrKind::IndexOutOfBounds);
+        assert_eq!(err.msg(), Some("invalid argument index: `-1`"));
+    }
+
     #[test]
     fn t_exit_builtin_is_valid() {
         use libc::{c_int, fork, waitpid, WEXITSTATUS};
@@ -269,6 +389,67 @@ mod tests {
         assert_eq!(WEXITSTATUS(status), 42);
     }
 
+    fn printed(code: &str) -> String {
+        let stdout = SharedBuffer::default();
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.init_stdlib().unwrap();
+        ctx.set_stdout(stdout.clone());
+        ctx.eval(code).unwrap();
+
+        stdout.contents()
+    }
+
+    #[test]
+    fn t_print_builtins() {
+        assert_eq!(printed(r#"println("hi")"#), "hi\n");
+        assert_eq!(printed("print(42)"), "42");
+        assert_eq!(
+            printed("print(true); print('c'); println(1.5)"),
+            "truec1.5\n"
+        );
+        assert_eq!(printed("n = 15; n.println()"), "15\n");
+    }
+
+    #[test]
+    fn t_print_float_format() {
+        use crate::value::FloatFormat;
+
+        assert_eq!(printed("print(1.0)"), "1.0");
+
+        let stdout = SharedBuffer::default();
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.init_stdlib().unwrap();
+        ctx.set_stdout(stdout.clone());
+        ctx.set_float_format(FloatFormat::Fixed(2));
+        ctx.eval("print(1.0)").unwrap();
+
+        assert_eq!(stdout.contents(), "1.00");
+    }
+
+    #[test]
+    fn t_print_builtins_invalid() {
+        jinko_fail! {
+            type Point(x: int, y: int);
+            print(Point(x: 1, y: 2));
+        };
+
+        jinko_fail! {
+            println(1, 2);
+        };
+    }
+
+    #[test]
+    fn t_print_runtime_error() {
+        let stdout = SharedBuffer::default();
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_stdout(stdout.clone());
+
+        assert!(ctx.eval("print(1 / 0)").is_err());
+        assert!(stdout.contents().is_empty());
+    }
+
     #[test]
     fn t_fmt_builtins_are_valid() {
         jinko! {
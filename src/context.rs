@@ -7,30 +7,61 @@
 use std::path::{Path, PathBuf};
 
 use colored::Colorize;
+use symbol::Symbol;
 
+mod include_ctx;
 mod scope_map;
+pub use include_ctx::{IncludeCtx, DEFAULT_MAX_INCLUDE_DEPTH};
 pub use scope_map::{Scope, ScopeMap};
 
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::Write;
 use std::rc::Rc;
 
 use crate::builtins::Builtins;
 use crate::error::{ErrKind, Error, ErrorHandler};
-use crate::instance::ObjectInstance;
-use crate::instruction::{Block, FunctionDec, FunctionKind, Instruction, TypeDec, Var};
+use crate::instance::{ObjectInstance, ToObjectInstance};
+use crate::instruction::{Block, DecArg, FunctionDec, FunctionKind, Instruction, TypeDec, Var};
 use crate::io_trait::JkReader;
 use crate::location::Source;
 use crate::parser;
 use crate::typechecker::CheckedType;
-use crate::typechecker::{SpecializedNode, TypeCheck, TypeCtx, TypeId};
+use crate::typechecker::{SpecializedNode, TypeCtx, TypeId};
+use crate::value::{ArithmeticMode, FloatFormat};
 
 /// Type the context uses for keys
 type CtxKey = String;
 
+/// Callback invoked when a `@breakpoint()` directive is executed
+pub type BreakpointCallback = Box<dyn FnMut(&Context)>;
+
+/// Function implemented by the host program, callable from jinko code. It receives
+/// the values of the arguments given to the call, in the order they were declared
+pub type NativeFunction = Box<dyn Fn(&[ObjectInstance]) -> Result<ObjectInstance, Error>>;
+
 /// Name of the entry point in jinko
 const ENTRY_NAME: &str = "__entry";
 
+/// jinko code loaded into every context before any user code
+const PRELUDE: &str = include_str!("../stdlib/prelude.jk");
+
+/// Default maximum amount of nested function calls
+pub const DEFAULT_MAX_RECURSION_DEPTH: usize = 1000;
+
+/// Amount of spaces the debug output is indented by for each evaluation level
+const DEBUG_INDENT_WIDTH: usize = 2;
+
+/// State of the declarations of a [`Context`], taken by [`Context::snapshot`] and
+/// given back to [`Context::restore`]
+pub struct ScopeSnapshot {
+    scope_map: ScopeMap<Var, Rc<FunctionDec>, Rc<TypeDec>>,
+    types: ScopeMap<CheckedType, FunctionDec, TypeDec>,
+    tests: HashMap<CtxKey, FunctionDec>,
+    includes: IncludeCtx,
+}
+
 // FIXME: Rework visibility here
 /// A context represents the state of a jinko program. It contains functions,
 /// variables, tests... and can be optimized, typechecked, executed or
@@ -43,17 +74,26 @@ pub struct Context {
     args: Vec<String>,
     /// Contains the functions shipping with the interpreter
     builtins: Builtins,
+    /// Functions defined by the host program, along with their declaration
+    native_functions: HashMap<String, (FunctionDec, NativeFunction)>,
     /// Tests registered in the context
     tests: HashMap<CtxKey, FunctionDec>,
-    /// SourceOwneds included by the context
-    included: HashSet<PathBuf>,
+    /// Source files included by the context. This is shared with the typechecker,
+    /// which resolves includes
+    includes: Rc<RefCell<IncludeCtx>>,
     /// External libraries to use via FFI
     #[cfg(feature = "ffi")]
     external_libs: Vec<libloading::Library>,
     /// Contains the scopes of the context, in which are variables and functions
     pub(crate) scope_map: ScopeMap<Var, Rc<FunctionDec>, Rc<TypeDec>>,
+    /// Functions looked up so far. An entry is removed whenever the function visible
+    /// under its name might change: When a function of the same name is declared, or
+    /// when exiting the scope that declared it. This keeps shadowing correct
+    function_cache: RefCell<HashMap<Symbol, Rc<FunctionDec>>>,
     /// Various passes ran by the context
     pub(crate) typechecker: TypeCtx,
+    /// Are the functions of the prelude available in the context
+    prelude: bool,
     /// Is the context in debugging mode or not
     pub debug_mode: bool,
     /// SourceOwned code currently being interpreted by the context
@@ -62,6 +102,36 @@ pub struct Context {
     pub entry_point: FunctionDec,
     /// Errors being kept by the context
     pub error_handler: ErrorHandler,
+    /// Is the context currently unwinding out of a function because of a `return`
+    returning: bool,
+    /// Exit code requested by `@quit`, once it has been executed
+    quit_code: Option<i32>,
+    /// Amount of nested function calls currently being executed
+    recursion_depth: usize,
+    /// Maximum amount of nested function calls before erroring out
+    max_recursion_depth: usize,
+    /// Amount of loop iterations executed during the current execution
+    loop_iterations: usize,
+    /// Maximum amount of loop iterations per execution before erroring out, if any
+    max_loop_iterations: Option<usize>,
+    /// Depth of the current evaluation, used to indent the debug output
+    eval_depth: usize,
+    /// Behavior of integer operations which overflow
+    arithmetic_mode: ArithmeticMode,
+    /// Tolerance used when comparing floats for equality, if any
+    float_epsilon: Option<f64>,
+    /// How floats are displayed by the program
+    float_format: FloatFormat,
+    /// Function to call when reaching a breakpoint
+    breakpoint_callback: Option<BreakpointCallback>,
+    /// Value produced by the last statement of the latest execution
+    last_value: Option<ObjectInstance>,
+    /// Output used by directives, such as `@dump`
+    stdout: Box<dyn Write>,
+    /// Diagnostic output used by directives, such as `@ir`
+    stderr: Box<dyn Write>,
+    /// Output used by debug traces, when in debugging mode
+    debug_writer: Box<dyn Write>,
 }
 
 impl Context {
@@ -76,30 +146,140 @@ impl Context {
 
     /// Create a new empty context without the standard library
     pub fn new(reader: Box<dyn JkReader>) -> Context {
+        Context::with_capacity(reader, 0, 0, 0)
+    }
+
+    /// Create a new empty context without the standard library, whose global scope
+    /// has room for the given amount of variables, functions and types
+    pub fn with_capacity(
+        reader: Box<dyn JkReader>,
+        variables: usize,
+        functions: usize,
+        types: usize,
+    ) -> Context {
+        Context::create(reader, variables, functions, types, true)
+    }
+
+    /// Create a new empty context without the standard library nor the prelude.
+    /// Only the primitive types and the builtins are available
+    pub fn without_prelude(reader: Box<dyn JkReader>) -> Context {
+        Context::create(reader, 0, 0, 0, false)
+    }
+
+    fn create(
+        reader: Box<dyn JkReader>,
+        variables: usize,
+        functions: usize,
+        types: usize,
+        prelude: bool,
+    ) -> Context {
+        let includes = Rc::new(RefCell::new(IncludeCtx::default()));
+        let mut typechecker = TypeCtx::new(reader);
+        typechecker.set_includes(includes.clone());
+
         let mut ctx = Context {
             path: None,
             args: Vec::new(),
             builtins: Builtins::new(),
+            native_functions: HashMap::new(),
             tests: HashMap::new(),
-            included: HashSet::new(),
+            includes,
             #[cfg(feature = "ffi")]
             external_libs: Vec::new(),
-            scope_map: ScopeMap::new(),
-            typechecker: TypeCtx::new(reader),
+            scope_map: ScopeMap::with_capacity(variables, functions, types),
+            function_cache: RefCell::new(HashMap::new()),
+            typechecker,
+            prelude,
             debug_mode: false,
             code: None,
             entry_point: Self::new_entry(),
             error_handler: ErrorHandler::default(),
+            returning: false,
+            quit_code: None,
+            recursion_depth: 0,
+            max_recursion_depth: DEFAULT_MAX_RECURSION_DEPTH,
+            loop_iterations: 0,
+            max_loop_iterations: None,
+            eval_depth: 0,
+            arithmetic_mode: ArithmeticMode::default(),
+            float_epsilon: None,
+            float_format: FloatFormat::default(),
+            breakpoint_callback: None,
+            last_value: None,
+            stdout: Box::new(std::io::stdout()),
+            stderr: Box::new(std::io::stderr()),
+            debug_writer: Box::new(std::io::stderr()),
         };
 
-        ctx.scope_enter();
+        ctx.add_primitive_types();
+        if ctx.prelude {
+            ctx.load_prelude();
+        }
 
-        // Add all primitive types as empty types without fields
+        ctx
+    }
+
+    /// Add all primitive types as empty types without fields
+    fn add_primitive_types(&mut self) {
         crate::typechecker::PRIMITIVE_TYPES
             .iter()
-            .for_each(|ty_name| ctx.add_type(TypeDec::from(*ty_name)).unwrap());
+            .for_each(|ty_name| self.add_type(TypeDec::from(*ty_name)).unwrap());
+    }
 
-        ctx
+    /// Declare the functions defined in the prelude. Like builtins, they live in
+    /// their own outer scope, so that user code can declare functions with the same
+    /// names
+    fn load_prelude(&mut self) {
+        let entry_point = std::mem::replace(&mut self.entry_point, Self::new_entry());
+
+        parser::parse(self, PRELUDE, Source::Input(PRELUDE)).expect("invalid prelude");
+        self.execute().expect("invalid prelude");
+
+        // Executing the prelude already opened a new scope for its declarations in
+        // the scope map. Open another one in both, so that functions declared by the
+        // host program before running any code can shadow the prelude too
+        self.scope_map.scope_enter();
+        self.typechecker.scope_enter();
+
+        self.entry_point = entry_point;
+        self.code = None;
+        self.last_value = None;
+    }
+
+    /// Remove all the declarations, code and errors kept in the context so that it
+    /// can be reused to run another program. Primitive types, the prelude and the
+    /// native functions stay available, and the configuration of the context is kept
+    pub fn reset(&mut self) {
+        self.scope_map.clear();
+        self.function_cache.get_mut().clear();
+        self.add_primitive_types();
+        self.typechecker.reset();
+
+        self.tests.clear();
+        self.includes.borrow_mut().clear();
+        self.code = None;
+        self.entry_point = Self::new_entry();
+        self.error_handler.clear();
+        self.returning = false;
+        self.quit_code = None;
+        self.recursion_depth = 0;
+        self.loop_iterations = 0;
+        self.eval_depth = 0;
+        self.last_value = None;
+
+        if self.prelude {
+            self.load_prelude();
+        }
+
+        let natives: Vec<FunctionDec> = self
+            .native_functions
+            .values()
+            .map(|(dec, _)| dec.clone())
+            .collect();
+        natives.into_iter().for_each(|dec| {
+            // Native functions have unique names and are declared in a fresh scope
+            self.declare_native_function(dec).unwrap();
+        });
     }
 
     /// Get a reference to a context's source path
@@ -107,6 +287,11 @@ impl Context {
         self.path.as_ref()
     }
 
+    /// Set the maximum amount of nested includes allowed when including source files
+    pub fn set_max_include_depth(&mut self, depth: usize) {
+        self.includes.borrow_mut().set_max_depth(depth)
+    }
+
     /// Get a reference to the arguments to give to the program
     pub fn args(&self) -> &Vec<String> {
         &self.args
@@ -126,15 +311,11 @@ impl Context {
     pub fn set_path(&mut self, path: Option<PathBuf>) {
         // FIXME: Remove that clone...
         self.path.clone_from(&path);
-        self.typechecker.set_path(path);
+        self.includes.borrow_mut().set_path(path);
 
         // FIXME: Is that correct? Remove that clone()...
         self.error_handler
             .set_path(self.path.clone().unwrap_or_default());
-
-        if let Some(p) = &self.path {
-            self.included.insert(p.clone());
-        }
     }
 
     /// Add an error to the context
@@ -152,17 +333,122 @@ impl Context {
         self.error_handler.clear();
     }
 
+    /// Remove all the errors and warnings recorded so far, including the ones kept by
+    /// the type context, so that code can keep being evaluated after a failure. This
+    /// is useful for REPLs and long-running embedders. Variables, functions and types
+    /// are kept
+    pub fn reset_errors(&mut self) {
+        self.clear_errors();
+        self.typechecker.reset_errors();
+    }
+
     /// Add a function to the context. Returns `Ok` if the function was added, `Err`
     /// if it existed already and was not.
     pub fn add_function(&mut self, function: FunctionDec) -> Result<(), Error> {
-        self.scope_map
-            .add_function(function.name().to_owned(), Rc::new(function))
+        let name = self.intern(function.name());
+
+        // The new function might shadow one which was looked up previously
+        self.function_cache.get_mut().remove(&name);
+
+        self.scope_map.add_function(name, Rc::new(function))
     }
 
     /// Add a variable to the context. Returns `Ok` if the variable was added, `Err`
     /// if it existed already and was not.
     pub fn add_variable(&mut self, var: Var) -> Result<(), Error> {
-        self.scope_map.add_variable(var.name().to_owned(), var)
+        self.scope_map.add_variable(var.symbol().clone(), var)
+    }
+
+    /// Define a constant, readable from jinko code but which cannot be assigned to.
+    /// Returns `Ok` if the constant was added, `Err` if a variable with the same
+    /// name existed already.
+    pub fn define_constant<T: ToObjectInstance>(
+        &mut self,
+        name: &str,
+        value: T,
+    ) -> Result<(), Error> {
+        let instance = value.to_instance();
+
+        // The constant must be registered in both the context and the typechecker, or
+        // in neither of them: Check that the typechecker accepts it before adding it
+        if self.typechecker.is_declared_in_scope(name) {
+            return Err(Error::new(ErrKind::Context)
+                .with_msg(format!("variable `{name}` is already declared")));
+        }
+
+        let mut var = Var::new(name.to_owned());
+        var.set_instance(instance.clone());
+
+        self.add_variable(var)?;
+        self.typechecker.declare_constant(name.to_owned(), instance)
+    }
+
+    /// Define a function implemented by the host program, taking arguments of the
+    /// types `args` and returning a value of type `return_ty`. Calls to the
+    /// function are typechecked like calls to any other function. Like functions
+    /// declared in jinko code, native functions can shadow the ones of the prelude.
+    /// They are kept when the context is [reset](Context::reset). Returns `Ok` if
+    /// the function was added, `Err` if a function with the same name existed
+    /// already.
+    pub fn define_native_function(
+        &mut self,
+        name: &str,
+        args: Vec<TypeId>,
+        return_ty: TypeId,
+        function: NativeFunction,
+    ) -> Result<(), Error> {
+        let args = args
+            .into_iter()
+            .enumerate()
+            .map(|(i, ty)| DecArg::new(format!("arg{i}"), ty))
+            .collect();
+
+        let mut dec = FunctionDec::new(name.to_owned(), Some(return_ty), vec![], args);
+        dec.set_kind(FunctionKind::Ext);
+
+        self.declare_native_function(dec.clone())?;
+        self.native_functions
+            .insert(name.to_owned(), (dec, function));
+
+        Ok(())
+    }
+
+    /// Declare a native function in both the context and the typechecker
+    fn declare_native_function(&mut self, dec: FunctionDec) -> Result<(), Error> {
+        let name = dec.name().to_owned();
+
+        // The function must be registered in both the context and the typechecker, or
+        // in neither of them: Check that the context accepts it before declaring it
+        let defined = self
+            .scope_map
+            .scopes()
+            .front()
+            .is_some_and(|scope| scope.functions.contains_key(&self.intern(&name)));
+        if defined {
+            return Err(Error::new(ErrKind::Context)
+                .with_msg(format!("function `{name}` is already defined")));
+        }
+
+        self.typechecker.declare_function(name, dec.clone())?;
+        self.add_function(dec)
+    }
+
+    /// Is `name` a function defined by the host program
+    pub fn is_native(&self, name: &str) -> bool {
+        self.native_functions.contains_key(name)
+    }
+
+    /// Call a function defined by the host program with the given arguments
+    pub fn call_native(
+        &mut self,
+        name: &str,
+        args: &[ObjectInstance],
+    ) -> Result<ObjectInstance, Error> {
+        match self.native_functions.get(name) {
+            Some((_, f)) => f(args),
+            None => Err(Error::new(ErrKind::Context)
+                .with_msg(format!("cannot find native function `{name}`"))),
+        }
     }
 
     /// Add a type to the context. Returns `Ok` if the type was added, `Err`
@@ -174,37 +460,295 @@ impl Context {
 
     /// Replace a variable or create it if it does not exist
     pub fn replace_variable(&mut self, var: Var) -> Result<(), Error> {
-        match self.scope_map.get_variable_mut(var.name()) {
+        match self.scope_map.get_variable_mut(var.symbol()) {
             None => self.add_variable(var)?,
-            Some(var_ref) => var_ref.set_instance(var.instance()),
+            Some(var_ref) => var_ref.set_shared_instance(var.shared_instance()),
         }
 
         Ok(())
     }
 
-    /// Get a mutable reference on an existing function
-    pub fn get_function(&self, name: &str) -> Option<&Rc<FunctionDec>> {
-        self.scope_map.get_function(name)
+    /// Get an existing function. Lookups are cached until the function visible under
+    /// that name changes
+    pub fn get_function(&self, name: &Symbol) -> Option<Rc<FunctionDec>> {
+        if let Some(function) = self.function_cache.borrow().get(name) {
+            return Some(function.clone());
+        }
+
+        let function = self.scope_map.get_function(name)?.clone();
+        self.function_cache
+            .borrow_mut()
+            .insert(name.clone(), function.clone());
+
+        Some(function)
+    }
+
+    /// Intern a name, returning a [`Symbol`] which is cheap to clone and compare.
+    /// Interning the same name multiple times always yields the same symbol. Names
+    /// are interned in the process-global interner of the `symbol` crate, so symbols
+    /// can be shared between contexts
+    pub fn intern(&self, name: &str) -> Symbol {
+        Symbol::from(name)
     }
 
     /// Get a reference on an existing variable
-    pub fn get_variable(&self, name: &str) -> Option<&Var> {
+    pub fn get_variable(&self, name: &Symbol) -> Option<&Var> {
         self.scope_map.get_variable(name)
     }
 
+    /// Get a mutable reference on an existing variable
+    pub fn get_variable_mut(&mut self, name: &Symbol) -> Option<&mut Var> {
+        self.scope_map.get_variable_mut(name)
+    }
+
+    /// Get a snapshot of all the currently visible variables and their values,
+    /// sorted by name. Variables shadowed by a variable of the same name in an inner
+    /// scope are not part of the snapshot
+    pub fn variables(&self) -> Vec<(String, ObjectInstance)> {
+        let mut seen = HashSet::new();
+        let mut variables: Vec<(String, ObjectInstance)> = self
+            .scope_map
+            .scopes()
+            .iter()
+            .flat_map(|scope| scope.variables.iter())
+            .filter(|(name, _)| seen.insert(name.access()))
+            .map(|(name, var)| (name.access().to_owned(), var.instance()))
+            .collect();
+
+        variables.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+        variables
+    }
+
     /// Get a reference on an existing type
     pub fn get_type(&self, type_id: &TypeId) -> Option<&Rc<TypeDec>> {
         self.scope_map.get_type(type_id.id())
     }
 
+    /// Signal that a `return` instruction was executed, and that the instructions
+    /// of the enclosing function should stop executing
+    pub fn signal_return(&mut self) {
+        self.returning = true
+    }
+
+    /// Value produced by the last statement of the latest execution, if any. A
+    /// statement which does not produce a value, such as a variable assignment,
+    /// resets it
+    pub fn last_value(&self) -> Option<&ObjectInstance> {
+        self.last_value.as_ref()
+    }
+
+    /// Is the context unwinding out of a function because of a `return`, or out of
+    /// the whole program because of `@quit`
+    pub fn is_returning(&self) -> bool {
+        self.returning || self.quit_code.is_some()
+    }
+
+    /// Stop the execution of the program, which should exit with the given code. The
+    /// context does not exit the process itself: It is up to the code running it to
+    /// check for a [`Context::quit_code`]
+    pub fn quit(&mut self, code: i32) {
+        self.quit_code = Some(code)
+    }
+
+    /// Exit code requested by the program, if it was stopped using `@quit`
+    pub fn quit_code(&self) -> Option<i32> {
+        self.quit_code
+    }
+
+    /// Stop unwinding, once the function containing the `return` has been exited
+    pub fn clear_return(&mut self) {
+        self.returning = false
+    }
+
+    /// Redirect the output used by directives. This defaults to the standard output
+    pub fn set_stdout(&mut self, stdout: impl Write + 'static) {
+        self.stdout = Box::new(stdout)
+    }
+
+    /// Redirect the diagnostic output used by directives. This defaults to the
+    /// standard error
+    pub fn set_stderr(&mut self, stderr: impl Write + 'static) {
+        self.stderr = Box::new(stderr)
+    }
+
+    /// Redirect the output of debug traces. This defaults to the standard error
+    pub fn set_debug_writer(&mut self, writer: impl Write + 'static) {
+        self.debug_writer = Box::new(writer)
+    }
+
+    /// Output used by directives
+    pub fn stdout(&mut self) -> &mut dyn Write {
+        &mut *self.stdout
+    }
+
+    /// Diagnostic output used by directives
+    pub fn stderr(&mut self) -> &mut dyn Write {
+        &mut *self.stderr
+    }
+
+    /// Enable or disable strict mode. In strict mode, expressions used as statements
+    /// whose values are not used are rejected by the typechecker
+    pub fn set_strict(&mut self, strict: bool) {
+        self.typechecker.set_strict(strict)
+    }
+
+    /// Set the maximum amount of nested function calls allowed during execution
+    pub fn set_max_recursion_depth(&mut self, depth: usize) {
+        self.max_recursion_depth = depth
+    }
+
+    /// Set the maximum amount of loop iterations allowed during an execution, across
+    /// all loops. This protects embedders running untrusted code from programs which
+    /// never terminate. Loops are unlimited by default
+    pub fn set_max_loop_iterations(&mut self, iterations: usize) {
+        self.max_loop_iterations = Some(iterations)
+    }
+
+    /// Set how integer operations which overflow should behave. Overflows are
+    /// errors by default
+    pub fn set_arithmetic_mode(&mut self, mode: ArithmeticMode) {
+        self.arithmetic_mode = mode
+    }
+
+    /// How integer operations which overflow behave
+    pub fn arithmetic_mode(&self) -> ArithmeticMode {
+        self.arithmetic_mode
+    }
+
+    /// Make `==` and `!=` consider two floats equal if they are at most `epsilon`
+    /// apart. Ordering operators such as `<` are not affected. Floats are compared
+    /// exactly by default
+    pub fn set_float_epsilon(&mut self, epsilon: f64) {
+        self.float_epsilon = Some(epsilon)
+    }
+
+    /// Tolerance used when comparing floats for equality, if any
+    pub fn float_epsilon(&self) -> Option<f64> {
+        self.float_epsilon
+    }
+
+    /// Set how floats are displayed and formatted by the program. Floats use their
+    /// shortest round-trippable representation by default
+    pub fn set_float_format(&mut self, format: FloatFormat) {
+        self.float_format = format
+    }
+
+    /// How floats are displayed and formatted by the program
+    pub fn float_format(&self) -> FloatFormat {
+        self.float_format
+    }
+
+    /// Count one iteration of a loop. Returns an error if the maximum amount of loop
+    /// iterations has been reached
+    pub fn loop_iteration(&mut self) -> Result<(), Error> {
+        if let Some(max) = self.max_loop_iterations {
+            if self.loop_iterations >= max {
+                return Err(Error::new(ErrKind::Context).with_msg(format!(
+                    "loop iteration limit exceeded: maximum amount of iterations is {max}"
+                )));
+            }
+        }
+
+        self.loop_iterations += 1;
+
+        Ok(())
+    }
+
+    /// Enter a function call. Returns an error if the maximum recursion depth
+    /// has been reached
+    pub fn enter_call(&mut self) -> Result<(), Error> {
+        if self.recursion_depth >= self.max_recursion_depth {
+            return Err(Error::new(ErrKind::Context).with_msg(format!(
+                "recursion limit exceeded: maximum depth is {}",
+                self.max_recursion_depth
+            )));
+        }
+
+        self.recursion_depth += 1;
+
+        Ok(())
+    }
+
+    /// Exit the function call that was entered last
+    pub fn exit_call(&mut self) {
+        self.recursion_depth -= 1
+    }
+
+    /// Install a callback to invoke with the current state of the context each time a
+    /// `@breakpoint()` directive is executed
+    pub fn set_breakpoint_callback(&mut self, callback: impl FnMut(&Context) + 'static) {
+        self.breakpoint_callback = Some(Box::new(callback))
+    }
+
+    /// Invoke the breakpoint callback, if one is installed
+    pub fn breakpoint(&mut self) {
+        // Take the callback out of the context so that it can borrow the context
+        if let Some(mut callback) = self.breakpoint_callback.take() {
+            callback(self);
+            self.breakpoint_callback = Some(callback);
+        }
+    }
+
+    /// Save the variables, functions, types and includes currently known to the
+    /// context, both at runtime and for the typechecker. This allows evaluating code
+    /// speculatively, and going back to the saved state using [`Context::restore`]
+    /// if it fails
+    pub fn snapshot(&self) -> ScopeSnapshot {
+        ScopeSnapshot {
+            scope_map: self.scope_map.clone(),
+            types: self.typechecker.scopes().clone(),
+            tests: self.tests.clone(),
+            includes: self.includes.borrow().clone(),
+        }
+    }
+
+    /// Go back to the state saved by [`Context::snapshot`], forgetting about all the
+    /// declarations performed since then. Errors are not affected
+    pub fn restore(&mut self, snapshot: ScopeSnapshot) {
+        self.scope_map = snapshot.scope_map;
+        self.function_cache.get_mut().clear();
+        self.typechecker.set_scopes(snapshot.types);
+        self.tests = snapshot.tests;
+        *self.includes.borrow_mut() = snapshot.includes;
+    }
+
     /// Create a new empty scope
     pub fn scope_enter(&mut self) {
+        self.eval_enter();
         self.scope_map.scope_enter()
     }
 
     /// Exit the latest created scope
     pub fn scope_exit(&mut self) {
-        self.scope_map.scope_exit()
+        // The functions declared in the scope we are exiting are not visible anymore
+        if let Some(scope) = self.scope_map.scopes().front() {
+            let cache = self.function_cache.get_mut();
+            scope.functions.keys().for_each(|name| {
+                cache.remove(name);
+            });
+        }
+
+        self.scope_map.scope_exit();
+        self.eval_exit();
+    }
+
+    /// Go one level deeper in the evaluation of the program. This happens when
+    /// entering a block or a function, as well as when evaluating the operands of an
+    /// instruction. The debug output is indented according to the evaluation depth,
+    /// so that traces read as a tree
+    pub fn eval_enter(&mut self) {
+        self.eval_depth += 1;
+    }
+
+    /// Go back to the previous evaluation depth
+    pub fn eval_exit(&mut self) {
+        self.eval_depth = self.eval_depth.saturating_sub(1);
+    }
+
+    /// Indentation of the debug output at the current evaluation depth
+    fn debug_indent(&self) -> String {
+        " ".repeat(DEBUG_INDENT_WIDTH * self.eval_depth)
     }
 
     /// Pretty-prints valid jinko code from a given ctx
@@ -219,9 +763,16 @@ impl Context {
     /// following format:
     ///
     /// `<specifier>: <msg>`
-    pub fn debug(&self, specifier: &str, msg: &str) {
+    pub fn debug(&mut self, specifier: &str, msg: &str) {
         if self.debug_mode {
-            println!("{}: {}", specifier.purple(), msg);
+            let indent = self.debug_indent();
+            let _ = writeln!(
+                self.debug_writer,
+                "{}{}: {}",
+                indent,
+                specifier.purple(),
+                msg
+            );
         }
     }
 
@@ -234,9 +785,10 @@ impl Context {
     /// secondary format is not necesarry. For example, when entering a block: There's
     /// no way to name a block, so no necessity to have more information other than
     /// "ENTER_BLOCK"
-    pub fn debug_step(&self, specifier: &str) {
+    pub fn debug_step(&mut self, specifier: &str) {
         if self.debug_mode {
-            println!("{}", specifier.yellow());
+            let indent = self.debug_indent();
+            let _ = writeln!(self.debug_writer, "{}{}", indent, specifier.yellow());
         }
     }
 
@@ -254,11 +806,7 @@ impl Context {
 
     /// Check if a source is included or not
     pub fn is_included(&self, source: &Path) -> bool {
-        self.included.contains(source)
-    }
-
-    pub fn remove_included(&mut self, source: &Path) {
-        self.included.remove(source);
+        self.includes.borrow().is_included(source)
     }
 
     pub fn type_check(&mut self, instruction: &mut dyn Instruction) -> Result<CheckedType, Error> {
@@ -273,14 +821,26 @@ impl Context {
         }
     }
 
-    fn inner_check(&mut self, ep: &mut Block) -> Result<(), Error> {
-        self.scope_enter();
+    /// Typecheck the entry point's block, keeping all the errors found in the
+    /// context without emitting them
+    fn resolve_entry_point(&mut self, ep: &mut Block) -> Result<(), Error> {
+        // The global scope is never exited, so it does not count as an evaluation level
+        self.scope_map.scope_enter();
+
+        // The entry point's declarations must stay available to later evaluations, so
+        // its instructions are typechecked in the global scope
+        if let Err(e) = ep.resolve_statements_type(&mut self.typechecker) {
+            self.typechecker.error(e);
+        }
+        if let Some(path) = &self.path {
+            self.typechecker.report_unused_functions(path);
+        }
 
-        ep.type_of(&mut self.typechecker)?;
+        let output = self.typechecker.take_output();
+        let _ = write!(self.stdout, "{output}");
 
         self.error_handler
             .append(&mut self.typechecker.error_handler);
-        self.emit_errors();
 
         let new_nodes = self.typechecker.take_specialized_nodes();
         new_nodes.into_iter().for_each(|node| {
@@ -298,10 +858,27 @@ impl Context {
         }
     }
 
-    pub fn check(&mut self) -> Result<(), Error> {
+    fn inner_check(&mut self, ep: &mut Block) -> Result<(), Error> {
+        let res = self.resolve_entry_point(ep);
+        self.emit_errors();
+
+        res
+    }
+
+    /// Typecheck the program without executing it, like [`Context::check`], but
+    /// without emitting the errors and warnings found. They are kept in the context's
+    /// error handler instead
+    pub(crate) fn check_quietly(&mut self) -> Result<(), Error> {
         // The entry point always has a block
         let mut ep = self.entry_point.block().unwrap().clone();
-        self.inner_check(&mut ep)
+        self.resolve_entry_point(&mut ep)
+    }
+
+    pub fn check(&mut self) -> Result<(), Error> {
+        let res = self.check_quietly();
+        self.emit_errors();
+
+        res
     }
 
     pub fn execute(&mut self) -> Result<Option<ObjectInstance>, Error> {
@@ -309,14 +886,19 @@ impl Context {
         let mut ep = self.entry_point.block().unwrap().clone();
         self.inner_check(&mut ep)?;
 
-        let res = ep
-            .instructions()
-            .iter()
-            .map(|inst| inst.execute(self))
-            .last()
-            .flatten();
+        // The loop iteration budget applies to each execution separately, and a
+        // previous `@quit` does not stop the next ones
+        self.loop_iterations = 0;
+        self.quit_code = None;
+
+        // A program ending with a statement has no value
+        let res = ep.execute_statements(self).filter(|value| !value.is_void());
+
+        // A `return` at the top level stops the execution of the program
+        self.clear_return();
 
         self.emit_errors();
+        self.last_value = res.clone();
 
         match self.error_handler.has_errors() {
             true => Err(Error::new(ErrKind::Context)),
@@ -332,6 +914,31 @@ impl Context {
         self.execute()
     }
 
+    /// Run a single line of code, such as one entered in a REPL, on top of everything
+    /// that was declared by previous lines. If the line fails to parse, typecheck or
+    /// execute, the errors it caused are returned and removed from the context, and
+    /// none of its declarations are kept, so that the next line can run normally
+    pub fn run_line(&mut self, line: &str) -> Result<Option<ObjectInstance>, Vec<Error>> {
+        let snapshot = self.snapshot();
+
+        self.set_code(line.to_string());
+
+        match self.eval(line) {
+            Ok(value) => Ok(value),
+            Err(e) => {
+                let mut errors = self.error_handler.errors().to_vec();
+                if errors.is_empty() {
+                    errors.push(e);
+                }
+
+                self.reset_errors();
+                self.restore(snapshot);
+
+                Err(errors)
+            }
+        }
+    }
+
     pub fn has_errors(&self) -> bool {
         self.error_handler.has_errors()
     }
@@ -471,6 +1078,334 @@ mod tests {
         };
     }
 
+    #[test]
+    fn t_define_constant() {
+        use crate::instance::FromObjectInstance;
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.define_constant("ANSWER", JkInt::from(42)).unwrap();
+
+        let res = ctx.eval("ANSWER + 1").unwrap().unwrap();
+        assert_eq!(JkInt::from_instance(&res).rust_value(), 43);
+    }
+
+    #[test]
+    fn t_failed_define_constant_leaves_no_binding() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        // `ANSWER` is declared in the typechecker, but execution stops before it is
+        // assigned
+        assert!(ctx.eval("a = 1 / 0; ANSWER = 2").is_err());
+
+        assert!(ctx.define_constant("ANSWER", JkInt::from(42)).is_err());
+        assert!(ctx.get_variable(&ctx.intern("ANSWER")).is_none());
+    }
+
+    fn define_host_add(ctx: &mut Context) {
+        use crate::instance::FromObjectInstance;
+        use crate::value::JkInt;
+
+        ctx.define_native_function(
+            "host_add",
+            vec![TypeId::from("int"), TypeId::from("int")],
+            TypeId::from("int"),
+            Box::new(|args| {
+                let lhs = JkInt::from_instance(&args[0]).rust_value();
+                let rhs = JkInt::from_instance(&args[1]).rust_value();
+
+                Ok(JkInt::from(lhs + rhs).to_instance())
+            }),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn t_define_native_function() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        define_host_add(&mut ctx);
+
+        assert_eq!(
+            ctx.eval("x = host_add(1, 2); host_add(x, 4)"),
+            Ok(Some(JkInt::from(7).to_instance()))
+        );
+    }
+
+    #[test]
+    fn tc_native_function_invalid_args() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        define_host_add(&mut ctx);
+
+        assert!(ctx.eval("host_add(1, 'a')").is_err());
+        assert_eq!(ctx.error_handler.errors()[0].kind(), ErrKind::TypeChecker);
+    }
+
+    #[test]
+    fn t_native_function_error() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.define_native_function(
+            "host_fail",
+            vec![],
+            TypeId::from("int"),
+            Box::new(|_| Err(Error::new(ErrKind::Context).with_msg(String::from("host error")))),
+        )
+        .unwrap();
+
+        assert!(ctx.eval("host_fail()").is_err());
+        assert_eq!(ctx.error_handler.errors()[0].msg(), Some("host error"));
+    }
+
+    #[test]
+    fn t_redefine_native_function() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        define_host_add(&mut ctx);
+
+        assert!(ctx
+            .define_native_function(
+                "host_add",
+                vec![],
+                TypeId::from("int"),
+                Box::new(|_| { Err(Error::new(ErrKind::Context)) })
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn t_native_function_shadows_prelude() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.define_native_function(
+            "max",
+            vec![],
+            TypeId::from("int"),
+            Box::new(|_| Ok(JkInt::from(0).to_instance())),
+        )
+        .unwrap();
+
+        assert_eq!(ctx.eval("max()"), Ok(Some(JkInt::from(0).to_instance())));
+    }
+
+    #[test]
+    fn t_native_function_conflicting_with_code_is_not_registered() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.eval("func host() -> int { 1 }").unwrap();
+
+        assert!(ctx
+            .define_native_function(
+                "host",
+                vec![],
+                TypeId::from("int"),
+                Box::new(|_| Ok(JkInt::from(0).to_instance()))
+            )
+            .is_err());
+
+        // Neither the typechecker nor the context know about the native function
+        assert!(!ctx.is_native("host"));
+        assert_eq!(ctx.eval("host()"), Ok(Some(JkInt::from(1).to_instance())));
+    }
+
+    #[test]
+    fn t_reset_keeps_native_functions() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        define_host_add(&mut ctx);
+
+        ctx.reset();
+        ctx.reset();
+
+        assert!(ctx.is_native("host_add"));
+        assert_eq!(
+            ctx.eval("host_add(1, 2)"),
+            Ok(Some(JkInt::from(3).to_instance()))
+        );
+    }
+
+    #[test]
+    fn t_native_function_wrong_return_type() {
+        use crate::value::JkString;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.define_native_function(
+            "host_lie",
+            vec![],
+            TypeId::from("int"),
+            Box::new(|_| Ok(JkString::from("not an int").to_instance())),
+        )
+        .unwrap();
+
+        assert!(ctx.eval("host_lie()").is_err());
+
+        let err = &ctx.error_handler.errors()[0];
+        assert_eq!(err.kind(), ErrKind::TypeChecker);
+        assert_eq!(
+            err.msg(),
+            Some("native function `host_lie` returned a value of type `string`, expected `int`")
+        );
+    }
+
+    #[test]
+    fn t_assign_to_constant() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.define_constant("ANSWER", JkInt::from(42)).unwrap();
+
+        assert!(ctx.eval("ANSWER = 15").is_err());
+        assert!(ctx.has_errors());
+    }
+
+    #[test]
+    fn t_variables_snapshot() {
+        use crate::value::JkInt;
+
+        let ctx = jinko! {
+            a = 1;
+            b = 2;
+        };
+
+        let variables = ctx.variables();
+
+        assert!(variables.contains(&(String::from("a"), JkInt::from(1).to_instance())));
+        assert!(variables.contains(&(String::from("b"), JkInt::from(2).to_instance())));
+    }
+
+    #[test]
+    fn t_last_value() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        assert!(ctx.last_value().is_none());
+
+        ctx.eval("6 * 7").unwrap();
+        assert_eq!(ctx.last_value(), Some(&JkInt::from(42).to_instance()));
+
+        ctx.eval("a = 1").unwrap();
+        assert!(ctx.last_value().is_none());
+    }
+
+    #[test]
+    fn t_empty_program() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert_eq!(ctx.eval(""), Ok(None));
+        assert_eq!(ctx.eval("   \n\t\n"), Ok(None));
+        assert_eq!(ctx.eval("// just a comment"), Ok(None));
+        assert_eq!(
+            ctx.eval("# another comment\n/* and a block comment */"),
+            Ok(None)
+        );
+        assert!(ctx.last_value().is_none());
+    }
+
+    #[test]
+    fn t_intern() {
+        let ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert_eq!(ctx.intern("name"), ctx.intern("name"));
+        assert_ne!(ctx.intern("name"), ctx.intern("other_name"));
+    }
+
+    #[test]
+    fn t_interned_lookups() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        ctx.eval("mut total = 0; for i in 0..4 { total = total + i }")
+            .unwrap();
+
+        let total = ctx.get_variable(&ctx.intern("total")).unwrap();
+        assert_eq!(total.instance(), JkInt::from(6).to_instance());
+        assert!(ctx.get_variable(&ctx.intern("i")).is_none());
+    }
+
+    #[test]
+    fn t_interned_function_lookups() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let other = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        ctx.eval("func twice(x: int) -> int { x * 2 } twice(2)")
+            .unwrap();
+
+        // Symbols are interned globally, so a symbol interned by another context
+        // finds the same function
+        let function = ctx.get_function(&other.intern("twice")).unwrap();
+        assert_eq!(function.name(), "twice");
+        assert!(ctx.get_function(&ctx.intern("thrice")).is_none());
+    }
+
+    #[test]
+    fn t_reset() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::with_capacity(Box::new(crate::io_trait::JkStdReader), 16, 16, 4);
+
+        ctx.eval("a = 15; func f(x: int) -> int { x }").unwrap();
+        assert!(ctx.get_variable(&"a".into()).is_some());
+
+        ctx.reset();
+
+        assert!(ctx.get_variable(&"a".into()).is_none());
+        assert!(ctx.get_function(&ctx.intern("f")).is_none());
+        assert!(ctx.eval("a").is_err());
+
+        ctx.reset();
+
+        let input = "func f(i: int, f: float, b: bool, c: char, s: string) -> int { i }
+            f(1, 2.0, true, 'c', \"s\")";
+        assert_eq!(ctx.eval(input), Ok(Some(JkInt::from(1).to_instance())));
+    }
+
+    #[test]
+    fn t_prelude_available_without_include() {
+        use crate::value::{JkBool, JkInt};
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        let input = "max(4, abs(0 - 12)) + modulo(7, 4)";
+        assert_eq!(ctx.eval(input), Ok(Some(JkInt::from(15).to_instance())));
+
+        ctx.reset();
+        assert_eq!(
+            ctx.eval("not(true)"),
+            Ok(Some(JkBool::from(false).to_instance()))
+        );
+    }
+
+    #[test]
+    fn t_prelude_functions_can_be_shadowed() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        let input = "func max(values: int) -> int { values * 2 } max(4)";
+        assert_eq!(ctx.eval(input), Ok(Some(JkInt::from(8).to_instance())));
+        assert_eq!(
+            ctx.eval("abs(0 - 3)"),
+            Ok(Some(JkInt::from(3).to_instance()))
+        );
+    }
+
+    #[test]
+    fn t_without_prelude() {
+        let mut ctx = Context::without_prelude(Box::new(crate::io_trait::JkStdReader));
+
+        assert!(ctx.eval("abs(0 - 3)").is_err());
+
+        ctx.reset();
+        ctx.clear_errors();
+        assert!(ctx
+            .eval("func abs(value: int) -> int { value } abs(3)")
+            .is_ok());
+    }
+
     #[test]
     fn t_eval() {
         let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
@@ -497,4 +1432,151 @@ mod tests {
         assert!(output.contains("my_var"));
         assert!(output.contains("my_new_var"));
     }
+
+    #[test]
+    fn t_restore_after_failed_eval() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.eval("x = 15").unwrap();
+
+        let snapshot = ctx.snapshot();
+        assert!(ctx
+            .eval("y = 2; func f() -> int { y } z = y + 'c'")
+            .is_err());
+
+        ctx.restore(snapshot);
+        ctx.clear_errors();
+
+        // `y` and `f` were forgotten, so they can be declared again
+        assert_eq!(
+            ctx.eval("y = x + 1; func f() -> int { y } f()"),
+            Ok(Some(JkInt::from(16).to_instance()))
+        );
+        assert_eq!(
+            ctx.get_variable(&"x".into()).unwrap().instance(),
+            JkInt::from(15).to_instance()
+        );
+    }
+
+    #[test]
+    fn t_restore_forgets_types() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let snapshot = ctx.snapshot();
+
+        ctx.eval("type Point(x: int, y: int)").unwrap();
+        ctx.restore(snapshot);
+
+        assert!(ctx.eval("p = Point(x: 1, y: 2)").is_err());
+    }
+
+    #[test]
+    fn t_restore_forgets_constants() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let snapshot = ctx.snapshot();
+
+        ctx.eval("const ANSWER = 42").unwrap();
+        ctx.restore(snapshot);
+
+        // `ANSWER` is not a constant anymore, so it can be declared as a variable
+        assert!(ctx.eval("ANSWER = 15").is_ok());
+    }
+
+    #[test]
+    fn t_run_line_session() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert_eq!(ctx.run_line("a = 15"), Ok(None));
+        assert_eq!(
+            ctx.run_line("func add(lhs: int, rhs: int) -> int { lhs + rhs } b = 4"),
+            Ok(None)
+        );
+        assert_eq!(
+            ctx.run_line("add(a, b)"),
+            Ok(Some(JkInt::from(19).to_instance()))
+        );
+    }
+
+    #[test]
+    fn t_run_line_failure_does_not_stick() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert_eq!(ctx.run_line("a = 15"), Ok(None));
+
+        let errs = ctx.run_line("b = a + 'c'").unwrap_err();
+        assert!(!errs.is_empty());
+        assert!(!ctx.has_errors());
+
+        assert!(ctx.run_line("b = (").is_err());
+
+        assert_eq!(
+            ctx.run_line("b = a + 1; b"),
+            Ok(Some(JkInt::from(16).to_instance()))
+        );
+    }
+
+    #[test]
+    fn t_reset_errors() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.eval("a = 15").unwrap();
+
+        assert!(ctx.eval("b = a + 'c'").is_err());
+        assert!(ctx.eval("func f() -> int { 'c' }").is_err());
+        assert!(ctx.has_errors());
+
+        ctx.reset_errors();
+        assert!(!ctx.has_errors());
+        assert!(ctx.error_handler.warnings().is_empty());
+
+        assert_eq!(
+            ctx.eval("c = a + 1; c"),
+            Ok(Some(JkInt::from(16).to_instance()))
+        );
+        assert!(!ctx.has_errors());
+    }
+
+    #[test]
+    fn t_function_cache_shadowing() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        let input = "func f() -> int { 1 }
+            mut sum = 0;
+            for i in 0..3 {
+                sum = sum + f();
+            }
+            {
+                func f() -> int { 10 };
+                sum = sum + f();
+                sum = sum + f();
+            }
+            {
+                sum = sum + f();
+            }
+            sum + f()";
+
+        assert_eq!(ctx.eval(input), Ok(Some(JkInt::from(25).to_instance())));
+    }
+
+    #[test]
+    fn t_function_cache_redeclaration_after_reset() {
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        ctx.eval("func f() -> int { 1 } f()").unwrap();
+        ctx.reset();
+
+        assert_eq!(
+            ctx.eval("func f() -> int { 2 } f()"),
+            Ok(Some(JkInt::from(2).to_instance()))
+        );
+    }
 }
@@ -0,0 +1,207 @@
+//! The `IncludeCtx` keeps track of the source files included by a program. It knows
+//! which file is currently being processed, so that includes are resolved relative to
+//! the including file, and makes sure each file only gets included once.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::error::{ErrKind, Error};
+
+/// Default maximum amount of nested includes
+pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 64;
+
+#[derive(Clone, Debug)]
+pub struct IncludeCtx {
+    /// Path of the main source file, if any
+    main: Option<PathBuf>,
+    /// Source files currently being included, from the outermost to the innermost one
+    stack: Vec<PathBuf>,
+    /// Source files which have been fully included
+    included: HashSet<PathBuf>,
+    /// Maximum amount of nested includes before erroring out
+    max_depth: usize,
+}
+
+impl Default for IncludeCtx {
+    fn default() -> IncludeCtx {
+        IncludeCtx {
+            main: None,
+            stack: vec![],
+            included: HashSet::new(),
+            max_depth: DEFAULT_MAX_INCLUDE_DEPTH,
+        }
+    }
+}
+
+impl IncludeCtx {
+    /// Path of the source file currently being processed, if any
+    pub fn path(&self) -> Option<&PathBuf> {
+        self.stack.last().or(self.main.as_ref())
+    }
+
+    /// Set the path of the main source file. The path is canonicalized if possible,
+    /// so that the main file cannot be included again through a different path
+    pub fn set_path(&mut self, path: Option<PathBuf>) {
+        self.main = path.map(|p| p.canonicalize().unwrap_or(p));
+    }
+
+    /// Directory in which includes should be looked up: The directory of the source
+    /// file currently being processed, or the current directory if there is none
+    pub fn base_dir(&self) -> PathBuf {
+        self.path()
+            .and_then(|path| path.parent())
+            .map_or_else(PathBuf::new, Path::to_owned)
+    }
+
+    /// Has the given source file already been fully included
+    pub fn is_included(&self, path: &Path) -> bool {
+        self.included.contains(path)
+    }
+
+    /// Chain of source files currently being processed, starting with the main one
+    fn chain(&self) -> impl Iterator<Item = &PathBuf> {
+        self.main.iter().chain(self.stack.iter())
+    }
+
+    /// Start processing an included source file, which becomes the current one. This
+    /// fails if the file is already being processed, as including it would never end
+    pub fn enter(&mut self, path: PathBuf) -> Result<(), Error> {
+        if let Some(start) = self.chain().position(|p| p == &path) {
+            let cycle = self
+                .chain()
+                .skip(start)
+                .chain(std::iter::once(&path))
+                .map(|p| format!("{}", p.display()))
+                .collect::<Vec<String>>()
+                .join(" -> ");
+
+            return Err(
+                Error::new(ErrKind::Context).with_msg(format!("cyclic include detected: {cycle}"))
+            );
+        }
+
+        if self.depth() >= self.max_depth {
+            return Err(Error::new(ErrKind::TypeChecker).with_msg(format!(
+                "maximum include depth ({}) exceeded when including {path:?}",
+                self.max_depth
+            )));
+        }
+
+        self.stack.push(path);
+
+        Ok(())
+    }
+
+    /// Stop processing the current included source file, which is now fully
+    /// included, and go back to the previous one
+    pub fn exit(&mut self) {
+        if let Some(path) = self.stack.pop() {
+            self.included.insert(path);
+        }
+    }
+
+    /// Amount of nested includes currently being processed
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Maximum amount of nested includes allowed
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Set the maximum amount of nested includes allowed
+    pub fn set_max_depth(&mut self, depth: usize) {
+        self.max_depth = depth
+    }
+
+    /// Forget about all the included files. The path of the main source file and the
+    /// configuration are kept
+    pub fn clear(&mut self) {
+        self.stack.clear();
+        self.included.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include_same_file_twice() {
+        let mut includes = IncludeCtx::default();
+        let file = PathBuf::from("/libs/lib.jk");
+
+        assert!(!includes.is_included(&file));
+
+        includes.enter(file.clone()).unwrap();
+        assert!(!includes.is_included(&file));
+
+        includes.exit();
+
+        assert!(includes.is_included(&file));
+        assert_eq!(includes.depth(), 0);
+    }
+
+    #[test]
+    fn enter_changes_base_dir() {
+        let mut includes = IncludeCtx::default();
+        includes.set_path(Some(PathBuf::from("/project/main.jk")));
+
+        assert_eq!(includes.base_dir(), PathBuf::from("/project"));
+
+        includes
+            .enter(PathBuf::from("/project/sub/lib.jk"))
+            .unwrap();
+        assert_eq!(includes.base_dir(), PathBuf::from("/project/sub"));
+
+        includes.exit();
+        assert_eq!(includes.base_dir(), PathBuf::from("/project"));
+    }
+
+    #[test]
+    fn base_dir_without_path() {
+        assert_eq!(IncludeCtx::default().base_dir(), PathBuf::new());
+    }
+
+    #[test]
+    fn max_depth() {
+        let mut includes = IncludeCtx::default();
+        includes.set_max_depth(1);
+
+        includes.enter(PathBuf::from("a.jk")).unwrap();
+        assert!(includes.enter(PathBuf::from("b.jk")).is_err());
+    }
+
+    #[test]
+    fn cycle() {
+        let mut includes = IncludeCtx::default();
+        includes.set_path(Some(PathBuf::from("main.jk")));
+
+        includes.enter(PathBuf::from("a.jk")).unwrap();
+        includes.enter(PathBuf::from("b.jk")).unwrap();
+
+        let err = includes.enter(PathBuf::from("a.jk")).unwrap_err();
+        assert_eq!(err.kind(), ErrKind::Context);
+        assert_eq!(
+            err.msg(),
+            Some("cyclic include detected: a.jk -> b.jk -> a.jk")
+        );
+
+        assert!(includes.enter(PathBuf::from("main.jk")).is_err());
+    }
+
+    #[test]
+    fn clear_keeps_main_file() {
+        let mut includes = IncludeCtx::default();
+        includes.set_path(Some(PathBuf::from("main.jk")));
+        includes.enter(PathBuf::from("lib.jk")).unwrap();
+        includes.exit();
+
+        includes.clear();
+
+        assert_eq!(includes.path(), Some(&PathBuf::from("main.jk")));
+        assert!(!includes.is_included(Path::new("lib.jk")));
+        assert_eq!(includes.depth(), 0);
+    }
+}
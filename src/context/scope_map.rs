@@ -11,28 +11,38 @@ use std::{
 };
 
 use crate::error::{ErrKind, Error};
+use crate::instance::ObjectInstance;
+use crate::typechecker::TypeId;
+
+use symbol::Symbol;
 
 /// A scope contains a set of available variables, functions and types.
 // FIXME: Shoud we split this in two? Between this and an ExecutionScope type?
 #[derive(Clone)]
 pub struct Scope<V, F, T> {
     /// Variables cannot be generic: They always have a concrete well defined
-    /// type.
-    pub(crate) variables: HashMap<String, V>,
+    /// type. They are accessed very often, so their names are interned in order to
+    /// keep lookups cheap
+    pub(crate) variables: HashMap<Symbol, V>,
+    /// Values of the variables of the scope which are constants. Constants are
+    /// variables as well, and are shadowed like any other variable
+    pub(crate) constants: HashMap<Symbol, ObjectInstance>,
     /// There are two types of functions: "base", generic functions, which can
     /// be duplicated and specialized into multiple versions, and "final" functions
     /// which are already specialized (or do not contain generics in the first place).
     /// Specialized functions should be referenced by their mangled names. If they
     /// do not contain generics, then they will simply be referenced by their
     /// names.
-    pub(crate) generic_functions: HashMap<String, F>,
-    pub(crate) functions: HashMap<String, F>,
+    pub(crate) generic_functions: HashMap<Symbol, F>,
+    pub(crate) functions: HashMap<Symbol, F>,
     /// Similarly, there are two types of types: generic types and specialized types, which are final.
     /// Types are identified by their [`TypeId`]. There is no way to differentiate
     /// between a generic [`TypeId`] and a specialized one, so you must be careful
     /// when appending types to the map.
     pub(crate) generic_types: HashMap<String, T>,
     pub(crate) types: HashMap<String, T>,
+    /// Aliases are alternative names for existing types, such as `type Meters = int`
+    pub(crate) aliases: HashMap<String, TypeId>,
 }
 
 impl<V, F, T> Default for Scope<V, F, T> {
@@ -40,12 +50,41 @@ impl<V, F, T> Default for Scope<V, F, T> {
     fn default() -> Scope<V, F, T> {
         Scope {
             variables: HashMap::new(),
+            constants: HashMap::new(),
             generic_functions: HashMap::new(),
             functions: HashMap::new(),
             generic_types: HashMap::new(),
             types: HashMap::new(),
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+impl<V, F, T> Scope<V, F, T> {
+    /// Create a new empty Scope with room for the given amount of variables, functions
+    /// and types
+    pub fn with_capacity(variables: usize, functions: usize, types: usize) -> Scope<V, F, T> {
+        Scope {
+            variables: HashMap::with_capacity(variables),
+            constants: HashMap::new(),
+            generic_functions: HashMap::new(),
+            functions: HashMap::with_capacity(functions),
+            generic_types: HashMap::new(),
+            types: HashMap::with_capacity(types),
+            aliases: HashMap::new(),
         }
     }
+
+    /// Remove all the declarations of the scope, keeping the allocated memory
+    pub fn clear(&mut self) {
+        self.variables.clear();
+        self.constants.clear();
+        self.generic_functions.clear();
+        self.functions.clear();
+        self.generic_types.clear();
+        self.types.clear();
+        self.aliases.clear();
+    }
 }
 
 /// A scope stack is a reversed stack. This alias is made for code clarity
@@ -66,6 +105,26 @@ impl<V, F, T> ScopeMap<V, F, T> {
         }
     }
 
+    /// Create a new scope map containing one scope, with room for the given amount of
+    /// variables, functions and types
+    pub fn with_capacity(variables: usize, functions: usize, types: usize) -> ScopeMap<V, F, T> {
+        let mut scopes = ScopeStack::new();
+        scopes.push_front(Scope::with_capacity(variables, functions, types));
+
+        ScopeMap { scopes }
+    }
+
+    /// Exit all scopes but the outermost one, and remove all of its declarations
+    pub fn clear(&mut self) {
+        while self.scopes.len() > 1 {
+            self.scope_exit();
+        }
+
+        if let Some(outermost) = self.scopes.front_mut() {
+            outermost.clear();
+        }
+    }
+
     /// Get a reference on the scopes inside a ScopeMap
     pub fn scopes(&self) -> &ScopeStack<Scope<V, F, T>> {
         &self.scopes
@@ -122,25 +181,44 @@ impl<V, F, T> ScopeMap<V, F, T> {
     }
 
     /// Maybe get a variable in any available scopes
-    pub fn get_variable(&self, name: &str) -> Option<&V> {
+    pub fn get_variable(&self, name: &Symbol) -> Option<&V> {
         self.get(name, |scope| &scope.variables)
     }
 
     /// Maybe get a mutable reference to a variable in any available scopes
-    pub fn get_variable_mut(&mut self, name: &str) -> Option<&mut V> {
+    pub fn get_variable_mut(&mut self, name: &Symbol) -> Option<&mut V> {
         self.scopes
             .iter_mut()
             .map(|scope| scope.variables.get_mut(name))
             .find(|var| var.is_some())?
     }
 
+    /// Maybe get the value of a constant in any available scopes. Only the innermost
+    /// declaration of `name` is considered: If it is a regular variable shadowing a
+    /// constant, there is no value
+    pub fn get_constant(&self, name: &Symbol) -> Option<&ObjectInstance> {
+        self.scopes
+            .iter()
+            .find(|scope| scope.variables.contains_key(name))?
+            .constants
+            .get(name)
+    }
+
     /// Maybe get a function in any available scopes
-    pub fn get_function(&self, name: &str) -> Option<&F> {
+    pub fn get_function(&self, name: &Symbol) -> Option<&F> {
         self.get(name, |scope| &scope.functions)
     }
 
+    /// Maybe get a mutable reference to a function in any available scopes
+    pub fn get_function_mut(&mut self, name: &Symbol) -> Option<&mut F> {
+        self.scopes
+            .iter_mut()
+            .map(|scope| scope.functions.get_mut(name))
+            .find(|func| func.is_some())?
+    }
+
     /// Maybe get a generic function in any available scopes
-    pub fn get_generic_function(&self, name: &str) -> Option<&F> {
+    pub fn get_generic_function(&self, name: &Symbol) -> Option<&F> {
         self.get(name, |scope| &scope.generic_functions)
     }
 
@@ -154,18 +232,28 @@ impl<V, F, T> ScopeMap<V, F, T> {
         self.get(name, |scope| &scope.generic_types)
     }
 
+    /// Maybe get the type aliased by the given name in any available scopes
+    pub fn get_alias(&self, name: &str) -> Option<&TypeId> {
+        self.get(name, |scope| &scope.aliases)
+    }
+
     /// Add a variable to the current scope if it hasn't been added before
-    pub fn add_variable(&mut self, name: String, var: V) -> Result<(), Error> {
+    pub fn add_variable(&mut self, name: Symbol, var: V) -> Result<(), Error> {
         self.insert_unique(name, var, |scope| &mut scope.variables)
     }
 
+    /// Mark a variable of the current scope as a constant, whose value is `value`
+    pub fn add_constant(&mut self, name: Symbol, value: ObjectInstance) -> Result<(), Error> {
+        self.insert_unique(name, value, |scope| &mut scope.constants)
+    }
+
     /// Add a function to the current scope if it hasn't been added before
-    pub fn add_function(&mut self, name: String, func: F) -> Result<(), Error> {
+    pub fn add_function(&mut self, name: Symbol, func: F) -> Result<(), Error> {
         self.insert_unique(name, func, |scope| &mut scope.functions)
     }
 
     /// Add a generic function to the current scope if it hasn't been added before
-    pub fn add_generic_function(&mut self, name: String, func: F) -> Result<(), Error> {
+    pub fn add_generic_function(&mut self, name: Symbol, func: F) -> Result<(), Error> {
         self.insert_unique(name, func, |scope| &mut scope.generic_functions)
     }
 
@@ -178,6 +266,57 @@ impl<V, F, T> ScopeMap<V, F, T> {
     pub fn add_generic_type(&mut self, name: String, custom_type: T) -> Result<(), Error> {
         self.insert_unique(name, custom_type, |scope| &mut scope.generic_types)
     }
+
+    /// Add a type alias to the current scope if it hasn't been added before
+    pub fn add_alias(&mut self, name: String, target: TypeId) -> Result<(), Error> {
+        self.insert_unique(name, target, |scope| &mut scope.aliases)
+    }
+
+    /// Collect the names extracted from every available scope, sorted. Names declared
+    /// in multiple scopes only appear once, as the inner declarations shadow the
+    /// outer ones
+    fn names<'map>(
+        &'map self,
+        names_extractor: impl Fn(&'map Scope<V, F, T>) -> Vec<&'map str>,
+    ) -> Vec<&'map str> {
+        let mut names: Vec<&str> = self.scopes.iter().flat_map(names_extractor).collect();
+        names.sort_unstable();
+        names.dedup();
+
+        names
+    }
+
+    /// Get the names of the variables visible from the current scope, sorted
+    pub fn variable_names(&self) -> Vec<&str> {
+        self.names(|scope| scope.variables.keys().map(Symbol::access).collect())
+    }
+
+    /// Get the names of the functions visible from the current scope, generic or
+    /// not, sorted
+    pub fn function_names(&self) -> Vec<&str> {
+        self.names(|scope| {
+            scope
+                .functions
+                .keys()
+                .chain(scope.generic_functions.keys())
+                .map(Symbol::access)
+                .collect()
+        })
+    }
+
+    /// Get the names of the types visible from the current scope, including generic
+    /// types and aliases, sorted
+    pub fn type_names(&self) -> Vec<&str> {
+        self.names(|scope| {
+            scope
+                .types
+                .keys()
+                .chain(scope.generic_types.keys())
+                .chain(scope.aliases.keys())
+                .map(String::as_str)
+                .collect()
+        })
+    }
 }
 
 #[cfg(test)]
@@ -187,7 +326,7 @@ mod tests {
 
     macro_rules! s {
         ($s:literal) => {
-            String::from($s)
+            Symbol::from($s)
         };
     }
 
@@ -205,6 +344,56 @@ mod tests {
         s.scope_exit();
     }
 
+    #[test]
+    fn t_clear_keeps_outermost_scope() {
+        let mut s: ScopeMap<Var, (), ()> = ScopeMap::with_capacity(4, 0, 0);
+
+        s.add_variable(s!("outer"), Var::new("outer".to_owned()))
+            .unwrap();
+        s.scope_enter();
+        s.add_variable(s!("inner"), Var::new("inner".to_owned()))
+            .unwrap();
+
+        s.clear();
+
+        assert_eq!(s.scopes().len(), 1);
+        assert!(s.get_variable(&s!("outer")).is_none());
+        assert!(s.get_variable(&s!("inner")).is_none());
+        assert!(s
+            .add_variable(s!("outer"), Var::new("outer".to_owned()))
+            .is_ok());
+    }
+
+    #[test]
+    fn t_visible_names() {
+        let mut s = new_scopemap();
+
+        s.scope_enter();
+        s.add_variable(s!("a"), Var::new("a".to_owned())).unwrap();
+        s.add_variable(s!("b"), Var::new("b".to_owned())).unwrap();
+        s.add_function(s!("f"), ()).unwrap();
+        s.add_type("T".to_owned(), ()).unwrap();
+        assert_eq!(s.variable_names(), vec!["a", "b"]);
+
+        s.scope_enter();
+        s.add_variable(s!("c"), Var::new("c".to_owned())).unwrap();
+        s.add_variable(s!("b"), Var::new("b".to_owned())).unwrap();
+        s.add_generic_function(s!("g"), ()).unwrap();
+        s.add_function(s!("f"), ()).unwrap();
+        s.add_alias("Alias".to_owned(), TypeId::from("T")).unwrap();
+        assert_eq!(s.variable_names(), vec!["a", "b", "c"]);
+        assert_eq!(s.function_names(), vec!["f", "g"]);
+        assert_eq!(s.type_names(), vec!["Alias", "T"]);
+
+        s.scope_exit();
+        assert_eq!(s.variable_names(), vec!["a", "b"]);
+        assert_eq!(s.function_names(), vec!["f"]);
+        assert_eq!(s.type_names(), vec!["T"]);
+
+        s.scope_exit();
+        assert!(s.variable_names().is_empty());
+    }
+
     #[test]
     #[should_panic]
     fn t_add_var_non_existent_scope() {
@@ -218,7 +407,7 @@ mod tests {
     fn t_find_non_existent_var() {
         let s = new_scopemap();
 
-        assert!(s.get_variable("a").is_none());
+        assert!(s.get_variable(&s!("a")).is_none());
     }
 
     #[test]
@@ -228,7 +417,7 @@ mod tests {
         s.scope_enter();
         s.add_variable(s!("a"), Var::new("a".to_owned())).unwrap();
 
-        assert!(s.get_variable("a").is_some());
+        assert!(s.get_variable(&s!("a")).is_some());
     }
 
     #[test]
@@ -244,7 +433,7 @@ mod tests {
         s.scope_enter();
         s.scope_enter();
 
-        assert!(s.get_variable("a").is_some());
+        assert!(s.get_variable(&s!("a")).is_some());
     }
 
     #[test]
@@ -257,7 +446,7 @@ mod tests {
 
         s.scope_exit();
 
-        assert!(s.get_variable("a").is_none());
+        assert!(s.get_variable(&s!("a")).is_none());
     }
 
     #[test]
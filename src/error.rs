@@ -1,10 +1,11 @@
 //! The Error module contains helpful wrapper around possible errors in jinko. They
 //! are used by the context as well as the parser.
 
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 
 use crate::location::{SourceOwned, SpanTuple};
 use crate::parser::ParseInput;
@@ -14,12 +15,26 @@ use crate::parser::ParseInput;
 #[derive(Default)]
 pub struct ErrorHandler {
     errors: Vec<Error>,
+    warnings: Vec<Error>,
+    /// Number of warnings which have already been emitted. Warnings do not stop the
+    /// execution of a program, so they must not be emitted again by later calls
+    emitted_warnings: usize,
     file: PathBuf,
 }
 
 impl ErrorHandler {
-    /// Emit all the errors contained in a handler
-    pub fn emit(&self) {
+    /// Emit all the warnings and errors contained in a handler. Warnings are only
+    /// emitted once
+    pub fn emit(&mut self) {
+        self.warnings
+            .iter()
+            .skip(self.emitted_warnings)
+            .for_each(|w| {
+                w.emit_warning();
+                eprintln!();
+            });
+        self.emitted_warnings = self.warnings.len();
+
         if let Some(first_err) = self.errors.first() {
             first_err.emit();
         }
@@ -34,15 +49,24 @@ impl ErrorHandler {
         self.errors.push(err)
     }
 
-    /// Drains all the errors contained in another handler in order to accumulate them
-    /// in one place
+    /// Add a new warning to the handler. Warnings do not prevent the execution of
+    /// a program
+    pub fn warn(&mut self, warning: Error) {
+        self.warnings.push(warning)
+    }
+
+    /// Drains all the errors and warnings contained in another handler in order to
+    /// accumulate them in one place
     pub fn append(&mut self, other: &mut ErrorHandler) {
-        self.errors.append(&mut other.errors)
+        self.errors.append(&mut other.errors);
+        self.warnings.append(&mut other.warnings);
     }
 
-    /// Remove all the errors contained in the handler
+    /// Remove all the errors and warnings contained in the handler
     pub fn clear(&mut self) {
-        self.errors.clear()
+        self.errors.clear();
+        self.warnings.clear();
+        self.emitted_warnings = 0;
     }
 
     /// Set the file that should be used by the error handler. This function should be
@@ -55,6 +79,43 @@ impl ErrorHandler {
     pub fn has_errors(&self) -> bool {
         !self.errors.is_empty()
     }
+
+    /// Get the errors currently contained in the handler, in the order they were added
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    /// Get the warnings currently contained in the handler, in the order they were added
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    /// Serialize all the errors contained in the handler to a JSON array, for tools
+    /// such as editors. Each diagnostic has a `kind`, a `message` and a `location`,
+    /// which are `null` when unknown
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let diagnostics: Vec<JsonDiagnostic> =
+            self.errors.iter().map(JsonDiagnostic::from).collect();
+
+        // Serializing plain structures to a string cannot fail
+        serde_json::to_string(&diagnostics).unwrap()
+    }
+
+    /// Group the errors contained in the handler by the file they originate from.
+    /// Errors without a location, or whose location does not point to a file, are
+    /// grouped under the handler's own file
+    pub fn by_file(&self) -> HashMap<PathBuf, Vec<&Error>> {
+        self.errors.iter().fold(HashMap::new(), |mut files, err| {
+            let file = match err.loc.as_deref().map(SpanTuple::source) {
+                Some(SourceOwned::Path(path)) => path.clone(),
+                _ => self.file.clone(),
+            };
+
+            files.entry(file).or_default().push(err);
+            files
+        })
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -69,6 +130,10 @@ pub enum ErrKind {
     IO,
     Debug,
     UTF8,
+    DivByZero,
+    Overflow,
+    IndexOutOfBounds,
+    UndeclaredVariable,
 }
 
 impl ErrKind {
@@ -83,6 +148,10 @@ impl ErrKind {
             ErrKind::ExternFunc => "external function",
             ErrKind::Debug => "debug",
             ErrKind::UTF8 => "UTF-8",
+            ErrKind::DivByZero => "division by zero",
+            ErrKind::Overflow => "overflow",
+            ErrKind::IndexOutOfBounds => "index out of bounds",
+            ErrKind::UndeclaredVariable => "undeclared variable",
         }
     }
 }
@@ -96,6 +165,38 @@ pub struct Error {
     hints: Vec<Error>,
 }
 
+/// Error as serialized by [`ErrorHandler::to_json`]
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct JsonDiagnostic<'err> {
+    kind: String,
+    message: Option<&'err str>,
+    location: Option<JsonLocation>,
+}
+
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct JsonLocation {
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+#[cfg(feature = "json")]
+impl<'err> From<&'err Error> for JsonDiagnostic<'err> {
+    fn from(err: &'err Error) -> JsonDiagnostic<'err> {
+        JsonDiagnostic {
+            kind: format!("{:?}", err.kind),
+            message: err.msg(),
+            location: err.loc.as_deref().map(|loc| JsonLocation {
+                file: get_path_str(loc),
+                line: loc.start().line(),
+                column: loc.start().column(),
+            }),
+        }
+    }
+}
+
 fn get_path_str(loc: &SpanTuple) -> String {
     match loc.source() {
         SourceOwned::Path(p) => format!("{}", p.display()),
@@ -105,14 +206,14 @@ fn get_path_str(loc: &SpanTuple) -> String {
 }
 
 impl Error {
-    fn emit_full_loc(&self, loc: &SpanTuple) {
+    fn emit_full_loc(&self, label: ColoredString, loc: &SpanTuple) {
         let (before_ctx, after_ctx) = loc.generate_context();
         let path = get_path_str(loc);
 
         if let Some(msg) = &self.msg {
             eprintln!(
                 "{}: {}:{}:{}: {}",
-                "error".black().on_yellow(),
+                label,
                 path.yellow(),
                 loc.start().line(),
                 loc.start().column(),
@@ -152,7 +253,7 @@ impl Error {
 
     pub fn emit(&self) {
         if let Some(loc) = &self.loc {
-            self.emit_full_loc(loc);
+            self.emit_full_loc("error".black().on_yellow(), loc);
         } else if let Some(msg) = &self.msg {
             eprintln!("{msg}")
         }
@@ -164,6 +265,19 @@ impl Error {
         self.hints.iter().skip(1).for_each(|hint| hint.emit_hint());
     }
 
+    /// Emit the error as a warning, which does not stop the interpreter
+    pub fn emit_warning(&self) {
+        let warning = "warning".black().on_cyan();
+
+        if let Some(loc) = &self.loc {
+            self.emit_full_loc(warning, loc);
+        } else if let Some(msg) = &self.msg {
+            eprintln!("{warning}: {msg}")
+        }
+
+        self.hints.iter().for_each(|hint| hint.emit_hint());
+    }
+
     /// Emit a debug interpreter error - this is only useful for debugging the
     /// interpreter itself
     pub fn emit_debug(&self) {
@@ -212,6 +326,21 @@ impl Error {
         }
     }
 
+    /// Get the kind of the error
+    pub fn kind(&self) -> ErrKind {
+        self.kind
+    }
+
+    /// Get the location in the source code the error points to, if it has one
+    pub fn loc(&self) -> Option<&SpanTuple> {
+        self.loc.as_deref()
+    }
+
+    /// Get the message of the error, if it has one
+    pub fn msg(&self) -> Option<&str> {
+        self.msg.as_deref()
+    }
+
     pub fn hint() -> Error {
         Error::new(ErrKind::Hint)
     }
@@ -248,6 +377,20 @@ impl Error {
     }
 }
 
+/// Create an internal compiler error, or "ICE", which indicates a bug in the
+/// interpreter rather than in the program it runs. Debug builds panic, so that the
+/// bug does not go unnoticed. Release builds return an error instead, so that the
+/// interpreter can unwind gracefully and embedders are kept alive
+#[track_caller]
+pub(crate) fn ice(msg: impl Display) -> Error {
+    let msg = format!("internal compiler error: {msg}");
+    if cfg!(debug_assertions) {
+        panic!("{msg}");
+    }
+
+    Error::new(ErrKind::Context).with_msg(msg)
+}
+
 use std::convert::From;
 use std::io;
 
@@ -310,8 +453,8 @@ impl<'i> nom::error::ParseError<ParseInput<'i>> for Error {
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         write!(f, "{}", self.kind.as_str())?;
-        if self.msg.is_some() {
-            write!(f, ": {}", self.msg.as_ref().unwrap())?;
+        if let Some(msg) = &self.msg {
+            write!(f, ": {}", msg)?;
         }
 
         if let Some(loc) = &self.loc {
@@ -350,3 +493,95 @@ impl From<xparser::Error<'_>> for Error {
         Error::new(ErrKind::Parsing)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::{Location, Source};
+    use std::path::Path;
+
+    fn error_in(file: &str) -> Error {
+        Error::new(ErrKind::TypeChecker).with_loc(Some(SpanTuple::with_source_ref(
+            Source::Path(Path::new(file)),
+            Location::new(1, 1),
+            Location::new(1, 2),
+        )))
+    }
+
+    #[test]
+    fn t_errors_by_file() {
+        let mut handler = ErrorHandler::default();
+        handler.set_path(PathBuf::from("main.jk"));
+
+        handler.add(error_in("a.jk"));
+        handler.add(error_in("b.jk"));
+        handler.add(error_in("a.jk"));
+        handler.add(Error::new(ErrKind::Context));
+
+        let files = handler.by_file();
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[Path::new("a.jk")].len(), 2);
+        assert_eq!(files[Path::new("b.jk")].len(), 1);
+        assert_eq!(files[Path::new("main.jk")].len(), 1);
+    }
+
+    #[test]
+    fn t_errors_accessors() {
+        let mut handler = ErrorHandler::default();
+
+        handler.add(Error::new(ErrKind::Context).with_msg(String::from("oops")));
+        handler.add(Error::new(ErrKind::Parsing));
+
+        let errors = handler.errors();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind(), ErrKind::Context);
+        assert_eq!(errors[0].msg(), Some("oops"));
+        assert_eq!(errors[1].kind(), ErrKind::Parsing);
+        assert_eq!(errors[1].msg(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn t_errors_to_json() {
+        let mut ctx = crate::Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert!(ctx.eval("1 + '4'").is_err());
+
+        let json = ctx.error_handler.to_json();
+        assert!(json.starts_with('[') && json.ends_with(']'));
+        assert!(json.contains(r#""kind":"TypeChecker""#));
+        assert!(json
+            .contains(r#""message":"trying to do binary operation on invalid types: int + char""#));
+        assert!(json.contains(r#""location":{"file":"<source>","line":1,"column":1}"#));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn t_errors_to_json_without_location() {
+        let mut handler = ErrorHandler::default();
+        handler.add(Error::new(ErrKind::Context));
+
+        assert_eq!(
+            handler.to_json(),
+            r#"[{"kind":"Context","message":null,"location":null}]"#
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "internal compiler error: unexpected state")]
+    fn t_ice_panics_in_debug() {
+        ice("unexpected state");
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn t_ice_is_an_error_in_release() {
+        let err = ice("unexpected state");
+
+        assert_eq!(err.kind(), ErrKind::Context);
+        assert_eq!(err.msg(), Some("internal compiler error: unexpected state"));
+    }
+}
@@ -3,7 +3,7 @@
 //! FIXME
 
 use crate::context::Context;
-use crate::error::{ErrKind, Error};
+use crate::error::{ice, ErrKind, Error};
 use crate::instance::{FromObjectInstance, ObjectInstance, ToObjectInstance};
 use crate::instruction::{FunctionCall, FunctionDec};
 use crate::value::{JkBool, JkFloat, JkInt, JkString};
@@ -151,12 +151,12 @@ pub fn execute(
     let args: Vec<FfiArg> = ffi_jk_args
         .iter()
         .map(|ffi_jk_arg| match ffi_jk_arg {
-            FfiJkArg::Int(i) => arg(i),
-            FfiJkArg::Float(f) => arg(f),
-            FfiJkArg::Pointer(p) => arg(p),
-            _ => unreachable!(),
+            FfiJkArg::Int(i) => Ok(arg(i)),
+            FfiJkArg::Float(f) => Ok(arg(f)),
+            FfiJkArg::Pointer(p) => Ok(arg(p)),
+            _ => Err(ice("invalid argument left for external function call")),
         })
-        .collect();
+        .collect::<Result<_, Error>>()?;
 
     for lib in ctx.libs().iter() {
         // FIXME: Rework this
@@ -170,7 +170,7 @@ pub fn execute(
             match dec.ty() {
                 None => {
                     ffi_call::<()>(func, &args);
-                    return Ok(None);
+                    return Ok(Some(ObjectInstance::void()));
                 }
                 Some(ty) => match ty.id() {
                     "int" => {
@@ -264,7 +264,7 @@ mod tests {
         let call = constructs::expr(span!("print_something()")).unwrap().1;
         let call = call.downcast_ref::<FunctionCall>().unwrap();
 
-        assert_eq!(execute(dec, call, &mut i), Ok(None));
+        assert_eq!(execute(dec, call, &mut i), Ok(Some(ObjectInstance::void())));
     }
 
     #[test]
@@ -0,0 +1,308 @@
+//! Formats jinko instructions back into canonically indented source code. Blocks and
+//! the instructions containing them, such as `if`/`else` constructs, function
+//! declarations or loops, are laid out by walking the instructions' children, with
+//! one instruction per line. Other instructions are printed using their own
+//! [`Instruction::print`] implementation.
+
+use crate::instruction::{
+    Block, FunctionDec, IfElse, InstrKind, Instruction, Loop, Match, Return, VarAssign,
+};
+
+/// Default amount of spaces used to indent the content of blocks
+pub const DEFAULT_INDENT_WIDTH: usize = 4;
+
+/// Format a list of top-level instructions into jinko source code, indenting blocks
+/// by [`DEFAULT_INDENT_WIDTH`] spaces
+pub fn format_source(instrs: &[Box<dyn Instruction>]) -> String {
+    SourceFormatter::default().format(instrs)
+}
+
+/// Formats instructions into source code, with a configurable indentation
+#[derive(Clone, Copy, Debug)]
+pub struct SourceFormatter {
+    indent_width: usize,
+}
+
+impl Default for SourceFormatter {
+    fn default() -> SourceFormatter {
+        SourceFormatter::new(DEFAULT_INDENT_WIDTH)
+    }
+}
+
+impl SourceFormatter {
+    /// Create a new formatter indenting the content of blocks by `indent_width` spaces
+    pub fn new(indent_width: usize) -> SourceFormatter {
+        SourceFormatter { indent_width }
+    }
+
+    /// Format a list of top-level instructions into jinko source code. Instructions
+    /// which do not end with a block are terminated by a semicolon
+    pub fn format(&self, instrs: &[Box<dyn Instruction>]) -> String {
+        instrs.iter().fold(String::new(), |mut source, instr| {
+            let formatted = self.format_instruction(instr.as_ref(), 0);
+            source.push_str(&formatted);
+            if !formatted.ends_with('}') {
+                source.push(';');
+            }
+            source.push('\n');
+
+            source
+        })
+    }
+
+    fn indent(&self, depth: usize) -> String {
+        " ".repeat(self.indent_width * depth)
+    }
+
+    /// Format an instruction located at the given nesting depth. The first line of
+    /// the result is not indented, since the instruction might not start a line
+    fn format_instruction(&self, instr: &dyn Instruction, depth: usize) -> String {
+        if let Some(block) = instr.downcast_ref::<Block>() {
+            return self.format_block(block, depth);
+        }
+
+        if let Some(if_else) = instr.downcast_ref::<IfElse>() {
+            return self.format_if_else(if_else, depth);
+        }
+
+        if let Some(function) = instr.downcast_ref::<FunctionDec>() {
+            return match function.block() {
+                Some(block) => format!(
+                    "{} {}",
+                    function.signature(),
+                    self.format_block(block, depth)
+                ),
+                None => format!("{} {{}}", function.signature()),
+            };
+        }
+
+        if let Some(jk_loop) = instr.downcast_ref::<Loop>() {
+            return format!(
+                "{} {}",
+                jk_loop.header(),
+                self.format_block(jk_loop.block(), depth)
+            );
+        }
+
+        if let Some(jk_match) = instr.downcast_ref::<Match>() {
+            return self.format_match(jk_match, depth);
+        }
+
+        if let Some(assign) = instr.downcast_ref::<VarAssign>() {
+            let mutable = if assign.mutable() { "mut " } else { "" };
+            return format!(
+                "{mutable}{} = {}",
+                assign.symbol(),
+                self.format_instruction(assign.value(), depth)
+            );
+        }
+
+        if let Some(ret) = instr.downcast_ref::<Return>() {
+            return match ret.children().first() {
+                Some(value) => format!("return {}", self.format_instruction(*value, depth)),
+                None => String::from("return"),
+            };
+        }
+
+        // The instruction prints itself: Only shift its following lines to the
+        // current depth
+        instr
+            .print()
+            .lines()
+            .collect::<Vec<&str>>()
+            .join(&format!("\n{}", self.indent(depth)))
+    }
+
+    fn format_block(&self, block: &Block, depth: usize) -> String {
+        let instructions = block.children();
+        if instructions.is_empty() {
+            return String::from("{}");
+        }
+
+        let mut source = String::from("{\n");
+        let last = instructions.len() - 1;
+
+        instructions.into_iter().enumerate().for_each(|(i, instr)| {
+            source.push_str(&self.indent(depth + 1));
+            source.push_str(&self.format_instruction(instr, depth + 1));
+
+            // A semicolon after the last instruction is only needed to discard its value
+            let discarded = !block.last_is_expression() && instr.kind() != InstrKind::Statement;
+            if i != last || discarded {
+                source.push(';');
+            }
+            source.push('\n');
+        });
+
+        source.push_str(&self.indent(depth));
+        source.push('}');
+
+        source
+    }
+
+    fn format_if_else(&self, if_else: &IfElse, depth: usize) -> String {
+        let children = if_else.children();
+        let mut source = format!(
+            "if {} {}",
+            self.format_instruction(children[0], depth),
+            self.format_instruction(children[1], depth)
+        );
+
+        if let Some(else_body) = children.get(2) {
+            source.push_str(" else ");
+            source.push_str(&self.format_instruction(*else_body, depth));
+        }
+
+        source
+    }
+
+    fn format_match(&self, jk_match: &Match, depth: usize) -> String {
+        // The scrutinee is followed by the pattern and body of each arm, and by the
+        // body of the default arm
+        let children = jk_match.children();
+        let (scrutinee, arms) = children.split_first().unwrap();
+        let (default, arms) = arms.split_last().unwrap();
+
+        let mut source = format!("match {} {{\n", self.format_instruction(*scrutinee, depth));
+        let mut push_arm = |pattern: String, body: &dyn Instruction| {
+            source.push_str(&self.indent(depth + 1));
+            source.push_str(&format!(
+                "{pattern} => {},\n",
+                self.format_instruction(body, depth + 1)
+            ));
+        };
+
+        arms.chunks(2)
+            .for_each(|arm| push_arm(self.format_instruction(arm[0], depth + 1), arm[1]));
+        push_arm(String::from("_"), *default);
+
+        source.push_str(&self.indent(depth));
+        source.push('}');
+
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::constructs;
+
+    fn parse(input: &str) -> Vec<Box<dyn Instruction>> {
+        let input = nom_locate::LocatedSpan::new_extra(input, crate::location::Source::Empty);
+
+        constructs::many_expr(input).unwrap().1
+    }
+
+    #[test]
+    fn nested_if_else() {
+        let instrs = parse("if a { if b { 1 } else { x = 2; x } } else { y = 3; y; }");
+
+        assert_eq!(
+            format_source(&instrs),
+            "if a {
+    if b {
+        1
+    } else {
+        x = 2;
+        x
+    }
+} else {
+    y = 3;
+    y;
+}
+"
+        );
+    }
+
+    #[test]
+    fn custom_indent_width() {
+        let instrs = parse("x = 1; if x == 1 { {} }");
+
+        assert_eq!(
+            SourceFormatter::new(2).format(&instrs),
+            "x = 1;
+if x == 1 {
+  {}
+}
+"
+        );
+    }
+
+    #[test]
+    fn nested_function_body() {
+        let instrs = parse(
+            "func sign(n: int) -> int { if n > 0 { 1 } else { if n < 0 { 0 - 1 } else { 0 } } }",
+        );
+
+        assert_eq!(
+            SourceFormatter::new(2).format(&instrs),
+            "func sign(n: int) -> int {
+  if n > 0 {
+    1
+  } else {
+    if n < 0 {
+      0 - 1
+    } else {
+      0
+    }
+  }
+}
+"
+        );
+    }
+
+    #[test]
+    fn nested_loops_and_assignments() {
+        let instrs = parse(
+            "func f(n: int) { mut i = 0; while i < n { x = { i + 1 }; i = x; }; loop { return; } }",
+        );
+
+        assert_eq!(
+            format_source(&instrs),
+            "func f(n: int) {
+    mut i = 0;
+    while i < n {
+        x = {
+            i + 1
+        };
+        i = x
+    };
+    loop {
+        return
+    }
+}
+"
+        );
+    }
+
+    #[test]
+    fn nested_match() {
+        let instrs = parse("func f(n: int) -> int { match n { 0 => { 1 }, _ => { n * 2 } } }");
+
+        assert_eq!(
+            SourceFormatter::new(2).format(&instrs),
+            "func f(n: int) -> int {
+  match n {
+    0 => {
+      1
+    },
+    _ => {
+      n * 2
+    },
+  }
+}
+"
+        );
+    }
+
+    #[test]
+    fn idempotent() {
+        let once = format_source(&parse(
+            "mut a = 1; if a < 2 { if a > 0 { a = a + 1; } else { a = 0 }; a } else { 4 } a",
+        ));
+        let twice = format_source(&parse(&once));
+
+        assert_eq!(once, twice);
+    }
+}
@@ -77,6 +77,31 @@ impl GenericMap {
     pub fn specialized_types(&self, generics: &[TypeId]) -> Result<Vec<TypeId>, Error> {
         generics.iter().map(|g| self.get_specialized(g)).collect()
     }
+
+    /// Replace all the generic types contained in a [`TypeId`] with their specialized
+    /// counterpart. Types which are not generic are returned as is
+    pub fn specialize(&self, ty: &TypeId) -> TypeId {
+        if let Some(specialized) = self.map.get(ty) {
+            return specialized.clone();
+        }
+
+        match ty {
+            TypeId::Type { id, path, generics } => TypeId::Type {
+                id: id.clone(),
+                path: path.clone(),
+                generics: generics.iter().map(|g| self.specialize(g)).collect(),
+            },
+            TypeId::Functor {
+                generics,
+                arg_types,
+                return_type,
+            } => TypeId::Functor {
+                generics: generics.iter().map(|g| self.specialize(g)).collect(),
+                arg_types: arg_types.iter().map(|a| self.specialize(a)).collect(),
+                return_type: return_type.as_ref().map(|r| Box::new(self.specialize(r))),
+            },
+        }
+    }
 }
 
 /// Mangle a name to resolve it to its proper expanded name.
@@ -122,20 +147,6 @@ pub fn original_name(mangled_name: &str) -> &str {
     }
 }
 
-/// Since most of the instructions cannot do generic expansion, we can implement
-/// default methods which do nothing. This avoid more boilerplate code for instructions
-/// such as constants or variables which cannot be generic.
-pub trait GenericUser {
-    /// Mutate an instruction in order to resolve to the proper, expanded generic instruction.
-    /// This function is also responsible for calling `resolve_usages` on all its sub-items:
-    /// A block is responsible for resolving each of its statements, as well as its final
-    /// expression if there is one.
-    ///
-    /// With a type map like the following: `{ T: int, U: string }`, a type call like
-    /// `f[T, U]()` should be resolved to `f+int+string()`
-    fn resolve_usages(&mut self, _type_map: &GenericMap, _ctx: &mut TypeCtx) {}
-}
-
 /// Only a certain set of instructions are generic expansion sites - They can generate a "new
 /// version of themselves" with a given typemap. Once a new version is generated, they must
 /// be typechecked by the generator.
@@ -183,6 +194,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn specialize_nested_types() {
+        use crate::io_trait::JkStdReader;
+        let mut ctx = TypeCtx::new(Box::new(JkStdReader));
+
+        let map = GenericMap::create(&[ty!("T")], &[ty!("int")], &mut ctx).unwrap();
+
+        assert_eq!(map.specialize(&ty!("T")), ty!("int"));
+        assert_eq!(map.specialize(&ty!("U")), ty!("U"));
+        assert_eq!(
+            map.specialize(&ty!("Vec").with_generic(ty!("T"))),
+            ty!("Vec").with_generic(ty!("int"))
+        );
+    }
+
+    #[test]
+    fn resolve_usages_of_children() {
+        use crate::io_trait::JkStdReader;
+        use crate::parser::constructs;
+        use crate::span;
+
+        let mut ctx = TypeCtx::new(Box::new(JkStdReader));
+        let map = GenericMap::create(&[ty!("T")], &[ty!("int")], &mut ctx).unwrap();
+
+        let (_, mut instr) = constructs::expr(span!(
+            "if id[T](1) > 2 { a = id[T](3); } else { a = id[U](4); }"
+        ))
+        .unwrap();
+        instr.resolve_usages(&map, &mut ctx);
+
+        let printed = instr.print();
+        assert!(printed.contains("id[int](1) > 2"));
+        assert!(printed.contains("a = id[int](3)"));
+        assert!(printed.contains("a = id[U](4)"));
+    }
+
     #[test]
     fn create_map_different_size() {
         use crate::io_trait::JkStdReader;
@@ -6,8 +6,12 @@
 //! For example, a variable contains an ObjectInstance. Since a variable cannot be uninitialized,
 //! the instance is always there. The type of the ObjectInstance might be resolved later, after
 //! different passes of the typechecker.
+//! The memory region and fields of an instance are reference counted: Cloning an
+//! instance, for example when reading a variable, shares them instead of copying them.
+//! Instances are never mutated in place, so copies never observe each other's changes.
 
 use std::collections::HashMap;
+use std::rc::Rc;
 
 use crate::error::{ErrKind, Error};
 use crate::indent::Indent;
@@ -34,12 +38,14 @@ type FieldsMap = HashMap<Name, FieldInstance>;
 /// The type is optional. At first, the type might not be known, and will only be
 /// revealed during the typechecking phase. `size` is the size of the instance in bytes.
 /// It's the same as `data.len()`. `data` is the raw byte value of the instance.
+/// Two instances are equal if they have the same type and the same raw bytes, as
+/// well as equal fields: An `int` instance is never equal to a `float` or `char` one.
 #[derive(Debug, PartialEq, Clone)]
 pub struct ObjectInstance {
     ty: CheckedType,
     size: usize,
-    data: Vec<u8>,
-    fields: Option<FieldsMap>,
+    data: Rc<[u8]>,
+    fields: Option<Rc<FieldsMap>>,
 }
 
 impl ObjectInstance {
@@ -48,6 +54,17 @@ impl ObjectInstance {
         ObjectInstance::new(CheckedType::Error, 0, vec![], None)
     }
 
+    /// Create the instance resulting from the execution of a statement, such as a
+    /// variable assignment. Its type is [`CheckedType::Void`]
+    pub fn void() -> ObjectInstance {
+        ObjectInstance::new(CheckedType::Void, 0, vec![], None)
+    }
+
+    /// Is the instance the result of a statement rather than a value
+    pub fn is_void(&self) -> bool {
+        self.ty == CheckedType::Void
+    }
+
     /// Create a new instance
     pub fn new(
         ty: CheckedType,
@@ -55,12 +72,12 @@ impl ObjectInstance {
         data: Vec<u8>,
         fields: Option<Vec<(Name, ObjectInstance)>>,
     ) -> ObjectInstance {
-        let fields = fields.map(ObjectInstance::fields_vec_to_hash_map);
+        let fields = fields.map(|fields| Rc::new(ObjectInstance::fields_vec_to_hash_map(fields)));
 
         ObjectInstance {
             ty,
             size,
-            data,
+            data: data.into(),
             fields,
         }
     }
@@ -75,6 +92,14 @@ impl ObjectInstance {
         ObjectInstance::new(ty, size, data.to_vec(), fields)
     }
 
+    /// Convert the instance to a jinko type, if the instance is of that type
+    pub fn downcast<T: FromObjectInstance>(&self) -> Option<T> {
+        match &self.ty {
+            CheckedType::Resolved(ty) if ty.id() == T::type_name() => Some(T::from_instance(self)),
+            _ => None,
+        }
+    }
+
     /// Get a reference to the type of the instance
     pub fn ty(&self) -> &CheckedType {
         &self.ty
@@ -107,8 +132,8 @@ impl ObjectInstance {
         }
     }
 
-    pub fn fields(&self) -> &Option<FieldsMap> {
-        &self.fields
+    pub fn fields(&self) -> Option<&FieldsMap> {
+        self.fields.as_deref()
     }
 
     fn fields_vec_to_hash_map(vec: Vec<(Name, ObjectInstance)>) -> FieldsMap {
@@ -136,7 +161,7 @@ impl ObjectInstance {
         if let Some(fields) = &instance.fields {
             base = format!("{base}{indent}fields:\n");
 
-            for (name, FieldInstance(_, instance)) in fields {
+            for (name, FieldInstance(_, instance)) in fields.iter() {
                 base = format!(
                     "{}{}{}:\n{}",
                     base,
@@ -165,6 +190,9 @@ pub trait ToObjectInstance {
 /// as well as user defined ones
 pub trait FromObjectInstance {
     fn from_instance(i: &ObjectInstance) -> Self;
+
+    /// Name of the jinko type an instance must have in order to be converted
+    fn type_name() -> &'static str;
 }
 
 #[cfg(test)]
@@ -227,4 +255,112 @@ mod tests {
         assert_eq!(v_f_x, JkInt::from(1).to_instance());
         assert_eq!(v_f_y, JkInt::from(2).to_instance());
     }
+
+    #[test]
+    fn t_downcast() {
+        use crate::value::JkFloat;
+
+        let instance = JkInt::from(15).to_instance();
+
+        assert_eq!(
+            instance.downcast::<JkInt>().map(|i| i.rust_value()),
+            Some(15)
+        );
+        assert!(instance.downcast::<JkFloat>().is_none());
+    }
+
+    #[test]
+    fn t_eq_same_type_same_value() {
+        use crate::value::{JkBool, JkChar, JkFloat, JkString};
+
+        assert_eq!(JkInt::from(1).to_instance(), JkInt::from(1).to_instance());
+        assert_eq!(
+            JkFloat::from(1.0).to_instance(),
+            JkFloat::from(1.0).to_instance()
+        );
+        assert_eq!(
+            JkBool::from(true).to_instance(),
+            JkBool::from(true).to_instance()
+        );
+        assert_eq!(
+            JkChar::from('a').to_instance(),
+            JkChar::from('a').to_instance()
+        );
+        assert_eq!(
+            JkString::from("jinko").to_instance(),
+            JkString::from("jinko").to_instance()
+        );
+
+        assert_ne!(JkInt::from(1).to_instance(), JkInt::from(2).to_instance());
+        assert_ne!(
+            JkFloat::from(1.0).to_instance(),
+            JkFloat::from(1.5).to_instance()
+        );
+        assert_ne!(
+            JkBool::from(true).to_instance(),
+            JkBool::from(false).to_instance()
+        );
+        assert_ne!(
+            JkChar::from('a').to_instance(),
+            JkChar::from('b').to_instance()
+        );
+        assert_ne!(
+            JkString::from("jinko").to_instance(),
+            JkString::from("jinx").to_instance()
+        );
+    }
+
+    #[test]
+    fn t_eq_different_types_same_value() {
+        use crate::value::{JkBool, JkChar, JkFloat, JkString};
+
+        let int = JkInt::from(1).to_instance();
+
+        assert_ne!(int, JkFloat::from(1.0).to_instance());
+        assert_ne!(int, JkChar::from('\u{1}').to_instance());
+        assert_ne!(int, JkBool::from(true).to_instance());
+        assert_ne!(
+            JkChar::from('1').to_instance(),
+            JkString::from("1").to_instance()
+        );
+        assert_ne!(
+            JkBool::from(true).to_instance(),
+            JkChar::from('\u{1}').to_instance()
+        );
+    }
+
+    #[test]
+    fn t_eq_compares_types_of_identical_bytes() {
+        let int = JkInt::from(1).to_instance();
+        let mut char_bytes = int.clone();
+        char_bytes.set_ty(CheckedType::Resolved(crate::typechecker::TypeId::from(
+            "char",
+        )));
+
+        assert_eq!(int.data(), char_bytes.data());
+        assert_ne!(int, char_bytes);
+    }
+
+    #[test]
+    fn t_clone_shares_data() {
+        let mut ctx = setup();
+
+        let inst = constructs::expr(span!("v")).unwrap().1;
+        let v = inst.execute(&mut ctx).unwrap();
+        let copy = v.clone();
+
+        assert_eq!(v.data().as_ptr(), copy.data().as_ptr());
+        assert!(std::ptr::eq(v.fields().unwrap(), copy.fields().unwrap()));
+    }
+
+    #[test]
+    fn t_clone_set_ty() {
+        let int = JkInt::from(15).to_instance();
+        let mut copy = int.clone();
+
+        copy.set_ty(CheckedType::Void);
+
+        assert_eq!(int.ty(), &CheckedType::Resolved(crate::TypeId::from("int")));
+        assert_eq!(copy.ty(), &CheckedType::Void);
+    }
 }
@@ -6,15 +6,17 @@ use std::fmt::Debug;
 
 use crate::context::Context;
 use crate::error::{ErrKind, Error};
+use crate::generics::GenericMap;
 use crate::instance::ObjectInstance;
 use crate::location::SpanTuple;
-use crate::typechecker::TypeCheck;
+use crate::typechecker::{TypeCheck, TypeCtx};
 
 use colored::Colorize;
 use downcast_rs::{impl_downcast, Downcast};
 
 mod binary_op;
 mod block;
+mod const_declaration;
 mod dec_arg;
 mod field_access;
 mod function_call;
@@ -22,11 +24,14 @@ mod function_declaration;
 mod if_else;
 mod incl;
 mod jk_inst;
+mod jk_match;
 mod jk_return;
 mod loop_block;
 mod method_call;
 mod operator;
+mod range;
 mod rename;
+mod type_alias;
 mod type_declaration;
 mod type_instantiation;
 mod var;
@@ -34,7 +39,8 @@ mod var_assignment;
 mod var_or_empty_type;
 
 pub use binary_op::BinaryOp;
-pub use block::Block;
+pub use block::{Block, BlockBuilder};
+pub use const_declaration::ConstDec;
 pub use dec_arg::DecArg;
 pub use field_access::FieldAccess;
 pub use function_call::FunctionCall;
@@ -42,10 +48,13 @@ pub use function_declaration::{FunctionDec, FunctionKind};
 pub use if_else::IfElse;
 pub use incl::Incl;
 pub use jk_inst::{JkInst, JkInstKind};
+pub use jk_match::{Match, MatchArm};
 pub use jk_return::Return;
 pub use loop_block::{Loop, LoopKind};
 pub use method_call::MethodCall;
 pub use operator::Operator;
+pub use range::Range;
+pub use type_alias::TypeAlias;
 pub use type_declaration::TypeDec;
 pub use type_instantiation::TypeInstantiation;
 pub use var::Var;
@@ -53,9 +62,12 @@ pub use var_assignment::VarAssign;
 pub use var_or_empty_type::VarOrEmptyType;
 
 /// The type of instructions available. An Instruction either is a statement, or an
-/// expression. An expression contains an instance of a result. For example,
-/// `1 + 1` is an expression: It will contain the result of the addition of one and one.
-/// `print("jinko")` is a statement: There is no "return value"
+/// expression. For example, `1 + 1` is an expression: It will result in the addition
+/// of one and one. `print("jinko")` is a statement: There is no "return value".
+/// An expression carries its resulting instance if that instance is known without
+/// executing the instruction, meaning the instruction can be replaced by its instance
+/// without changing the behavior of the program. This is the case for literals such
+/// as `15` or `"jinko"`. Otherwise, an expression carries `None`.
 // FIXME: Make InstrKind a simpler enum
 #[derive(Debug, PartialEq, Clone)]
 pub enum InstrKind {
@@ -63,14 +75,27 @@ pub enum InstrKind {
     Expression(Option<ObjectInstance>),
 }
 
+impl InstrKind {
+    /// Drop the instance carried by an expression. This is used by instructions
+    /// which forward the kind of one of their children, but cannot be replaced by
+    /// that child's value
+    pub fn without_value(self) -> InstrKind {
+        match self {
+            InstrKind::Expression(_) => InstrKind::Expression(None),
+            InstrKind::Statement => InstrKind::Statement,
+        }
+    }
+}
+
 // FIXME: Fix documentation for execute_*()
 
 /// The `Instruction` trait is the basic trait for all of Jinko's execution nodes. Each
 /// node that can be executed needs to implement it
 pub trait Instruction: InstructionClone + Downcast + TypeCheck {
     // FIXME: Add Rename here
-    /// Execute the instruction, altering the state of the context. Executing
-    /// this method may return an object instance
+    /// Execute the instruction, altering the state of the context. Expressions return
+    /// their value, and statements return a void instance, as created by
+    /// [`ObjectInstance::void`]. `None` is returned if the execution failed
     fn execute(&self, _ctx: &mut Context) -> Option<ObjectInstance> {
         unreachable!(
             "\n{}\n --> {}",
@@ -79,14 +104,15 @@ pub trait Instruction: InstructionClone + Downcast + TypeCheck {
         )
     }
 
-    /// Execute the instruction, hoping for an instance to be returned. If no instance is
-    /// returned, error out.
+    /// Execute the instruction, hoping for an instance to be returned. If a void
+    /// instance is returned, error out. If no instance is returned, the instruction
+    /// failed and has already reported its error
     fn execute_expression(&self, ctx: &mut Context) -> Option<ObjectInstance> {
-        let instance = self.execute(ctx);
+        let instance = self.execute(ctx)?;
 
-        match instance {
-            Some(obj) => Some(obj),
-            None => {
+        match instance.is_void() {
+            false => Some(instance),
+            true => {
                 ctx.error(Error::new(ErrKind::Context).with_msg(format!(
                     "statement found when expression was expected: {}",
                     self.print()
@@ -96,14 +122,17 @@ pub trait Instruction: InstructionClone + Downcast + TypeCheck {
         }
     }
 
-    /// Execute the instruction, hoping for no instance to be returned. If an instance is
-    /// returned, error out.
+    /// Execute the instruction, hoping for a void instance to be returned. If a value
+    /// is returned, error out. If no instance is returned, the instruction failed
     // FIXME: Cleanup the return type of this function
     fn execute_statement(&self, ctx: &mut Context) -> Result<(), Error> {
         let instance = self.execute(ctx);
 
         match instance {
-            None => Ok(()),
+            // The error has already been reported by the failing instruction
+            None => Err(Error::new(ErrKind::Context)
+                .with_msg(format!("statement failed to execute: {}", self.print()))),
+            Some(obj) if obj.is_void() => Ok(()),
             Some(_) => {
                 let e = Error::new(ErrKind::Context).with_msg(format!(
                     "expression found when statement was expected: {}",
@@ -115,12 +144,48 @@ pub trait Instruction: InstructionClone + Downcast + TypeCheck {
         }
     }
 
-    /// What is the type of the instruction: a Statement or an Expression.
-    /// This method will always return Expression(None) if the instruction is an
-    /// expression. This method does not care about the return value or the execution
-    /// of the instruction, just the kind of it.
+    /// What is the type of the instruction: a Statement or an Expression. Only
+    /// constant expressions carry their instance, as described in [`InstrKind`].
+    /// This method does not execute the instruction.
     fn kind(&self) -> InstrKind;
 
+    /// Does the instruction always divert the control flow, for example by returning
+    /// from the current function? Instructions following a diverging instruction in
+    /// a block can never be executed.
+    fn diverges(&self) -> bool {
+        false
+    }
+
+    /// Sub-instructions of the instruction, in the order they appear in the source
+    /// code. This allows walking the AST without knowing the type of each node
+    fn children(&self) -> Vec<&dyn Instruction> {
+        vec![]
+    }
+
+    /// Mutable version of [`Instruction::children`]. Both methods must return the
+    /// same sub-instructions, in the same order
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        vec![]
+    }
+
+    /// Resolve the generic usages of the instruction itself, without its
+    /// sub-instructions. Most instructions cannot be generic, so this does nothing by
+    /// default.
+    ///
+    /// With a type map like the following: `{ T: int, U: string }`, a type call like
+    /// `f[T, U]()` should be resolved to `f+int+string()`
+    fn resolve_self(&mut self, _type_map: &GenericMap, _ctx: &mut TypeCtx) {}
+
+    /// Resolve the generic usages of the instruction and of all of its
+    /// sub-instructions, as given by [`Instruction::children_mut`]. Instructions
+    /// should implement [`Instruction::resolve_self`] rather than this method
+    fn resolve_usages(&mut self, type_map: &GenericMap, ctx: &mut TypeCtx) {
+        self.resolve_self(type_map, ctx);
+        self.children_mut()
+            .into_iter()
+            .for_each(|child| child.resolve_usages(type_map, ctx));
+    }
+
     /// Pretty-print the instruction to valid jinko code
     fn print(&self) -> String;
 
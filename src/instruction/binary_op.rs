@@ -4,15 +4,18 @@
 //!
 //! The available operators are `+`, `-`, `*` and `/`.
 //! That is `Add`, `Substract`, `Multiply` and `Divide`.
+//!
+//! Operators can be implemented for custom types by declaring a function named after
+//! the operator, such as `func add(lhs: Vec2, rhs: Vec2) -> Vec2`.
 
 use crate::context::Context;
 use crate::error::{ErrKind, Error};
-use crate::instance::{FromObjectInstance, ObjectInstance};
-use crate::instruction::{InstrKind, Instruction, Operator};
+use crate::instance::{ObjectInstance, ToObjectInstance};
+use crate::instruction::{FunctionCall, FunctionDec, InstrKind, Instruction, Operator};
 use crate::location::SpanTuple;
 use crate::typechecker::TypeCheck;
-use crate::typechecker::{CheckedType, TypeCtx, TypeId};
-use crate::value::{JkFloat, JkInt, Value};
+use crate::typechecker::{CheckedType, TypeCtx, TypeId, PRIMITIVE_TYPES};
+use crate::value::{JkBool, JkFloat, JkInt, Value};
 
 /// The `BinaryOp` struct contains two expressions and an operator, which can be an arithmetic
 /// or a comparison one
@@ -21,6 +24,10 @@ pub struct BinaryOp {
     lhs: Box<dyn Instruction>,
     rhs: Box<dyn Instruction>,
     op: Operator,
+    /// Call to the method implementing the operator, for operands of custom types
+    overload: Option<FunctionCall>,
+    /// Was the operation surrounded by parentheses in the source code
+    parenthesized: bool,
     cached_type: Option<CheckedType>,
     location: Option<SpanTuple>,
 }
@@ -32,6 +39,8 @@ impl BinaryOp {
             lhs,
             rhs,
             op,
+            overload: None,
+            parenthesized: false,
             cached_type: None,
             location: None,
         }
@@ -45,34 +54,213 @@ impl BinaryOp {
 
     // Get a reference on the left side member of a BinaryOp
     #[cfg(test)]
-    pub fn lhs(&self) -> &Box<dyn Instruction> {
-        &self.lhs
+    pub fn lhs(&self) -> &dyn Instruction {
+        self.lhs.as_ref()
     }
 
     /// Get a reference on the right side member of a BinaryOp
     #[cfg(test)]
-    pub fn rhs(&self) -> &Box<dyn Instruction> {
-        &self.rhs
+    pub fn rhs(&self) -> &dyn Instruction {
+        self.rhs.as_ref()
+    }
+
+    /// Value of the operation if it compares numeric constants, such as `1 < 2`,
+    /// `2.5 >= 1.0` or `LIMIT > 10` with `LIMIT` declared using `const`. Operations
+    /// which cannot be computed before executing the program return `None`
+    pub fn constant_bool(&self) -> Option<bool> {
+        let compare = |ordering: Option<std::cmp::Ordering>| {
+            let ordering = ordering?;
+
+            match self.op {
+                Operator::Lt => Some(ordering.is_lt()),
+                Operator::Gt => Some(ordering.is_gt()),
+                Operator::LtEq => Some(ordering.is_le()),
+                Operator::GtEq => Some(ordering.is_ge()),
+                Operator::Equals => Some(ordering.is_eq()),
+                Operator::NotEquals => Some(ordering.is_ne()),
+                _ => None,
+            }
+        };
+
+        let (lhs, rhs) = match (self.lhs.kind(), self.rhs.kind()) {
+            (InstrKind::Expression(Some(lhs)), InstrKind::Expression(Some(rhs))) => (lhs, rhs),
+            _ => return None,
+        };
+
+        if let (Some(l), Some(r)) = (lhs.downcast::<JkInt>(), rhs.downcast::<JkInt>()) {
+            return compare(Some(l.rust_value().cmp(&r.rust_value())));
+        }
+
+        if let (Some(l), Some(r)) = (lhs.downcast::<JkFloat>(), rhs.downcast::<JkFloat>()) {
+            // Float equality depends on the epsilon configured on the context
+            return match self.op {
+                Operator::Equals | Operator::NotEquals => None,
+                _ => compare(l.rust_value().partial_cmp(&r.rust_value())),
+            };
+        }
+
+        None
     }
 
     // FIXME: Use Context::execute_expression
     /// Execute a node of the binary operation
     fn execute_node(&self, node: &dyn Instruction, ctx: &mut Context) -> Option<ObjectInstance> {
         match node.execute(ctx) {
-            None => {
+            Some(v) if v.is_void() => {
                 ctx.error(Error::new(ErrKind::Context).with_msg(format!(
                     "invalid use of statement in binary operation: {}",
                     node.print()
                 )));
                 None
             }
+            // The error has already been emitted when executing the node
+            None => None,
             Some(v) => Some(v),
         }
     }
 
+    /// Promote the value of an integer literal to a float if the other operand is a
+    /// float. Integers which are not literals never get promoted, as this could lead
+    /// to silent loss of precision
+    fn promote_literal(
+        node: &dyn Instruction,
+        value: ObjectInstance,
+        other: &ObjectInstance,
+    ) -> ObjectInstance {
+        match (node.downcast_ref::<JkInt>(), other.ty().resolved_id()) {
+            (Some(literal), Some("float")) => {
+                JkFloat::from(literal.rust_value() as f64).to_instance()
+            }
+            _ => value,
+        }
+    }
+
+    /// Type an operand, considering integer literals as floats if the other operand
+    /// is a float
+    fn coerce_literal_type(
+        node: &dyn Instruction,
+        ty: CheckedType,
+        other: &CheckedType,
+    ) -> CheckedType {
+        let float = CheckedType::Resolved(TypeId::from("float"));
+
+        match node.downcast_ref::<JkInt>() {
+            Some(_) if *other == float => float,
+            _ => ty,
+        }
+    }
+
+    /// Resolve the method implementing the operator for a custom left hand side type.
+    /// The method must take the left hand side operand as its first argument, and the
+    /// right hand side operand as its second one
+    fn resolve_overload(
+        &mut self,
+        ctx: &mut TypeCtx,
+        l_type: &TypeId,
+        r_type: &CheckedType,
+    ) -> Result<CheckedType, Error> {
+        // Parentheses never make it into a binary operation
+        let method = self.op.method_name().unwrap();
+        let r_name = match r_type.resolved_id() {
+            Some(id) => id.to_string(),
+            None => r_type.to_string(),
+        };
+
+        let matches_operands = |function: &FunctionDec| match function.args().as_slice() {
+            [lhs, rhs] => {
+                lhs.get_type() == l_type && CheckedType::Resolved(rhs.get_type().clone()) == *r_type
+            }
+            _ => false,
+        };
+
+        if !ctx.get_function(method).is_some_and(matches_operands) {
+            return Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "no implementation of `{}` for types `{}` and `{r_name}`; expected a method `{method}(self, {r_name})`",
+                    self.op.as_str(),
+                    l_type.id(),
+                ))
+                .with_loc(self.location.clone()));
+        }
+
+        let mut call = FunctionCall::new(
+            method.to_string(),
+            vec![],
+            vec![self.lhs.clone(), self.rhs.clone()],
+        );
+        if let Some(location) = &self.location {
+            call.set_location(location.clone());
+        }
+
+        let return_type = call.type_of(ctx)?;
+        self.overload = Some(call);
+
+        Ok(return_type)
+    }
+
     pub fn set_location(&mut self, location: SpanTuple) {
         self.location = Some(location)
     }
+
+    /// Mark the operation as explicitly grouped with parentheses, such as `(1 + 2)`
+    /// in `(1 + 2) * 3`. The parentheses are then kept when printing the operation
+    pub fn set_parenthesized(&mut self) {
+        self.parenthesized = true
+    }
+
+    /// Compute the result of the operation, without emitting any debug trace
+    fn evaluate(&self, ctx: &mut Context) -> Option<ObjectInstance> {
+        if let Some(overload) = &self.overload {
+            return overload.execute(ctx);
+        }
+
+        let l_value = self.execute_node(&*self.lhs, ctx)?;
+        let r_value = self.execute_node(&*self.rhs, ctx)?;
+
+        let l_value = BinaryOp::promote_literal(&*self.lhs, l_value, &r_value);
+        let r_value = BinaryOp::promote_literal(&*self.rhs, r_value, &l_value);
+
+        // FIXME: This produces unhelpful errors for now
+        if l_value.ty() != r_value.ty() {
+            return None;
+        }
+
+        // At this point, we will already have checked whether or not a binary op
+        // is valid type-wise. If a type is still unknown at this point, this is an
+        // interpreter error, which gets reported instead of crashing the interpreter
+        let return_value = if let (Some(lhs), Some(rhs)) =
+            (l_value.downcast::<JkInt>(), r_value.downcast::<JkInt>())
+        {
+            lhs.do_op_with_mode(&rhs, self.op, ctx.arithmetic_mode())
+        } else if let (Some(lhs), Some(rhs)) =
+            (l_value.downcast::<JkFloat>(), r_value.downcast::<JkFloat>())
+        {
+            lhs.do_op_with_epsilon(&rhs, self.op, ctx.float_epsilon())
+        } else if let (Some(lhs), Some(rhs)) =
+            (l_value.downcast::<JkBool>(), r_value.downcast::<JkBool>())
+        {
+            lhs.do_op(&rhs, self.op)
+        } else {
+            ctx.error(
+                Error::new(ErrKind::Context)
+                    .with_msg(format!(
+                        "interpreter error: binary operation `{}` with operands of unresolved type `{}`",
+                        self.print(),
+                        l_value.ty()
+                    ))
+                    .with_loc(self.location.clone()),
+            );
+            return None;
+        };
+
+        match return_value {
+            Ok(value) => Some(value),
+            Err(e) => {
+                ctx.error(e.with_loc(self.location.clone()));
+                None
+            }
+        }
+    }
 }
 
 impl Instruction for BinaryOp {
@@ -80,51 +268,43 @@ impl Instruction for BinaryOp {
         InstrKind::Expression(None)
     }
 
+    fn children(&self) -> Vec<&dyn Instruction> {
+        vec![self.lhs.as_ref(), self.rhs.as_ref()]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        vec![self.lhs.as_mut(), self.rhs.as_mut()]
+    }
+
     fn print(&self) -> String {
-        format!(
+        let op = format!(
             "{} {} {}",
             self.lhs.print(),
             self.op.as_str(),
             self.rhs.print()
-        )
+        );
+
+        match self.parenthesized {
+            true => format!("({op})"),
+            false => op,
+        }
     }
 
     fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
-        let l_value = self.execute_node(&*self.lhs, ctx)?;
-        let r_value = self.execute_node(&*self.rhs, ctx)?;
-
-        // FIXME: This produces unhelpful errors for now
-        if l_value.ty() != r_value.ty() {
-            return None;
+        // Printing the operation is costly, so only do it when it gets displayed
+        if ctx.debug_mode {
+            ctx.debug("BINOP ENTER", &self.print());
         }
+        ctx.eval_enter();
 
-        let return_value;
+        let value = self.evaluate(ctx);
 
-        // At this point, we will already have checked whether or not a binary op
-        // is valid type-wise. So we can unwrap at will. If a type is still unknown
-        // at this point, this is an interpreter error
-        match l_value.ty() {
-            CheckedType::Resolved(ty) => match ty.id() {
-                "int" => {
-                    return_value = JkInt::from_instance(&l_value)
-                        .do_op(&JkInt::from_instance(&r_value), self.op)
-                        .unwrap();
-                }
-                "float" => {
-                    return_value = JkFloat::from_instance(&l_value)
-                        .do_op(&JkFloat::from_instance(&r_value), self.op)
-                        .unwrap();
-                }
-                _ => unreachable!(
-                    "attempting binary operation with void type or unknown type AFTER typechecking"
-                ),
-            },
-            _ => unreachable!(
-                "attempting binary operation with void type or unknown type AFTER typechecking"
-            ),
+        ctx.eval_exit();
+        if ctx.debug_mode {
+            ctx.debug("BINOP EXIT", &self.print());
         }
 
-        Some(return_value)
+        value
     }
 
     fn location(&self) -> Option<&SpanTuple> {
@@ -140,7 +320,50 @@ impl TypeCheck for BinaryOp {
         // Unpacking after checking rhs to give maximum feedback to the user
         let l_type = l_type?;
 
-        if l_type != r_type {
+        // One of the operands cannot be typechecked yet: Defer the check instead of
+        // erroring out on a type mismatch
+        if l_type == CheckedType::Later || r_type == CheckedType::Later {
+            return Ok(CheckedType::Later);
+        }
+
+        if self.op.is_bitwise() {
+            let int = CheckedType::Resolved(TypeId::from("int"));
+
+            return match l_type == int && r_type == int {
+                true => Ok(int),
+                false => Err(Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!(
+                        "bitwise operations are only available on `int`s: found {} {} {}",
+                        l_type,
+                        self.op.as_str(),
+                        r_type,
+                    ))
+                    .with_loc(self.location.clone())),
+            };
+        }
+
+        if let CheckedType::Resolved(ty) = &l_type {
+            if !PRIMITIVE_TYPES.contains(&ty.id()) {
+                return self.resolve_overload(ctx, ty, &r_type);
+            }
+        }
+
+        let l_type = BinaryOp::coerce_literal_type(&*self.lhs, l_type, &r_type);
+        let r_type = BinaryOp::coerce_literal_type(&*self.rhs, r_type, &l_type);
+
+        let is_equality = matches!(self.op, Operator::Equals | Operator::NotEquals);
+        if l_type == CheckedType::Resolved(TypeId::from("bool")) && !is_equality {
+            return Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "`bool`s can only be compared for equality: found {} {} {}",
+                    l_type,
+                    self.op.as_str(),
+                    r_type,
+                ))
+                .with_loc(self.location.clone()));
+        }
+
+        if !r_type.is_assignable_to(&l_type) {
             Err(Error::new(ErrKind::TypeChecker)
                 .with_msg(format!(
                     "trying to do binary operation on invalid types: {} {} {}",
@@ -181,7 +404,7 @@ mod tests {
     use crate::instance::ToObjectInstance;
     use crate::location::Source;
     use crate::value::JkInt;
-    use crate::{jinko, jinko_fail};
+    use crate::{jinko, jinko_fail, span};
 
     #[test]
     fn t_binop_rhs_execute() {
@@ -205,6 +428,83 @@ mod tests {
         assert!(!i.error_handler.has_errors());
     }
 
+    #[test]
+    fn t_binop_debug_trace() {
+        use crate::utils::SharedBuffer;
+
+        let binary_op = BinaryOp::new(
+            Box::new(JkInt::from(4)),
+            Box::new(JkInt::from(5)),
+            Operator::new("+"),
+        );
+
+        let trace = SharedBuffer::default();
+        let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
+        i.debug_mode = true;
+        i.set_debug_writer(trace.clone());
+
+        binary_op.execute(&mut i).unwrap();
+
+        let trace = trace.contents();
+        assert!(trace.contains("BINOP ENTER"));
+        assert!(trace.contains("BINOP EXIT"));
+        assert!(trace.contains("4 + 5"));
+    }
+
+    #[test]
+    fn t_binop_debug_trace_nesting() {
+        use crate::utils::SharedBuffer;
+
+        let binary_op = BinaryOp::new(
+            Box::new(JkInt::from(1)),
+            Box::new(BinaryOp::new(
+                Box::new(JkInt::from(2)),
+                Box::new(JkInt::from(3)),
+                Operator::new("*"),
+            )),
+            Operator::new("+"),
+        );
+
+        let trace = SharedBuffer::default();
+        let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
+        i.debug_mode = true;
+        i.set_debug_writer(trace.clone());
+
+        binary_op.execute(&mut i).unwrap();
+
+        let trace = trace.contents();
+        let indent_of = |step: &str| {
+            let line = trace.lines().find(|line| line.contains(step)).unwrap();
+            line.len() - line.trim_start().len()
+        };
+
+        assert_eq!(trace.lines().count(), 4);
+        assert!(indent_of(": 2 * 3") > indent_of(": 1 + 2 * 3"));
+        assert_eq!(
+            trace.lines().last().map(|line| line.trim_start() == line),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn t_binop_no_debug_trace_outside_debug_mode() {
+        use crate::utils::SharedBuffer;
+
+        let binary_op = BinaryOp::new(
+            Box::new(JkInt::from(4)),
+            Box::new(JkInt::from(5)),
+            Operator::new("+"),
+        );
+
+        let trace = SharedBuffer::default();
+        let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
+        i.set_debug_writer(trace.clone());
+
+        binary_op.execute(&mut i).unwrap();
+
+        assert!(trace.contents().is_empty());
+    }
+
     #[test]
     fn t_binop_lhs_execute() {
         let l_bin = BinaryOp::new(
@@ -264,7 +564,52 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
+    fn comparison_simple_bool() {
+        assert_bool("true == false", false);
+        assert_bool("true != false", true);
+    }
+
+    #[test]
+    fn t_binop_bool_arithmetic() {
+        for input in ["true + false", "true < false"] {
+            let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+            crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+
+            // The operation is rejected before anything gets executed
+            assert!(ctx.check().is_err());
+
+            let err = &ctx.error_handler.errors()[0];
+            assert_eq!(err.kind(), ErrKind::TypeChecker);
+            assert!(err
+                .msg()
+                .unwrap()
+                .contains("can only be compared for equality"));
+        }
+    }
+
+    #[test]
+    fn t_binop_unresolved_type_is_an_error() {
+        use crate::instance::ObjectInstance;
+        use crate::instruction::Var;
+        use crate::typechecker::CheckedType;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        let mut unresolved = Var::new(String::from("unresolved"));
+        unresolved.set_instance(ObjectInstance::new(CheckedType::Later, 0, vec![], None));
+        ctx.add_variable(unresolved).unwrap();
+
+        let binop = BinaryOp::new(
+            Box::new(Var::new(String::from("unresolved"))),
+            Box::new(Var::new(String::from("unresolved"))),
+            Operator::new("+"),
+        );
+
+        assert!(binop.execute(&mut ctx).is_none());
+        assert_eq!(ctx.error_handler.errors()[0].kind(), ErrKind::Context);
+    }
+
+    #[test]
     fn comparison_precedence() {
         assert_bool(
             "1 + 4 * 2 - 1 + 2 * (14 + (2 - 17) * 1) - 12 + 3 / 2 < 45",
@@ -288,6 +633,33 @@ mod tests {
         };
     }
 
+    #[test]
+    fn t_binop_int_literal_coercion() {
+        use crate::value::JkFloat;
+
+        let ctx = jinko! {
+            a = 1 + 1.5;
+            b = 2.5 * 2;
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkFloat::from(2.5).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"b".into()).unwrap().instance(),
+            JkFloat::from(5.0).to_instance()
+        );
+    }
+
+    #[test]
+    fn tc_binop_int_variable_no_coercion() {
+        jinko_fail! {
+            a = 1;
+            b = a + 1.5;
+        };
+    }
+
     #[test]
     fn tc_binop_mismatched_valid() {
         jinko_fail! {
@@ -296,6 +668,85 @@ mod tests {
         };
     }
 
+    #[test]
+    fn tc_binop_forward_referenced_call() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        let value = ctx
+            .eval("a = twice(2) + 1; func twice(x: int) -> int { x * 2 } a")
+            .unwrap();
+
+        assert_eq!(value, Some(JkInt::from(5).to_instance()));
+    }
+
+    #[test]
+    fn tc_binop_mismatched_error_message() {
+        let ctx = jinko_fail! {
+            t0 = 1 + '4';
+        };
+
+        let errors = ctx.error_handler.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), ErrKind::TypeChecker);
+
+        let msg = errors[0].msg().unwrap();
+        assert!(msg.contains("int"));
+        assert!(msg.contains("char"));
+    }
+
+    #[test]
+    fn tc_binop_missing_overload() {
+        let ctx = jinko_fail! {
+            type Vec2(x: int, y: int);
+            v1 = Vec2(x: 1, y: 2);
+            v2 = Vec2(x: 3, y: 4);
+            v1 + v2
+        };
+
+        let errors = ctx.error_handler.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].msg().unwrap(),
+            "no implementation of `+` for types `Vec2` and `Vec2`; expected a method `add(self, Vec2)`"
+        );
+    }
+
+    #[test]
+    fn tc_binop_overload_wrong_operand_type() {
+        jinko_fail! {
+            type Vec2(x: int, y: int);
+            func add(lhs: Vec2, rhs: int) -> int { rhs }
+            v1 = Vec2(x: 1, y: 2);
+            v1 + v1
+        };
+    }
+
+    #[test]
+    fn t_binop_overload() {
+        let ctx = jinko! {
+            type Vec2(x: int, y: int);
+            func add(lhs: Vec2, rhs: Vec2) -> Vec2 {
+                Vec2(x: lhs.x + rhs.x, y: lhs.y + rhs.y)
+            }
+            func eq(lhs: Vec2, rhs: Vec2) -> bool {
+                lhs.x == rhs.x
+            }
+
+            v = Vec2(x: 1, y: 2) + Vec2(x: 3, y: 4);
+            x = v.x;
+            y = v.y;
+            same = v == v;
+        };
+
+        let value = |name: &str| ctx.get_variable(&name.into()).unwrap().instance();
+        assert_eq!(value("x"), JkInt::from(4).to_instance());
+        assert_eq!(value("y"), JkInt::from(6).to_instance());
+        assert_eq!(
+            value("same"),
+            crate::value::JkBool::from(true).to_instance()
+        );
+    }
+
     macro_rules! binop_assert {
         ($expr:expr) => {{
             let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
@@ -412,4 +863,136 @@ mod tests {
     fn binop_execute_valid_extremely_complex_expr() {
         binop_assert!(1 + 4 * 2 - 1 + 2 * (14 + (2 - 17)) - 12 + 3 / 2);
     }
+
+    #[test]
+    fn t_binop_bit_and() {
+        binop_assert!(6 & 3);
+    }
+
+    #[test]
+    fn t_binop_bit_or() {
+        binop_assert!(4 | 1);
+    }
+
+    #[test]
+    fn t_binop_bit_xor() {
+        binop_assert!(5 ^ 1);
+    }
+
+    #[test]
+    fn t_binop_mixed_bitwise() {
+        binop_assert!(6 ^ 3 & 5 | 8);
+        binop_assert!(1 | 6 ^ 3);
+    }
+
+    #[test]
+    fn t_binop_bitwise_precedence() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let expr = crate::parser::constructs::expr(span!("1 + 6 & 3 * 2"))
+            .unwrap()
+            .1;
+
+        assert_eq!(
+            expr.execute(&mut ctx).unwrap(),
+            JkInt::from(6).to_instance()
+        );
+        assert_bool("6 & 3 == 2", true);
+    }
+
+    #[test]
+    fn tc_binop_bitwise_int() {
+        jinko! {
+            flags = 6 & 3 | 8 ^ 1;
+        };
+    }
+
+    #[test]
+    fn tc_binop_bitwise_float() {
+        jinko_fail! {
+            flags = 1.0 & 2.0;
+        };
+    }
+
+    #[test]
+    fn tc_binop_bitwise_bool() {
+        jinko_fail! {
+            flags = true | false;
+        };
+    }
+
+    #[test]
+    fn tc_binop_bitwise_mixed() {
+        jinko_fail! {
+            flags = 1 ^ 2.0;
+        };
+    }
+
+    #[test]
+    fn t_binop_shifts() {
+        binop_assert!(1 << 4);
+        binop_assert!(256 >> 2);
+        binop_assert!(-256 >> 2);
+        assert_bool("1 + 1 << 2 == 8", true);
+    }
+
+    #[test]
+    fn t_binop_shift_out_of_range() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert!(ctx.eval("1 << 64").is_err());
+        assert_eq!(ctx.error_handler.errors()[0].kind(), ErrKind::Context);
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        assert!(ctx.eval("16 >> (0 - 1)").is_err());
+    }
+
+    #[test]
+    fn tc_binop_shift_float() {
+        jinko_fail! {
+            shifted = 1.0 << 2;
+        };
+    }
+
+    #[test]
+    fn children() {
+        fn count_nodes(instruction: &dyn Instruction) -> usize {
+            1 + instruction
+                .children()
+                .into_iter()
+                .map(count_nodes)
+                .sum::<usize>()
+        }
+
+        let expr = crate::parser::constructs::expr(span!("1 + (2 * 3)"))
+            .unwrap()
+            .1;
+
+        let binop = expr.downcast_ref::<BinaryOp>().unwrap();
+        let children = binop.children();
+
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].print(), "1");
+        assert_eq!(children[1].print(), "(2 * 3)");
+        assert_eq!(count_nodes(expr.as_ref()), 5);
+    }
+
+    #[test]
+    fn t_binop_generic_call_operand() {
+        let ctx = jinko! {
+            func gen_call[T]() -> int { 41 }
+            func add_one[T]() -> int { 1 + gen_call[T]() }
+            top_level = 1 + gen_call[int]();
+            in_generic = add_one[int]();
+        };
+
+        let expected = JkInt::from(42).to_instance();
+        assert_eq!(
+            ctx.get_variable(&"top_level".into()).unwrap().instance(),
+            expected
+        );
+        assert_eq!(
+            ctx.get_variable(&"in_generic".into()).unwrap().instance(),
+            expected
+        );
+    }
 }
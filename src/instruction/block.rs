@@ -18,27 +18,82 @@
 //! Otherwise, it's `void`
 
 use crate::context::Context;
-use crate::error::Error;
+use crate::error::{ErrKind, Error};
 use crate::instance::ObjectInstance;
-use crate::instruction::{InstrKind, Instruction};
+use crate::instruction::{FunctionDec, IfElse, InstrKind, Instruction};
 use crate::location::SpanTuple;
 use crate::typechecker::{CheckedType, TypeCheck, TypeCtx};
 
 #[derive(Clone, Default)]
 pub struct Block {
     instructions: Vec<Box<dyn Instruction>>,
-    is_statement: bool,
+    /// Is the value of the last instruction discarded, such as in `{ a = 1; a; }`
+    discard_last: bool,
     cached_type: Option<CheckedType>,
     location: Option<SpanTuple>,
 }
 
+/// Builder for [`Block`]s, useful when creating blocks from host code rather than
+/// from the parser
+/// ```
+/// use jinko::instruction::{Block, Var};
+///
+/// let block = Block::builder()
+///     .instruction(Box::new(Var::new(String::from("x"))))
+///     .build();
+///
+/// assert!(!block.is_statement());
+/// ```
+pub struct BlockBuilder {
+    block: Block,
+}
+
+impl BlockBuilder {
+    /// Add an instruction at the end of the block's instructions
+    pub fn instruction(mut self, instruction: Box<dyn Instruction>) -> BlockBuilder {
+        self.block.add_instruction(instruction);
+        self
+    }
+
+    /// Add a set of instructions at the end of the block's instructions
+    pub fn instructions(mut self, instructions: Vec<Box<dyn Instruction>>) -> BlockBuilder {
+        self.block.add_instructions(instructions);
+        self
+    }
+
+    /// Discard the value of the block's last instruction, as a trailing semicolon
+    /// does. By default, blocks ending with an expression return its value
+    pub fn statement(mut self, is_statement: bool) -> BlockBuilder {
+        self.block.set_statement(is_statement);
+        self
+    }
+
+    /// Set the location of the block
+    pub fn location(mut self, location: SpanTuple) -> BlockBuilder {
+        self.block.set_location(location);
+        self
+    }
+
+    /// Create the block
+    pub fn build(self) -> Block {
+        self.block
+    }
+}
+
 impl Block {
+    /// Create a builder for a new, empty block
+    pub fn builder() -> BlockBuilder {
+        BlockBuilder {
+            block: Block::new(),
+        }
+    }
+
     /// Create a new block
     pub fn new() -> Block {
         // FIXME: Remove this method
         Block {
             instructions: Vec::new(),
-            is_statement: true,
+            discard_last: false,
             cached_type: None,
             location: None,
         }
@@ -49,6 +104,11 @@ impl Block {
         &self.instructions
     }
 
+    /// Iterate over the instructions contained in the block, in source order
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Instruction> {
+        self.instructions.iter().map(|instr| instr.as_ref())
+    }
+
     /// Gives a set of instructions to the block
     pub fn set_instructions(&mut self, instructions: Vec<Box<dyn Instruction>>) {
         self.instructions = instructions;
@@ -74,25 +134,182 @@ impl Block {
         self.instructions.pop()
     }
 
-    /// Set block is_statement to given value
+    /// Return the last instruction of the block, whether it is an expression or a
+    /// statement
+    pub fn last_instruction(&self) -> Option<&dyn Instruction> {
+        self.instructions.last().map(|inst| inst.as_ref())
+    }
+
+    /// Does the block produce the value of its last instruction. This is the case if
+    /// that instruction is an expression whose value is not discarded by a trailing
+    /// semicolon
+    pub fn last_is_expression(&self) -> bool {
+        !self.discard_last
+            && self
+                .last_instruction()
+                .is_some_and(|last| matches!(last.kind(), InstrKind::Expression(_)))
+    }
+
+    /// Does the block end with a statement, in which case it does not produce a value
+    pub fn is_statement(&self) -> bool {
+        !self.last_is_expression()
+    }
+
+    /// Return the expression giving the block its value, if any. Blocks ending with a
+    /// statement, such as `{ a = 1; }`, do not have a final expression
+    pub fn final_expression(&self) -> Option<&dyn Instruction> {
+        match self.last_is_expression() {
+            true => self.last_instruction(),
+            false => None,
+        }
+    }
+
+    /// Discard the value of the block's last instruction or not, as a trailing
+    /// semicolon does
     pub fn set_statement(&mut self, is_statement: bool) {
-        self.is_statement = is_statement;
+        self.discard_last = is_statement;
     }
 
     /// Set block's location
     pub fn set_location(&mut self, location: SpanTuple) {
         self.location = Some(location)
     }
+
+    /// Execute the instructions of the block in order, in the current scope. The
+    /// instructions are borrowed, and the values produced by all instructions but the
+    /// last one are dropped as soon as they are produced. Execution stops early if a
+    /// `return` is executed, in which case its value is returned, or if an instruction
+    /// fails, in which case the block fails as well
+    pub fn execute_statements(&self, ctx: &mut Context) -> Option<ObjectInstance> {
+        // Functions are declared before any other instruction, so that they can be
+        // called from anywhere in the block, just like when typechecking it
+        let is_function = |inst: &dyn Instruction| inst.downcast_ref::<FunctionDec>().is_some();
+        self.instructions
+            .iter()
+            .filter(|inst| is_function(inst.as_ref()))
+            .for_each(|function| {
+                function.execute(ctx);
+            });
+
+        // Empty blocks produce a void value
+        let mut ret_val = Some(ObjectInstance::void());
+        for inst in self.instructions.iter() {
+            ret_val = match is_function(inst.as_ref()) {
+                true => Some(ObjectInstance::void()),
+                false => inst.execute(ctx),
+            };
+
+            // A `return` was executed: Stop executing the block and let the value
+            // bubble up to the enclosing function
+            if ctx.is_returning() || ret_val.is_none() {
+                break;
+            }
+        }
+
+        ret_val
+    }
+
+    /// Typecheck the instructions of the block in order, in the current scope, and
+    /// return the type of the block. This is the typechecking equivalent of
+    /// [`Block::execute_statements`]
+    pub fn resolve_statements_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        ctx.enter_block();
+
+        self.instructions
+            .iter_mut()
+            .filter_map(|inst| inst.downcast_mut::<FunctionDec>())
+            .for_each(|function| function.hoist(ctx));
+
+        let types: Vec<CheckedType> = self
+            .instructions
+            .iter_mut()
+            .map(|inst| match inst.type_of(ctx) {
+                // Aggregate
+                Err(e) => {
+                    ctx.error(e);
+                    CheckedType::Error
+                }
+                Ok(t) => t,
+            })
+            .collect();
+
+        ctx.exit_block();
+
+        // In strict mode, the value of each expression must be used, unless it is the
+        // block's tail expression and thus gives the block its value
+        if ctx.is_strict() {
+            let used = usize::from(self.last_is_expression());
+            let unused = self.instructions.iter().zip(types.iter()).rev().skip(used);
+            for (inst, ty) in unused {
+                if let CheckedType::Resolved(_) = ty {
+                    ctx.error(
+                        Error::new(ErrKind::TypeChecker)
+                            .with_msg(format!("unused value of type {ty}: `{}`", inst.print()))
+                            .with_loc(inst.location().cloned()),
+                    );
+                }
+            }
+        }
+
+        let last_type = types.last().cloned().unwrap_or(CheckedType::Void);
+
+        let mut instructions = self.instructions.iter();
+        if let Some(diverging) = instructions.find(|inst| inst.diverges()) {
+            if let Some(unreachable) = instructions.next() {
+                ctx.error(
+                    Error::new(ErrKind::TypeChecker)
+                        .with_msg(format!("unreachable code: `{}`", unreachable.print()))
+                        .with_loc(unreachable.location().cloned())
+                        .with_hint(
+                            Error::hint()
+                                .with_msg(String::from(
+                                    "any code following this instruction is unreachable",
+                                ))
+                                .with_loc(diverging.location().cloned()),
+                        ),
+                );
+            }
+        }
+
+        // `if`s whose condition is constant are replaced by the branch they would
+        // execute. This is done once all diagnostics have been emitted, so that dead
+        // branches are still typechecked
+        self.instructions.iter_mut().for_each(|inst| {
+            if let Some(branch) = inst.downcast_mut::<IfElse>().and_then(IfElse::fold) {
+                *inst = Box::new(branch);
+            }
+        });
+
+        match self.last_is_expression() {
+            true => Ok(last_type),
+            false => Ok(CheckedType::Void),
+        }
+    }
 }
 
 impl Instruction for Block {
     fn kind(&self) -> InstrKind {
-        match self.is_statement {
-            true => InstrKind::Statement,
-            false => self.instructions.last().unwrap().kind(),
+        match self.final_expression() {
+            Some(last) => last.kind().without_value(),
+            None => InstrKind::Statement,
         }
     }
 
+    fn children(&self) -> Vec<&dyn Instruction> {
+        self.iter().collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        self.instructions
+            .iter_mut()
+            .map(|instr| instr.as_mut())
+            .collect()
+    }
+
+    fn diverges(&self) -> bool {
+        self.instructions.iter().any(|inst| inst.diverges())
+    }
+
     fn print(&self) -> String {
         let mut base = String::from("{\n");
 
@@ -110,17 +327,14 @@ impl Instruction for Block {
     fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
         ctx.scope_enter();
 
-        let ret_val = self
-            .instructions
-            .iter()
-            .map(|inst| inst.execute(ctx))
-            .last();
+        let ret_val = self.execute_statements(ctx);
 
         ctx.scope_exit();
 
-        match self.is_statement {
-            false => ret_val.flatten(),
-            true => None,
+        match (self.last_is_expression(), ctx.is_returning()) {
+            (true, _) | (_, true) => ret_val,
+            // The value of a statement is discarded, but not its failure
+            (false, false) => ret_val.map(|_| ObjectInstance::void()),
         }
     }
 
@@ -131,24 +345,13 @@ impl Instruction for Block {
 
 impl TypeCheck for Block {
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
-        let last_type = self
-            .instructions
-            .iter_mut()
-            .map(|inst| match inst.type_of(ctx) {
-                // Aggregate
-                Err(e) => {
-                    ctx.error(e);
-                    CheckedType::Error
-                }
-                Ok(t) => t,
-            })
-            .last()
-            .unwrap_or(CheckedType::Void);
+        // Variables and types declared in the block do not outlive it, just like when
+        // executing it
+        ctx.scope_enter();
+        let ty = self.resolve_statements_type(ctx);
+        ctx.scope_exit();
 
-        match &self.is_statement {
-            true => Ok(CheckedType::Void),
-            false => Ok(last_type),
-        }
+        ty
     }
 
     fn set_cached_type(&mut self, ty: CheckedType) {
@@ -164,10 +367,164 @@ impl TypeCheck for Block {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::instance::ToObjectInstance;
     use crate::instruction::{Var, VarAssign};
     use crate::value::JkInt;
     use crate::{jinko, jinko_fail, span};
 
+    fn execute_both_ways(input: &str) -> (Option<ObjectInstance>, Option<ObjectInstance>) {
+        let input = nom_locate::LocatedSpan::new_extra(input, crate::location::Source::Empty);
+        let block = crate::parser::constructs::expr(input).unwrap().1;
+        let block = block.downcast_ref::<Block>().unwrap();
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let executed = block.execute(&mut ctx);
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.scope_enter();
+        let streamed = block.execute_statements(&mut ctx);
+        ctx.scope_exit();
+
+        (executed, streamed)
+    }
+
+    #[test]
+    fn execute_statements_same_value() {
+        let (executed, streamed) =
+            execute_both_ways("{ mut x = 0; x = x + 1; x = x * 4; y = x - 1; y * 2 }");
+
+        assert_eq!(executed, Some(JkInt::from(6).to_instance()));
+        assert_eq!(executed, streamed);
+    }
+
+    #[test]
+    fn execute_statements_many_statements() {
+        let mut input = String::from("{ mut x = 0;");
+        (0..100).for_each(|_| input.push_str(" x = x + 1;"));
+        input.push_str(" x }");
+
+        let (executed, streamed) = execute_both_ways(&input);
+
+        assert_eq!(executed, Some(JkInt::from(100).to_instance()));
+        assert_eq!(executed, streamed);
+    }
+
+    #[test]
+    fn builder_same_as_imperative() {
+        let instructions = || -> Vec<Box<dyn Instruction>> {
+            vec![
+                Box::new(VarAssign::new(
+                    true,
+                    String::from("a"),
+                    Box::new(JkInt::from(15)),
+                )),
+                Box::new(Var::new(String::from("a"))),
+            ]
+        };
+
+        let mut imperative = Block::new();
+        imperative.set_instructions(instructions());
+
+        let built = Block::builder()
+            .instructions(instructions())
+            .instruction(Box::new(JkInt::from(4)))
+            .build();
+        imperative.add_instruction(Box::new(JkInt::from(4)));
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let imperative_value = imperative.execute(&mut ctx);
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let built_value = built.execute(&mut ctx);
+
+        assert_eq!(built.print(), imperative.print());
+        assert_eq!(built.kind(), imperative.kind());
+        assert_eq!(built_value, imperative_value);
+        assert_eq!(built_value, Some(JkInt::from(4).to_instance()));
+    }
+
+    #[test]
+    fn builder_defaults_to_statement() {
+        let block = Block::builder().build();
+
+        assert_eq!(block.kind(), InstrKind::Statement);
+    }
+
+    #[test]
+    fn iter() {
+        let block = Block::builder()
+            .instruction(Box::new(JkInt::from(1)))
+            .instruction(Box::new(JkInt::from(2)))
+            .instruction(Box::new(JkInt::from(3)))
+            .build();
+
+        let printed: Vec<String> = block.iter().map(|instr| instr.print()).collect();
+
+        assert_eq!(block.iter().count(), 3);
+        assert_eq!(printed, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn final_expression() {
+        let value = crate::parser::constructs::expr(span!("{ a = 1; a }"))
+            .unwrap()
+            .1;
+        let value = value.downcast_ref::<Block>().unwrap();
+
+        assert!(!value.is_statement());
+        assert_eq!(value.last_instruction().unwrap().print(), "a");
+        assert_eq!(value.final_expression().unwrap().print(), "a");
+
+        let statement = crate::parser::constructs::expr(span!("{ a = 1; a; }"))
+            .unwrap()
+            .1;
+        let statement = statement.downcast_ref::<Block>().unwrap();
+
+        assert!(statement.is_statement());
+        assert_eq!(statement.last_instruction().unwrap().print(), "a");
+        assert!(statement.final_expression().is_none());
+
+        let empty = Block::builder().build();
+
+        assert!(empty.final_expression().is_none());
+        assert_eq!(empty.kind(), InstrKind::Statement);
+    }
+
+    #[test]
+    fn last_is_expression() {
+        let value = Block::builder()
+            .instruction(Box::new(VarAssign::new(
+                false,
+                String::from("a"),
+                Box::new(JkInt::from(1)),
+            )))
+            .instruction(Box::new(JkInt::from(42)))
+            .build();
+
+        assert!(value.last_is_expression());
+        assert_eq!(value.kind(), InstrKind::Expression(None));
+
+        let statement = Block::builder()
+            .instruction(Box::new(JkInt::from(42)))
+            .instruction(Box::new(VarAssign::new(
+                false,
+                String::from("a"),
+                Box::new(JkInt::from(1)),
+            )))
+            .build();
+
+        assert!(!statement.last_is_expression());
+        assert_eq!(statement.kind(), InstrKind::Statement);
+
+        let discarded = Block::builder()
+            .instruction(Box::new(JkInt::from(42)))
+            .statement(true)
+            .build();
+
+        assert!(!discarded.last_is_expression());
+        assert_eq!(discarded.kind(), InstrKind::Statement);
+    }
+
     #[test]
     fn empty() {
         let b = Block::new();
@@ -185,7 +542,6 @@ mod tests {
         ];
 
         b.set_instructions(instrs);
-        b.set_statement(false);
 
         assert_eq!(b.kind(), InstrKind::Expression(None));
     }
@@ -202,7 +558,6 @@ mod tests {
 
         b.set_instructions(instrs);
         b.add_instruction(last);
-        b.set_statement(false);
 
         assert_eq!(b.kind(), InstrKind::Expression(None));
     }
@@ -213,7 +568,7 @@ mod tests {
 
         let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
 
-        assert_eq!(b.execute(&mut i), None);
+        assert!(b.execute(&mut i).unwrap().is_void());
         assert!(!i.error_handler.has_errors());
     }
 
@@ -234,10 +589,52 @@ mod tests {
 
         let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
 
-        assert_eq!(b.execute(&mut i), None);
+        assert!(b.execute(&mut i).unwrap().is_void());
         assert!(!i.error_handler.has_errors());
     }
 
+    #[test]
+    fn block_execute_failed_statement() {
+        let b = Block::builder()
+            .instruction(Box::new(Var::new(String::from("undeclared"))))
+            .instruction(Box::new(VarAssign::new(
+                false,
+                String::from("a"),
+                Box::new(JkInt::from(15)),
+            )))
+            .build();
+
+        let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert!(b.execute(&mut i).is_none());
+        assert!(i.error_handler.has_errors());
+    }
+
+    #[test]
+    fn failed_statement_is_an_error() {
+        let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let statement = Block::builder()
+            .instruction(Box::new(Var::new(String::from("undeclared"))))
+            .statement(true)
+            .build();
+
+        assert!(Block::new().execute_statement(&mut i).is_ok());
+        assert!(statement.execute_statement(&mut i).is_err());
+    }
+
+    #[test]
+    fn failed_expression_is_reported_once() {
+        let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let assign = VarAssign::new(
+            false,
+            String::from("a"),
+            Box::new(Var::new(String::from("undeclared"))),
+        );
+
+        assert!(assign.execute(&mut i).is_none());
+        assert_eq!(i.error_handler.errors().len(), 1);
+    }
+
     #[test]
     fn block_execute_with_last() {
         use crate::instance::ToObjectInstance;
@@ -250,7 +647,6 @@ mod tests {
 
         let last = Box::new(JkInt::from(18));
         b.add_instruction(last);
-        b.set_statement(false);
 
         let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
 
@@ -282,6 +678,76 @@ mod tests {
         };
     }
 
+    #[test]
+    fn tc_unreachable_after_diverging_if() {
+        jinko_fail! {
+            func f(b: bool) -> int {
+                if b { return 1 } else { return 2 };
+                3
+            }
+        };
+    }
+
+    #[test]
+    fn tc_reachable_after_non_diverging_if() {
+        jinko! {
+            func f(b: bool) -> int {
+                if b { return 1; };
+                3
+            }
+        };
+    }
+
+    #[test]
+    fn tc_unreachable_after_loop() {
+        jinko_fail! {
+            func f() -> int {
+                loop {};
+                3
+            }
+        };
+    }
+
+    fn strict_check(input: &str, strict: bool) -> Result<(), Error> {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.init_stdlib().unwrap();
+        ctx.set_strict(strict);
+
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+
+        ctx.check()
+    }
+
+    #[test]
+    fn tc_unused_value_strict() {
+        let input = "func one() -> int { 1 } one(); x = 2";
+
+        assert!(strict_check(input, false).is_ok());
+        assert!(strict_check(input, true).is_err());
+    }
+
+    #[test]
+    fn tc_unused_last_value_strict() {
+        let input = "func two() -> int { 2 } { two(); }";
+
+        assert!(strict_check(input, false).is_ok());
+        assert!(strict_check(input, true).is_err());
+    }
+
+    #[test]
+    fn tc_discarded_value_strict() {
+        let input = "func one() -> int { 1 } _ = one(); x = 2";
+
+        assert!(strict_check(input, true).is_ok());
+    }
+
+    #[test]
+    fn tc_used_values_strict() {
+        let input = "func one() -> int { 1 } x = one(); x";
+
+        assert!(strict_check(input, true).is_ok());
+    }
+
     #[test]
     fn tc_block_invalid() {
         jinko_fail! {
@@ -290,4 +756,55 @@ mod tests {
             takes_int({ { { true } } });
         };
     }
+
+    /// Typecheck the given source, returning the kinds of the errors found
+    fn typecheck_errors(input: &str) -> Vec<ErrKind> {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+
+        let _ = ctx.check();
+
+        ctx.error_handler.errors().iter().map(Error::kind).collect()
+    }
+
+    #[test]
+    fn tc_if_body_variable_does_not_leak() {
+        let errs = typecheck_errors("if true { x = 15; } y = x");
+
+        assert_eq!(errs, vec![ErrKind::TypeChecker]);
+    }
+
+    #[test]
+    fn tc_loop_body_variable_does_not_leak() {
+        let errs = typecheck_errors("mut i = 0; while i < 2 { x = i; i = i + 1; } y = x");
+
+        assert_eq!(errs, vec![ErrKind::TypeChecker]);
+    }
+
+    #[test]
+    fn tc_block_type_does_not_leak() {
+        let errs = typecheck_errors("{ type Inner(x: int); } i = Inner(x: 15)");
+
+        assert!(errs.contains(&ErrKind::TypeChecker));
+    }
+
+    #[test]
+    fn tc_outer_variable_visible_in_block() {
+        jinko! {
+            x = 15;
+            if true {
+                y = x;
+            }
+        };
+    }
+
+    #[test]
+    fn t_entry_point_declarations_persist() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        ctx.eval("type Point(x: int, y: int); x = 15").unwrap();
+        let value = ctx.eval("Point(x: x, y: 2).x").unwrap();
+
+        assert_eq!(value, Some(JkInt::from(15).to_instance()));
+    }
 }
@@ -0,0 +1,194 @@
+//! Constants are declared with the `const` keyword and cannot be assigned to
+//! afterwards. Their value must be known before executing the program, so that it
+//! is computed a single time and can be used when folding constant expressions.
+//! ```ignore
+//! const PI = 3.14159;
+//!
+//! if PI > 3.0 { "folded" } else { "removed" }
+//! ```
+
+use crate::context::Context;
+use crate::error::{ErrKind, Error};
+use crate::instance::ObjectInstance;
+use crate::instruction::{InstrKind, Instruction, Var};
+use crate::location::SpanTuple;
+use crate::typechecker::{CheckedType, TypeCheck, TypeCtx};
+
+#[derive(Clone)]
+pub struct ConstDec {
+    name: String,
+    value: Box<dyn Instruction>,
+    typechecked: bool,
+    location: Option<SpanTuple>,
+}
+
+impl ConstDec {
+    /// Create a new constant named `name`, initialized with `value`
+    pub fn new(name: String, value: Box<dyn Instruction>) -> ConstDec {
+        ConstDec {
+            name,
+            value,
+            typechecked: false,
+            location: None,
+        }
+    }
+
+    /// Get a reference to the name of the constant
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get a reference to the value used to initialize the constant
+    pub fn value(&self) -> &dyn Instruction {
+        &*self.value
+    }
+
+    pub fn set_location(&mut self, location: SpanTuple) {
+        self.location = Some(location)
+    }
+}
+
+impl Instruction for ConstDec {
+    fn kind(&self) -> InstrKind {
+        InstrKind::Statement
+    }
+
+    fn children(&self) -> Vec<&dyn Instruction> {
+        vec![self.value.as_ref()]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        vec![self.value.as_mut()]
+    }
+
+    fn print(&self) -> String {
+        format!("const {} = {}", self.name, self.value.print())
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
+        let mut var = Var::new(self.name.clone());
+        var.set_instance(self.value.execute_expression(ctx)?);
+
+        if let Err(e) = ctx.add_variable(var) {
+            ctx.error(e.with_loc(self.location.clone()));
+            return None;
+        }
+
+        Some(ObjectInstance::void())
+    }
+
+    fn location(&self) -> Option<&SpanTuple> {
+        self.location.as_ref()
+    }
+}
+
+impl TypeCheck for ConstDec {
+    fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        self.value.type_of(ctx)?;
+
+        let value = match self.value.kind() {
+            InstrKind::Expression(Some(value)) => value,
+            _ => {
+                return Err(Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!(
+                        "value of constant `{}` is not known before execution: `{}`",
+                        self.name,
+                        self.value.print()
+                    ))
+                    .with_loc(self.location.clone()))
+            }
+        };
+
+        ctx.declare_constant(self.name.clone(), value)
+            .map_err(|e| e.with_loc(self.location.clone()))?;
+
+        Ok(CheckedType::Void)
+    }
+
+    fn set_cached_type(&mut self, _ty: CheckedType) {
+        self.typechecked = true;
+    }
+
+    fn cached_type(&self) -> Option<&CheckedType> {
+        match self.typechecked {
+            true => Some(&CheckedType::Void),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::FromObjectInstance;
+    use crate::value::{JkFloat, JkInt};
+    use crate::{jinko, jinko_fail};
+
+    #[test]
+    fn print() {
+        let constant = ConstDec::new(String::from("RATIO"), Box::new(JkFloat::from(1.5)));
+
+        assert_eq!(constant.print(), "const RATIO = 1.5");
+    }
+
+    #[test]
+    fn t_const_in_expression() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        let res = ctx.eval("const RATIO = 1.5; RATIO * 2.0").unwrap().unwrap();
+        assert_eq!(JkFloat::from_instance(&res).rust_value(), 3.0);
+    }
+
+    #[test]
+    fn tc_const_reassign() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert!(ctx.eval("const PI = 3.14159; PI = 3.0").is_err());
+        assert_eq!(ctx.error_handler.errors()[0].kind(), ErrKind::TypeChecker);
+    }
+
+    #[test]
+    fn t_const_shadowed_by_argument() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        let res = ctx
+            .eval("const X = 1; func f(X: int) -> int { if X > 3 { 10 } else { 20 } } f(5)")
+            .unwrap()
+            .unwrap();
+        assert_eq!(JkInt::from_instance(&res).rust_value(), 10);
+    }
+
+    #[test]
+    fn tc_const_scoped_to_block() {
+        jinko! {
+            {
+                const X = 1;
+            }
+            X = 7;
+        };
+    }
+
+    #[test]
+    fn tc_const_redeclare() {
+        jinko_fail! {
+            const PI = 3.14159;
+            const PI = 3.0;
+        };
+    }
+
+    #[test]
+    fn tc_const_unknown_value() {
+        jinko_fail! {
+            a = 15;
+            const B = a;
+        };
+    }
+
+    #[test]
+    fn tc_const_valid() {
+        jinko! {
+            const NAME = "jinko";
+            const ANSWER = 42;
+        };
+    }
+}
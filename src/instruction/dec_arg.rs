@@ -1,12 +1,14 @@
 use std::fmt::{Display, Formatter, Result};
 
+use crate::instruction::Instruction;
 use crate::location::SpanTuple;
 use crate::typechecker::TypeId;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct DecArg {
     name: String,
     ty: TypeId,
+    default: Option<Box<dyn Instruction>>,
     location: Option<SpanTuple>,
 }
 
@@ -16,6 +18,7 @@ impl DecArg {
         DecArg {
             name,
             ty,
+            default: None,
             location: None,
         }
     }
@@ -30,6 +33,16 @@ impl DecArg {
         &self.ty
     }
 
+    /// Return a reference to the argument's default value, if it has one
+    pub fn default(&self) -> Option<&dyn Instruction> {
+        self.default.as_deref()
+    }
+
+    /// Return a mutable reference to the argument's default value, if it has one
+    pub fn default_mut(&mut self) -> Option<&mut Box<dyn Instruction>> {
+        self.default.as_mut()
+    }
+
     /// Get a reference to the argument's location
     pub fn location(&self) -> Option<&SpanTuple> {
         self.location.as_ref()
@@ -40,14 +53,33 @@ impl DecArg {
         self.ty = ty
     }
 
+    /// Set the default value of the argument, used when a call does not provide it
+    pub fn set_default(&mut self, default: Box<dyn Instruction>) {
+        self.default = Some(default)
+    }
+
     /// Set the location of the argument
     pub fn set_location(&mut self, location: SpanTuple) {
         self.location = Some(location)
     }
 }
 
+impl PartialEq for DecArg {
+    fn eq(&self, other: &DecArg) -> bool {
+        self.name == other.name
+            && self.ty == other.ty
+            && self.location == other.location
+            && self.default.as_ref().map(|d| d.print()) == other.default.as_ref().map(|d| d.print())
+    }
+}
+
 impl Display for DecArg {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}: {}", self.name, self.ty.id())
+        write!(f, "{}: {}", self.name, self.ty.id())?;
+
+        match &self.default {
+            Some(default) => write!(f, " = {}", default.print()),
+            None => Ok(()),
+        }
     }
 }
@@ -54,9 +54,9 @@ impl ExtraContent {
 }
 
 impl Instruction for ExtraContent {
-    fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
-
-        None
+    fn execute(&self, _ctx: &mut Context) -> Option<ObjectInstance> {
+        // Comments and whitespaces do nothing when executed
+        Some(ObjectInstance::void())
     }
 
     fn kind(&self) -> InstrKind {
@@ -1,8 +1,14 @@
 //! FieldAccesses represent an access onto a type instance's members.
-//! FIXME: Add doc
+//! ```ignore
+//! type Point(x: int, y: int);
+//! p = Point(x: 1, y: 2);
+//! p.x // 1
+//! ```
+//! Accesses can be chained to reach into nested instances, such as `a.b.c`. The type
+//! of an access is the declared type of the field in the instance's type declaration.
 
 use crate::context::Context;
-use crate::error::{ErrKind, Error};
+use crate::error::{ice, ErrKind, Error};
 use crate::instance::ObjectInstance;
 use crate::instruction::{InstrKind, Instruction};
 use crate::location::SpanTuple;
@@ -66,6 +72,14 @@ impl Instruction for FieldAccess {
         InstrKind::Expression(None)
     }
 
+    fn children(&self) -> Vec<&dyn Instruction> {
+        vec![self.instance.as_ref()]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        vec![self.instance.as_mut()]
+    }
+
     fn print(&self) -> String {
         format!("{}.{}", self.instance.print(), self.field_name)
     }
@@ -92,14 +106,26 @@ impl TypeCheck for FieldAccess {
                     ))
                     .with_loc(self.instance.location().cloned()));
             }
-            // FIXME: Remove this once we don't have ::Later and ::Error variants
-            // anymore
-            _ => unreachable!(),
+            // The instance cannot be typechecked yet, so neither can its fields
+            CheckedType::Later => return Ok(CheckedType::Later),
+            // FIXME: Remove this once we don't have the ::Error variant anymore
+            CheckedType::Error => {
+                return Err(ice("field access on an instance of erroneous type")
+                    .with_loc(self.location.clone()))
+            }
         };
 
-        // We can unwrap here since the type that was resolved from the instance HAS
-        // to exist. If it does not, this is an interpreter error
-        let dec = ctx.get_custom_type(instance_ty_name).unwrap();
+        let dec = match ctx.get_custom_type(instance_ty_name) {
+            Some(dec) => dec,
+            None => {
+                return Err(Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!(
+                        "trying to access field `{}` on instance of unknown type `{}`",
+                        self.field_name, instance_ty
+                    ))
+                    .with_loc(self.location.clone()))
+            }
+        };
 
         match dec
             .fields()
@@ -148,7 +174,7 @@ mod tests {
         let inst = constructs::expr(span!("b.x")).unwrap().1;
         let res = match inst.execute(&mut ctx) {
             Some(i) => i,
-            None => return assert!(false, "Error when accessing valid field"),
+            None => panic!("Error when accessing valid field"),
         };
 
         let exp = JkInt::from(15).to_instance();
@@ -174,34 +200,39 @@ mod tests {
     }
 
     #[test]
-    #[ignore] // FIXME: Do not ignore once we can type instance fields
     fn t_valid_multi_field_access() {
-        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let ctx = jinko! {
+            type Pair1(x: int, y: int);
+            type Pair2(x: Pair1, y: int);
+            type Pair3(x: Pair2, y: int);
 
-        let inst = constructs::expr(span!("type Pair1(x: int, y: int)"))
-            .unwrap()
-            .1;
-        inst.execute(&mut ctx).unwrap();
+            p = Pair3(x: Pair2(x: Pair1(x: 1, y: 2), y: 3), y: 4);
+            res = p.x.x.y;
+        };
 
-        let inst = constructs::expr(span!("type Pair2(x: Pair1, y: int)"))
-            .unwrap()
-            .1;
-        inst.execute(&mut ctx).unwrap();
+        let res = ctx.get_variable(&"res".into()).unwrap();
+        assert_eq!(res.instance(), JkInt::from(2).to_instance());
+    }
 
-        let inst = constructs::expr(span!("p = Pair2 ( x : Pair1 ( x : 1, y : 2), y : 3)"))
-            .unwrap()
-            .1;
-        inst.execute(&mut ctx).unwrap();
+    #[test]
+    fn tc_multi_field_access_type() {
+        jinko! {
+            type Pair1(x: int, y: int);
+            type Pair2(x: Pair1, y: int);
 
-        let inst = constructs::expr(span!("p.x.y")).unwrap().1;
-        let res = match inst.execute(&mut ctx) {
-            Some(i) => i,
-            None => unreachable!("Error when accessing valid multi field"),
+            func inner_y(p: Pair2) -> int { p.x.y }
         };
+    }
 
-        let expected = JkInt::from(2).to_instance();
+    #[test]
+    fn tc_multi_field_access_unknown_field() {
+        jinko_fail! {
+            type Pair1(x: int, y: int);
+            type Pair2(x: Pair1, y: int);
 
-        assert_eq!(res, expected)
+            p = Pair2(x: Pair1(x: 1, y: 2), y: 3);
+            p.x.z
+        };
     }
 
     #[test]
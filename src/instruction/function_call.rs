@@ -4,17 +4,23 @@
 use std::fmt::Write;
 use std::rc::Rc;
 
+use crate::builtins;
 use crate::context::Context;
-use crate::error::{ErrKind, Error};
+use crate::error::{ice, ErrKind, Error};
+use crate::generics::{self, GenericExpander, GenericMap};
 use crate::instance::ObjectInstance;
-use crate::instruction::{FunctionDec, FunctionKind, Var};
+use crate::instruction::{FunctionDec, FunctionKind, Var, VarAssign};
 use crate::instruction::{InstrKind, Instruction};
 use crate::location::SpanTuple;
-use crate::typechecker::{CheckedType, TypeCheck, TypeCtx, TypeId};
+use crate::typechecker::{
+    CheckedType, SpecializedNode, TypeCheck, TypeCtx, TypeId, PRIMITIVE_TYPES,
+};
+
+use symbol::Symbol;
 
 #[derive(Clone)]
 pub struct FunctionCall {
-    fn_name: String,
+    fn_name: Symbol,
     generics: Vec<TypeId>,
     args: Vec<Box<dyn Instruction>>,
     cached_type: Option<CheckedType>,
@@ -29,7 +35,7 @@ impl FunctionCall {
         args: Vec<Box<dyn Instruction>>,
     ) -> FunctionCall {
         FunctionCall {
-            fn_name,
+            fn_name: Symbol::from(fn_name),
             generics,
             args,
             cached_type: None,
@@ -49,6 +55,11 @@ impl FunctionCall {
 
     /// Return a reference the called function's name
     pub fn name(&self) -> &str {
+        self.fn_name.access()
+    }
+
+    /// Return the interned name of the called function
+    pub fn symbol(&self) -> &Symbol {
         &self.fn_name
     }
 
@@ -64,9 +75,8 @@ impl FunctionCall {
 
     /// Get the corresponding declaration from a context
     fn get_declaration(&self, ctx: &mut Context) -> Result<Rc<FunctionDec>, Error> {
-        match ctx.get_function(self.name()) {
-            // get_function() return a Rc, so this clones the Rc, not the FunctionDec
-            Some(f) => Ok(f.clone()),
+        match ctx.get_function(self.symbol()) {
+            Some(f) => Ok(f),
             // FIXME: Fix Location and input
             None => Err(Error::new(ErrKind::Context)
                 .with_msg(format!("cannot find function {}", self.name()))
@@ -74,9 +84,105 @@ impl FunctionCall {
         }
     }
 
-    /// Map each argument to its corresponding instruction
-    fn map_args(&self, function: &FunctionDec, ctx: &mut Context) {
-        for (call_arg, func_arg) in self.args.iter().zip(function.args()) {
+    /// Order the arguments given to the call according to the function's declaration.
+    /// Positional arguments come first, followed by named arguments in any order.
+    /// Arguments that were not given are replaced by their default value, if any
+    fn ordered_args(&self, function: &FunctionDec) -> Result<Vec<Box<dyn Instruction>>, Error> {
+        let dec_args = function.args();
+        let mut slots: Vec<Option<Box<dyn Instruction>>> = vec![None; dec_args.len()];
+        let mut positional_idx = 0;
+        let mut seen_named = false;
+
+        let wrong_arg_count = |problem: &str| {
+            Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "{problem} arguments \
+                    for call to function `{}`: expected {}, got {}",
+                    self.name(),
+                    dec_args.len(),
+                    self.args().len()
+                ))
+                .with_loc(self.location.clone())
+        };
+
+        for arg in self.args.iter() {
+            match arg.downcast_ref::<VarAssign>() {
+                Some(named) if !named.mutable() => {
+                    seen_named = true;
+
+                    let idx = dec_args
+                        .iter()
+                        .position(|dec_arg| dec_arg.name() == named.symbol())
+                        .ok_or_else(|| {
+                            Error::new(ErrKind::TypeChecker)
+                                .with_msg(format!(
+                                    "function `{}` has no argument named `{}`",
+                                    self.name(),
+                                    named.symbol()
+                                ))
+                                .with_loc(named.location().cloned())
+                        })?;
+
+                    if slots[idx].is_some() {
+                        return Err(Error::new(ErrKind::TypeChecker)
+                            .with_msg(format!(
+                                "argument `{}` given twice in call to function `{}`",
+                                named.symbol(),
+                                self.name()
+                            ))
+                            .with_loc(named.location().cloned()));
+                    }
+
+                    slots[idx] = Some(named.value().box_clone());
+                }
+                _ => {
+                    if seen_named {
+                        return Err(Error::new(ErrKind::TypeChecker)
+                            .with_msg(format!(
+                                "positional argument `{}` given after named arguments",
+                                arg.print()
+                            ))
+                            .with_loc(arg.location().cloned()));
+                    }
+
+                    match slots.get_mut(positional_idx) {
+                        Some(slot) => *slot = Some(arg.clone()),
+                        None => return Err(wrong_arg_count("too many")),
+                    }
+                    positional_idx += 1;
+                }
+            }
+        }
+
+        slots
+            .into_iter()
+            .zip(dec_args)
+            .map(|(slot, dec_arg)| match (slot, dec_arg.default()) {
+                (Some(arg), _) => Ok(arg),
+                (None, Some(default)) => Ok(default.box_clone()),
+                (None, None) => Err(wrong_arg_count("too few").with_hint(
+                    Error::hint()
+                        .with_msg(format!("missing argument `{}`", dec_arg.name()))
+                        .with_loc(dec_arg.location().cloned()),
+                )),
+            })
+            .collect()
+    }
+
+    /// Map each argument to its corresponding instruction. Arguments, including
+    /// default values, are evaluated in the caller's scope, and returned as variables
+    /// named after the function's arguments, to declare in the callee's scope
+    fn map_args(&self, function: &FunctionDec, ctx: &mut Context) -> Option<Vec<Var>> {
+        let call_args = match self.ordered_args(function) {
+            Ok(args) => args,
+            Err(e) => {
+                ctx.error(e);
+                return None;
+            }
+        };
+
+        let mut vars = Vec::with_capacity(call_args.len());
+        for (call_arg, func_arg) in call_args.iter().zip(function.args()) {
             // Create a new variable, and execute the content of the function argument
             // passed to the call
             let mut new_var = Var::new(func_arg.name().to_owned());
@@ -92,7 +198,7 @@ impl FunctionCall {
                             ))
                             .with_loc(func_arg.location().cloned()),
                     );
-                    return;
+                    return None;
                 }
             };
 
@@ -106,7 +212,7 @@ impl FunctionCall {
                             .with_msg(format!("type not found: {}", func_arg.get_type().id()))
                             .with_loc(func_arg.location().cloned()),
                     );
-                    return;
+                    return None;
                 }
             };
 
@@ -114,10 +220,10 @@ impl FunctionCall {
 
             new_var.set_instance(instance);
 
-            if let Err(e) = ctx.add_variable(new_var) {
-                ctx.error(e);
-            }
+            vars.push(new_var);
         }
+
+        Some(vars)
     }
 
     fn type_args(&self, args: Vec<(String, CheckedType)>, ctx: &mut TypeCtx) {
@@ -134,19 +240,97 @@ impl FunctionCall {
         ctx.scope_exit();
     }
 
+    fn execute_builtin(&self, ctx: &mut Context) -> Option<ObjectInstance> {
+        // Specialized generic builtins, such as `size_of+int`, share the same
+        // implementation
+        let name = generics::original_name(self.name());
+
+        match ctx.call_builtin(name, self.args.clone()) {
+            Ok(value) => value,
+            Err(e) => {
+                ctx.error(e);
+                None
+            }
+        }
+    }
+
+    /// Typecheck a call to one of the builtin printers, which take a single value
+    /// of any primitive type
+    fn resolve_primitive_printer(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        if self.args.len() != 1 {
+            return Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "wrong number of arguments \
+                    for call to function `{}`: expected 1, got {}",
+                    self.name(),
+                    self.args.len()
+                ))
+                .with_loc(self.location.clone()));
+        }
+
+        let arg = &mut self.args[0];
+        match arg.type_of(ctx)? {
+            CheckedType::Later => Ok(CheckedType::Later),
+            CheckedType::Resolved(ty) if PRIMITIVE_TYPES.contains(&ty.id()) => {
+                Ok(CheckedType::Void)
+            }
+            arg_ty => Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "function `{}` expects a primitive value, got `{arg_ty}`",
+                    self.fn_name
+                ))
+                .with_loc(arg.location().cloned())),
+        }
+    }
+
+    /// Execute a call to a function defined by the host program
+    fn execute_native(&self, ctx: &mut Context, dec: &FunctionDec) -> Option<ObjectInstance> {
+        let call_args = match self.ordered_args(dec) {
+            Ok(args) => args,
+            Err(e) => {
+                ctx.error(e);
+                return None;
+            }
+        };
+
+        let mut values = Vec::with_capacity(call_args.len());
+        for arg in call_args.iter() {
+            values.push(arg.execute_expression(ctx)?);
+        }
+
+        match ctx.call_native(dec.name(), &values) {
+            Ok(value) => match dec.ty() {
+                Some(ty) if value.ty() != &CheckedType::Resolved(ty.clone()) => {
+                    ctx.error(
+                        Error::new(ErrKind::TypeChecker)
+                            .with_msg(format!(
+                                "native function `{}` returned a value of type `{}`, expected `{}`",
+                                dec.name(),
+                                value.ty(),
+                                ty
+                            ))
+                            .with_loc(self.location.clone()),
+                    );
+                    None
+                }
+                _ => Some(value),
+            },
+            Err(e) => {
+                ctx.error(e.with_loc(self.location.clone()));
+                None
+            }
+        }
+    }
+
     fn execute_external_function(
         &self,
         ctx: &mut Context,
         dec: &FunctionDec,
     ) -> Option<ObjectInstance> {
-        if ctx.is_builtin(dec.name()) {
-            match ctx.call_builtin(dec.name(), self.args.clone()) {
-                Ok(value) => value,
-                Err(e) => {
-                    ctx.error(e);
-                    None
-                }
-            }
+        if ctx.is_native(dec.name()) {
+            self.execute_native(ctx, dec)
+        } else if ctx.is_builtin(generics::original_name(dec.name())) {
+            self.execute_builtin(ctx)
         } else {
             #[cfg(feature = "ffi")]
             match crate::ffi::execute(dec, self, ctx) {
@@ -172,12 +356,77 @@ impl FunctionCall {
         }
     }
 
+    /// Specialize the generic function being called using the generic types given to
+    /// the call. The call then resolves to the specialized function, which is returned
+    fn specialize_call(
+        &mut self,
+        function: FunctionDec,
+        ctx: &mut TypeCtx,
+    ) -> Result<FunctionDec, Error> {
+        if function.generics().len() != self.generics.len() {
+            return Err(Error::new(ErrKind::Generics)
+                .with_msg(format!(
+                    "wrong number of generic types \
+                    for call to function `{}`: expected {}, got {}",
+                    self.name(),
+                    function.generics().len(),
+                    self.generics.len()
+                ))
+                .with_loc(self.location.clone())
+                .with_hint(
+                    Error::hint()
+                        .with_msg(format!("function `{}` declared here", function.name()))
+                        .with_loc(function.loc()),
+                ));
+        }
+
+        self.generics = self
+            .generics
+            .iter()
+            .map(|ty| ctx.resolve_alias(ty))
+            .collect();
+
+        if let Some(unknown) = self
+            .generics
+            .iter()
+            .find(|ty| ctx.get_custom_type(ty.id()).is_none())
+        {
+            return Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!("use of undeclared type `{unknown}`"))
+                .with_loc(self.location.clone()));
+        }
+
+        let mangled = generics::mangle(self.name(), &self.generics);
+
+        // Each specialization only gets generated and typechecked once
+        if ctx.get_specialized_node(&mangled).is_none() {
+            let type_map = GenericMap::create(function.generics(), &self.generics, ctx)?;
+            let specialized = function.generate(mangled.clone(), &type_map, ctx);
+
+            ctx.add_specialized_node(SpecializedNode::Func(Box::new(specialized)))?;
+        }
+
+        let specialized = match ctx.get_specialized_node(&mangled) {
+            Some(SpecializedNode::Func(f)) => (**f).clone(),
+            _ => {
+                return Err(ice(format!(
+                    "specialized function `{mangled}` was just generated"
+                ))
+                .with_loc(self.location.clone()))
+            }
+        };
+
+        self.fn_name = Symbol::from(mangled);
+
+        Ok(specialized)
+    }
+
     pub fn generics(&self) -> &Vec<TypeId> {
         &self.generics
     }
 
     pub fn set_name(&mut self, fn_name: String) {
-        self.fn_name = fn_name
+        self.fn_name = Symbol::from(fn_name)
     }
 }
 
@@ -187,8 +436,27 @@ impl Instruction for FunctionCall {
         InstrKind::Expression(None)
     }
 
+    fn children(&self) -> Vec<&dyn Instruction> {
+        self.args.iter().map(|arg| arg.as_ref()).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        self.args.iter_mut().map(|arg| arg.as_mut()).collect()
+    }
+
+    /// Generic calls performed in the body of a generic function, such as
+    /// `id[T](x)`, call the specialization matching the types the function is
+    /// specialized with
+    fn resolve_self(&mut self, type_map: &GenericMap, _ctx: &mut TypeCtx) {
+        self.generics = self
+            .generics
+            .iter()
+            .map(|generic| type_map.specialize(generic))
+            .collect();
+    }
+
     fn print(&self) -> String {
-        let mut base = String::from(&self.fn_name);
+        let mut base = String::from(self.name());
 
         if !self.generics.is_empty() {
             base = format!("{}[{}", base, self.generics[0]);
@@ -219,6 +487,8 @@ impl Instruction for FunctionCall {
     fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
         let function = match self.get_declaration(ctx) {
             Ok(f) => f,
+            // Builtins do not need to be declared in order to be called
+            Err(_) if ctx.is_builtin(self.name()) => return self.execute_builtin(ctx),
             Err(e) => {
                 ctx.error(e);
                 return None;
@@ -229,13 +499,25 @@ impl Instruction for FunctionCall {
             return self.execute_external_function(ctx, &function);
         }
 
+        let args = self.map_args(&function, ctx)?;
+
+        if let Err(e) = ctx.enter_call() {
+            ctx.error(e.with_loc(self.location.clone()));
+            return None;
+        }
+
         ctx.scope_enter();
 
-        self.map_args(&function, ctx);
+        for arg in args {
+            if let Err(e) = ctx.add_variable(arg) {
+                ctx.error(e);
+            }
+        }
 
         let ret_val = function.run(ctx);
 
         ctx.scope_exit();
+        ctx.exit_call();
 
         ret_val
     }
@@ -247,15 +529,18 @@ impl Instruction for FunctionCall {
 
 impl TypeCheck for FunctionCall {
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
-        // FIXME: Expand generic here instead of resolving later
-        // if !self.generics.is_empty() && !ctx.is_second_pass() {
-        //     return CheckedType::Later;
-        // }
+        // The function might read any variable from the enclosing scopes
+        ctx.record_read_all();
 
         // FIXME: This function is very large and should be refactored
         let function = match ctx.get_function(self.name()) {
+            Some(f)
+                if f.fn_kind() == FunctionKind::Ext
+                    && builtins::is_primitive_printer(self.name()) =>
+            {
+                return self.resolve_primitive_printer(ctx)
+            }
             Some(f) => f.clone(), // FIXME: Remove this clone...
-            // FIXME: This does not account for functions declared later in the code
             None => {
                 return Err(Error::new(ErrKind::TypeChecker)
                     .with_msg(format!(
@@ -266,35 +551,49 @@ impl TypeCheck for FunctionCall {
             }
         };
 
-        // FIXME: Add check for calling non-generic function usign generics
+        let function = match function.generics().is_empty() && self.generics.is_empty() {
+            true => function,
+            false => self.specialize_call(function, ctx)?,
+        };
 
         let (args_type, return_type) = (function.args(), function.ty());
         let args_type = args_type.clone();
 
-        // if !function.generics().is_empty() || !self.generics.is_empty() {
-        //     return self.resolve_generic_call(function, ctx);
-        // }
+        // Typecheck the given arguments in place first, so that the instructions
+        // executed at runtime are the ones which got resolved, such as generic calls
+        let mut errors: Vec<Error> = self
+            .args
+            .iter_mut()
+            .filter_map(|arg| {
+                let res = match arg.downcast_mut::<VarAssign>() {
+                    Some(named) if !named.mutable() => named.value_mut().type_of(ctx),
+                    _ => arg.type_of(ctx),
+                };
+
+                res.err()
+            })
+            .collect();
+
+        if !errors.is_empty() {
+            errors.into_iter().for_each(|err| ctx.error(err));
+
+            return Ok(
+                return_type.map_or_else(|| CheckedType::Void, |t| CheckedType::Resolved(t.clone()))
+            );
+        }
 
-        let mut errors = vec![];
         let mut args = vec![];
 
-        if self.args().len() != args_type.len() {
-            errors.push(
-                Error::new(ErrKind::TypeChecker)
-                    .with_msg(format!(
-                        "wrong number of arguments \
-                    for call to function `{}`: expected {}, got {}",
-                        self.name(),
-                        args_type.len(),
-                        self.args().len()
-                    ))
-                    .with_loc(self.location.clone()),
-            );
-        }
+        let call_args = match self.ordered_args(&function) {
+            Ok(call_args) => call_args,
+            Err(e) => {
+                errors.push(e);
+                vec![]
+            }
+        };
 
-        for (dec_arg, given_arg) in args_type.iter().zip(self.args.iter()) {
-            // FIXME: Remove clone
-            let given_ty = match given_arg.clone().type_of(ctx) {
+        for (position, (dec_arg, mut given_arg)) in args_type.iter().zip(call_args).enumerate() {
+            let given_ty = match given_arg.type_of(ctx) {
                 Err(e) => {
                     errors.push(e);
                     continue;
@@ -306,7 +605,10 @@ impl TypeCheck for FunctionCall {
                 errors.push(
                     Error::new(ErrKind::TypeChecker)
                         .with_msg(format!(
-                            "invalid type used for function argument: expected `{expected_ty}`, got `{given_ty}`"
+                            "invalid type for argument {} (`{}`) of function `{}`: expected `{expected_ty}`, got `{given_ty}`",
+                            position + 1,
+                            dec_arg.name(),
+                            self.name(),
                         ))
                         .with_loc(given_arg.location().cloned())
                         .with_hint(
@@ -338,7 +640,9 @@ impl TypeCheck for FunctionCall {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::instance::ToObjectInstance;
     use crate::parser::constructs;
+    use crate::value::JkInt;
     use crate::{jinko, jinko_fail, span};
 
     #[test]
@@ -378,6 +682,56 @@ mod tests {
         );
     }
 
+    fn call_errors(input: &str) -> Vec<String> {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        let source = format!("func add(lhs: int, rhs: int) -> int {{ lhs + rhs }} {input}");
+        assert!(ctx.eval(&source).is_err());
+
+        ctx.error_handler
+            .errors()
+            .iter()
+            .inspect(|err| assert_eq!(err.kind(), ErrKind::TypeChecker))
+            .filter_map(|err| err.msg().map(String::from))
+            .collect()
+    }
+
+    #[test]
+    fn tc_too_few_args() {
+        assert_eq!(
+            call_errors("add(1)"),
+            vec!["too few arguments for call to function `add`: expected 2, got 1"]
+        );
+    }
+
+    #[test]
+    fn tc_too_many_args() {
+        assert_eq!(
+            call_errors("add(1, 2, 3)"),
+            vec!["too many arguments for call to function `add`: expected 2, got 3"]
+        );
+    }
+
+    #[test]
+    fn tc_arg_type_mismatch() {
+        assert_eq!(
+            call_errors("add(1, 'c')"),
+            vec![
+                "invalid type for argument 2 (`rhs`) of function `add`: expected `int`, got `char`"
+            ]
+        );
+    }
+
+    #[test]
+    fn tc_valid_call() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert_eq!(
+            ctx.eval("func add(lhs: int, rhs: int) -> int { lhs + rhs } add(1, rhs = 2)"),
+            Ok(Some(JkInt::from(3).to_instance()))
+        );
+    }
+
     #[test]
     fn t_func_call_arg_return() {
         use crate::instance::ToObjectInstance;
@@ -469,6 +823,117 @@ mod tests {
         };
     }
 
+    #[test]
+    fn t_default_args_all_positional() {
+        let ctx = jinko! {
+            func sub(x: int, y: int = 10) -> int { x - y }
+            a = sub(15, 3);
+            b = sub(15);
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkInt::from(12).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"b".into()).unwrap().instance(),
+            JkInt::from(5).to_instance()
+        );
+    }
+
+    #[test]
+    fn t_named_args() {
+        let ctx = jinko! {
+            func sub(x: int, y: int) -> int { x - y }
+            a = sub(y = 2, x = 1);
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkInt::from(-1).to_instance()
+        );
+    }
+
+    #[test]
+    fn t_mixed_positional_named_and_default_args() {
+        let ctx = jinko! {
+            func f(x: int, y: int = 10, z: int = 100) -> int { x + y * 2 + z * 3 }
+            a = f(1, z = 2);
+            b = f(1, 2, z = 3);
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkInt::from(27).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"b".into()).unwrap().instance(),
+            JkInt::from(14).to_instance()
+        );
+    }
+
+    #[test]
+    fn t_args_evaluated_in_caller_scope() {
+        let ctx = jinko! {
+            x = 10;
+            func add(x: int, y: int = x) -> int { x + y }
+            func sub(a: int, b: int) -> int { a - b }
+            a = add(1);
+            b = sub(5, a);
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkInt::from(11).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"b".into()).unwrap().instance(),
+            JkInt::from(-6).to_instance()
+        );
+    }
+
+    #[test]
+    fn tc_missing_required_arg() {
+        jinko_fail! {
+            func sub(x: int, y: int = 10) -> int { x - y }
+            sub(y = 3);
+        };
+    }
+
+    #[test]
+    fn tc_invalid_named_args() {
+        jinko_fail! {
+            func sub(x: int, y: int) -> int { x - y }
+            sub(x = 1, x = 2);
+        };
+        jinko_fail! {
+            func sub(x: int, y: int) -> int { x - y }
+            sub(x = 1, z = 2);
+        };
+        jinko_fail! {
+            func sub(x: int, y: int) -> int { x - y }
+            sub(x = 1, 2);
+        };
+    }
+
+    #[test]
+    fn tc_invalid_default_arg_type() {
+        jinko_fail! {
+            func sub(x: int, y: int = 'c') -> int { x - y }
+        };
+    }
+
+    #[test]
+    fn t_recursion_limit() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_max_recursion_depth(64);
+
+        let input = "func forever(x: int) -> int { forever(x + 1) } forever(0)";
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+
+        assert!(ctx.execute().is_err());
+    }
+
     #[test]
     fn t_call_invalid_builtin() {
         jinko_fail! {
@@ -477,4 +942,90 @@ mod tests {
             not_a_builtin();
         };
     }
+
+    #[test]
+    fn t_generic_call() {
+        use crate::value::JkString;
+
+        let ctx = jinko! {
+            func id[T](x: T) -> T { x }
+            i = id[int](5);
+            s = id[string]("x");
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"i".into()).unwrap().instance(),
+            JkInt::from(5).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"s".into()).unwrap().instance(),
+            JkString::from("x").to_instance()
+        );
+    }
+
+    #[test]
+    fn tc_generic_call_resolves_to_specialized_type() {
+        jinko! {
+            func id[T](x: T) -> T { x }
+            i = id[int](5) + 1;
+            s = id[string]("x").concat("y");
+            same = id[int](5) + id[int](6);
+        };
+        jinko_fail! {
+            func id[T](x: T) -> T { x }
+            i = id[string]("x") + 1;
+        };
+    }
+
+    #[test]
+    fn t_nested_generic_call() {
+        let ctx = jinko! {
+            func id[T](x: T) -> T { x }
+            func outer[T](x: T) -> T { id[T](x) }
+            i = outer[int](3);
+            s = outer[string]("x");
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"i".into()).unwrap().instance(),
+            JkInt::from(3).to_instance()
+        );
+        assert!(ctx.error_handler.warnings().is_empty());
+    }
+
+    #[test]
+    fn tc_generic_call_mismatched_arg() {
+        jinko_fail! {
+            func id[T](x: T) -> T { x }
+            id[int]("x");
+        };
+    }
+
+    #[test]
+    fn tc_generic_call_invalid_body() {
+        jinko_fail! {
+            func returns_generic[T]() -> T { true }
+            returns_generic[string]();
+        };
+    }
+
+    #[test]
+    fn tc_generic_call_wrong_generics() {
+        jinko_fail! {
+            func id[T](x: T) -> T { x }
+            id(5);
+        };
+        jinko_fail! {
+            func id[T](x: T) -> T { x }
+            id[int, int](5);
+        };
+        jinko_fail! {
+            func not_generic(x: int) -> int { x }
+            not_generic[int](5);
+        };
+        jinko_fail! {
+            func id[T](x: T) -> T { x }
+            id[NotAType](5);
+        };
+    }
 }
@@ -5,6 +5,7 @@ use std::fmt::Write;
 
 use crate::context::Context;
 use crate::error::{ErrKind, Error};
+use crate::generics::{GenericExpander, GenericMap};
 use crate::instance::ObjectInstance;
 use crate::instruction::{Block, DecArg, InstrKind, Instruction};
 use crate::location::{Location, SpanTuple};
@@ -30,6 +31,8 @@ pub struct FunctionDec {
     args: Vec<DecArg>,
     block: Option<Block>,
     typechecked: bool,
+    /// Was the function declared in the type context before typechecking its body
+    hoisted: bool,
     location: Option<SpanTuple>,
 }
 
@@ -49,10 +52,39 @@ impl FunctionDec {
             args,
             block: None,
             typechecked: false,
+            hoisted: false,
             location: None,
         }
     }
 
+    /// Replace the aliases used in the signature by the types they refer to, so that
+    /// the rest of the interpreter never has to know about them
+    fn resolve_signature_aliases(&mut self, ctx: &mut TypeCtx) {
+        for dec_arg in self.args.iter_mut() {
+            dec_arg.set_type(ctx.resolve_alias(dec_arg.get_type()));
+        }
+        self.ty = self.ty.as_ref().map(|ty| ctx.resolve_alias(ty));
+    }
+
+    /// Declare the signature of the function in the type context, before the
+    /// instructions preceding it are typechecked. This allows calling functions
+    /// declared later in the same block, and functions calling each other.
+    /// Generic functions, tests and mocks are not hoisted
+    pub fn hoist(&mut self, ctx: &mut TypeCtx) {
+        let hoistable = matches!(self.fn_kind(), FunctionKind::Func | FunctionKind::Ext)
+            && self.generics.is_empty();
+        if !hoistable || self.hoisted {
+            return;
+        }
+
+        self.resolve_signature_aliases(ctx);
+
+        // Redeclarations are reported when typechecking the declaration itself
+        self.hoisted = ctx
+            .declare_function(self.name().into(), self.clone())
+            .is_ok();
+    }
+
     pub fn generics(&self) -> &Vec<TypeId> {
         &self.generics
     }
@@ -146,43 +178,9 @@ impl FunctionDec {
         self.block.as_mut()
     }
 
-    /// Run through the function as if it was called. This is useful for setting
-    /// an entry point into the interpreter and executing it
-    pub fn run(&self, ctx: &mut Context) -> Option<ObjectInstance> {
-        self.block().unwrap().execute(ctx)
-    }
-}
-
-impl Instruction for FunctionDec {
-    fn kind(&self) -> InstrKind {
-        InstrKind::Statement
-    }
-
-    fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
-        match self.fn_kind() {
-            FunctionKind::Func | FunctionKind::Ext => {
-                // FIXME: This error will have gotten caught at typechecking:
-                // We should unwrap for perf
-                if let Err(e) = ctx.add_function(self.clone()) {
-                    ctx.error(e);
-                }
-            }
-            FunctionKind::Test => {
-                if let Err(e) = ctx.add_test(self.clone()) {
-                    ctx.error(e);
-                }
-            }
-            FunctionKind::Mock | FunctionKind::Unknown => ctx.error(
-                Error::new(ErrKind::Context)
-                    .with_msg(format!("unknown type for function {}", self.name()))
-                    .with_loc(self.loc()),
-            ),
-        }
-
-        None
-    }
-
-    fn print(&self) -> String {
+    /// Get the part of the declaration preceding the function's block, such as
+    /// `func add(a: int, b: int) -> int`
+    pub fn signature(&self) -> String {
         let mut base = String::from(match self.kind {
             FunctionKind::Func => "func",
             FunctionKind::Ext => "ext func",
@@ -219,10 +217,60 @@ impl Instruction for FunctionDec {
         }
         base.push(')');
 
-        base = match &self.ty {
+        match &self.ty {
             Some(ty) => format!("{} -> {}", base, ty.id()),
             None => base,
-        };
+        }
+    }
+
+    /// Run through the function as if it was called. This is useful for setting
+    /// an entry point into the interpreter and executing it
+    pub fn run(&self, ctx: &mut Context) -> Option<ObjectInstance> {
+        let ret_val = self.block().unwrap().execute(ctx);
+
+        // A `return` only unwinds up to the function it was executed in
+        ctx.clear_return();
+
+        ret_val
+    }
+}
+
+impl Instruction for FunctionDec {
+    fn kind(&self) -> InstrKind {
+        InstrKind::Statement
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
+        match self.fn_kind() {
+            FunctionKind::Func | FunctionKind::Ext => {
+                // FIXME: This error will have gotten caught at typechecking:
+                // We should unwrap for perf
+                if let Err(e) = ctx.add_function(self.clone()) {
+                    ctx.error(e);
+                    return None;
+                }
+            }
+            FunctionKind::Test => {
+                if let Err(e) = ctx.add_test(self.clone()) {
+                    ctx.error(e);
+                    return None;
+                }
+            }
+            FunctionKind::Mock | FunctionKind::Unknown => {
+                ctx.error(
+                    Error::new(ErrKind::Context)
+                        .with_msg(format!("unknown type for function {}", self.name()))
+                        .with_loc(self.loc()),
+                );
+                return None;
+            }
+        }
+
+        Some(ObjectInstance::void())
+    }
+
+    fn print(&self) -> String {
+        let base = self.signature();
 
         match &self.block {
             Some(block) => format!("{} {}", base, block.print()),
@@ -246,6 +294,8 @@ impl TypeCheck for FunctionDec {
                 .map_or(Ok(CheckedType::Void), |b| b.type_of(ctx));
         }
 
+        self.resolve_signature_aliases(ctx);
+
         // If a declaration contains generic types, there is no point in type-checking
         // it: All the methods or field accesses will, by definition, not exist, since
         // the generic types do not exist yet
@@ -258,7 +308,9 @@ impl TypeCheck for FunctionDec {
         }
 
         // FIXME: Remove clone?
-        if let Err(e) = ctx.declare_function(self.name().into(), self.clone()) {
+        if self.hoisted {
+            ctx.update_function(self.clone());
+        } else if let Err(e) = ctx.declare_function(self.name().into(), self.clone()) {
             ctx.error(e);
         }
 
@@ -272,6 +324,28 @@ impl TypeCheck for FunctionDec {
             None => CheckedType::Void,
         };
 
+        // Default values are evaluated at the call site, before any of the
+        // function's arguments are declared
+        for dec_arg in self.args.iter_mut() {
+            let expected_ty = CheckedType::Resolved(dec_arg.get_type().clone());
+            let loc = dec_arg.location().cloned();
+            let name = dec_arg.name().to_string();
+
+            if let Some(default) = dec_arg.default_mut() {
+                match default.type_of(ctx) {
+                    Ok(default_ty) if default_ty != expected_ty => ctx.error(
+                        Error::new(ErrKind::TypeChecker)
+                            .with_msg(format!(
+                                "invalid default value for argument `{name}`: expected type {expected_ty}, found type {default_ty}"
+                            ))
+                            .with_loc(loc),
+                    ),
+                    Ok(_) => {}
+                    Err(e) => ctx.error(e),
+                }
+            }
+        }
+
         let args_ty: Vec<(String, CheckedType)> = self
             .args
             .iter()
@@ -289,11 +363,23 @@ impl TypeCheck for FunctionDec {
             }
         });
 
+        ctx.enter_function(return_ty.clone());
+
         // If the function has no block, trust the declaration
         if let Some(b) = &mut self.block {
-            let block_ty = b.type_of(ctx)?;
+            let block_ty = match b.type_of(ctx) {
+                Ok(ty) => ty,
+                Err(e) => {
+                    ctx.exit_function();
+                    ctx.scope_exit();
 
-            if block_ty != return_ty {
+                    return Err(e);
+                }
+            };
+
+            // If the block always returns early, then each `return` will have
+            // been checked against the function's return type already
+            if !b.diverges() && block_ty != return_ty {
                 let err = Err(Error::new(ErrKind::TypeChecker)
                     .with_msg(format!(
                         "invalid type returned in function `{}`: expected type {}, found type {}",
@@ -303,12 +389,14 @@ impl TypeCheck for FunctionDec {
                     ))
                     .with_loc(self.loc()));
 
+                ctx.exit_function();
                 ctx.scope_exit();
 
                 return err;
             }
         }
 
+        ctx.exit_function();
         ctx.scope_exit();
 
         Ok(CheckedType::Void)
@@ -326,6 +414,32 @@ impl TypeCheck for FunctionDec {
     }
 }
 
+impl GenericExpander for FunctionDec {
+    fn generate(&self, new_name: String, type_map: &GenericMap, ctx: &mut TypeCtx) -> FunctionDec {
+        let args = self
+            .args
+            .iter()
+            .map(|dec_arg| {
+                let mut specialized = dec_arg.clone();
+                specialized.set_type(type_map.specialize(dec_arg.get_type()));
+                specialized
+            })
+            .collect();
+
+        let mut specialized = self.clone();
+        specialized.name = new_name;
+        specialized.generics = vec![];
+        specialized.args = args;
+        specialized.ty = self.ty.as_ref().map(|ty| type_map.specialize(ty));
+        specialized.typechecked = false;
+        if let Some(block) = &mut specialized.block {
+            block.resolve_usages(type_map, ctx);
+        }
+
+        specialized
+    }
+}
+
 impl Default for FunctionDec {
     fn default() -> Self {
         FunctionDec::new(String::new(), None, vec![], vec![])
@@ -353,8 +467,10 @@ impl From<&str> for FunctionKind {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::instance::ToObjectInstance;
     use crate::span;
-    use crate::{jinko, parser::constructs, typechecker::TypeId};
+    use crate::value::{JkBool, JkInt};
+    use crate::{jinko, jinko_fail, parser::constructs, typechecker::TypeId};
 
     #[test]
     fn simple_no_arg() {
@@ -430,4 +546,108 @@ mod tests {
             func return_int_i(i: int) -> int { i }
         };
     }
+
+    #[test]
+    fn mutually_recursive_functions() {
+        let ctx = jinko! {
+            func is_even(n: int) -> bool {
+                if n == 0 { true } else { is_odd(n - 1) }
+            }
+            func is_odd(n: int) -> bool {
+                if n == 0 { false } else { is_even(n - 1) }
+            }
+            even = is_even(10);
+            odd = is_odd(10);
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"even".into()).unwrap().instance(),
+            JkBool::from(true).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"odd".into()).unwrap().instance(),
+            JkBool::from(false).to_instance()
+        );
+    }
+
+    #[test]
+    fn call_before_declaration() {
+        let ctx = jinko! {
+            value = declared_later();
+            func declared_later() -> int { 15 }
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"value".into()).unwrap().instance(),
+            JkInt::from(15).to_instance()
+        );
+    }
+
+    #[test]
+    fn tc_hoisted_redefinition() {
+        let ctx = jinko_fail! {
+            func twice() -> int { 1 }
+            func twice() -> int { 2 }
+        };
+
+        assert_eq!(ctx.error_handler.errors().len(), 1);
+    }
+
+    /// Write `input` to a temporary file named after the test, and typecheck it
+    fn check_file(name: &str, input: &str) -> Context {
+        let dir = crate::utils::TempDir::new(name);
+        let path = dir.write("main.jk", input);
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_path(Some(path.clone()));
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Path(&path)).unwrap();
+        ctx.check().unwrap();
+
+        ctx
+    }
+
+    fn warnings(ctx: &Context) -> Vec<&str> {
+        ctx.error_handler
+            .warnings()
+            .iter()
+            .filter_map(|warning| warning.msg())
+            .collect()
+    }
+
+    #[test]
+    fn unused_function_warns() {
+        let ctx = check_file("unused_function_warns", "func unused() -> int { 1 }");
+
+        assert_eq!(warnings(&ctx), vec!["unused function: `unused`"]);
+    }
+
+    #[test]
+    fn used_function_does_not_warn() {
+        let ctx = check_file(
+            "used_function_does_not_warn",
+            "func used() -> int { 1 } used()",
+        );
+
+        assert!(warnings(&ctx).is_empty());
+    }
+
+    #[test]
+    fn underscore_function_does_not_warn() {
+        let ctx = check_file(
+            "underscore_function_does_not_warn",
+            "func _unused() -> int { 1 }",
+        );
+
+        assert!(warnings(&ctx).is_empty());
+    }
+
+    #[test]
+    fn specialized_function_does_not_warn() {
+        let ctx = check_file(
+            "specialized_function_does_not_warn",
+            "func id[T](value: T) -> T { value } id[int](1)",
+        );
+
+        assert!(warnings(&ctx).is_empty());
+    }
 }
@@ -18,7 +18,7 @@
 use crate::context::Context;
 use crate::error::{ErrKind, Error};
 use crate::instance::{FromObjectInstance, ObjectInstance};
-use crate::instruction::{Block, InstrKind, Instruction};
+use crate::instruction::{BinaryOp, Block, InstrKind, Instruction};
 use crate::location::SpanTuple;
 use crate::typechecker::{CheckedType, TypeCheck, TypeCtx, TypeId};
 use crate::value::JkBool;
@@ -51,6 +51,34 @@ impl IfElse {
     pub fn set_location(&mut self, location: SpanTuple) {
         self.location = Some(location)
     }
+
+    /// Value of the condition, if it is known before executing the program
+    fn constant_condition(&self) -> Option<bool> {
+        match self.condition.downcast_ref::<BinaryOp>() {
+            Some(op) => op.constant_bool(),
+            None => self
+                .condition
+                .downcast_ref::<JkBool>()
+                .map(JkBool::rust_value),
+        }
+    }
+
+    /// If the condition is constant, take the only branch which can ever be executed
+    /// out of the `IfElse`, so that it can replace it. An `if false` without an
+    /// `else` block folds to an empty block
+    pub fn fold(&mut self) -> Option<Block> {
+        let branch = match self.constant_condition()? {
+            true => std::mem::take(&mut self.if_body),
+            false => self.else_body.take().unwrap_or_else(|| {
+                let mut empty = Block::new();
+                empty.set_cached_type(CheckedType::Void);
+
+                empty
+            }),
+        };
+
+        Some(branch)
+    }
 }
 
 impl Instruction for IfElse {
@@ -60,6 +88,34 @@ impl Instruction for IfElse {
         self.if_body.kind()
     }
 
+    fn diverges(&self) -> bool {
+        // An `if` without an `else` might not be entered, so it only diverges if both
+        // of its branches do
+        match &self.else_body {
+            Some(else_body) => self.if_body.diverges() && else_body.diverges(),
+            None => false,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn Instruction> {
+        let mut children: Vec<&dyn Instruction> = vec![self.condition.as_ref(), &self.if_body];
+        if let Some(else_body) = &self.else_body {
+            children.push(else_body);
+        }
+
+        children
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        let mut children: Vec<&mut dyn Instruction> =
+            vec![self.condition.as_mut(), &mut self.if_body];
+        if let Some(else_body) = &mut self.else_body {
+            children.push(else_body);
+        }
+
+        children
+    }
+
     fn print(&self) -> String {
         let base = format!("if {} {}", self.condition.print(), self.if_body.print());
 
@@ -79,7 +135,7 @@ impl Instruction for IfElse {
                 Some(b) => b.execute(ctx),
                 // FIXME: Fix logic: If an `if` returns something, the else should too.
                 // if there is no else, then error out
-                None => None,
+                None => Some(ObjectInstance::void()),
             }
         }
     }
@@ -94,7 +150,7 @@ impl TypeCheck for IfElse {
         let bool_checkedtype = CheckedType::Resolved(TypeId::from("bool"));
         let cond_ty = self.condition.type_of(ctx)?;
 
-        if cond_ty != bool_checkedtype {
+        if !cond_ty.is_assignable_to(&bool_checkedtype) {
             return Err(Error::new(ErrKind::TypeChecker)
                 .with_msg(format!(
                     "if condition should be a boolean, not a `{cond_ty}`"
@@ -112,7 +168,16 @@ impl TypeCheck for IfElse {
             (CheckedType::Void, None) => Ok(CheckedType::Void),
             (if_ty, Some(else_ty)) => {
                 let else_ty = else_ty?;
-                if if_ty != else_ty {
+                let compatible = else_ty.is_assignable_to(&if_ty);
+                if !compatible && (if_ty == CheckedType::Void || else_ty == CheckedType::Void) {
+                    // One of the blocks is a statement while the other returns a value
+                    Err(Error::new(ErrKind::TypeChecker)
+                        .with_msg(format!(
+                            "`if` and `else` blocks must either both return a value or both be statements: \
+                            `if` block has type {if_ty}, `else` block has type {else_ty}",
+                        ))
+                        .with_loc(self.location.clone()))
+                } else if !compatible {
                     Err(Error::new(ErrKind::TypeChecker)
                         .with_msg(format!(
                             "incompatible types for `if` and `else` block: {if_ty} and {else_ty}",
@@ -184,9 +249,7 @@ mod tests {
 
         let mut if_block = Block::new();
         let mut else_block = Block::new();
-        if_block.set_statement(false);
         if_block.add_instruction(Box::new(JkInt::from(42)));
-        else_block.set_statement(false);
         else_block.add_instruction(Box::new(JkInt::from(69)));
 
         let if_else = IfElse::new(Box::new(JkBool::from(true)), if_block, Some(else_block));
@@ -206,9 +269,7 @@ mod tests {
 
         let mut if_block = Block::new();
         let mut else_block = Block::new();
-        if_block.set_statement(false);
         if_block.add_instruction(Box::new(JkInt::from(42)));
-        else_block.set_statement(false);
         else_block.add_instruction(Box::new(JkInt::from(69)));
 
         let if_else = IfElse::new(Box::new(JkBool::from(false)), if_block, Some(else_block));
@@ -254,6 +315,146 @@ mod tests {
         };
     }
 
+    #[test]
+    fn tc_if_else_statements() {
+        jinko! {
+            if true {
+                println("a")
+            } else {
+                println("b")
+            }
+        };
+    }
+
+    #[test]
+    fn t_if_else_statement_side_effects() {
+        use crate::instance::ToObjectInstance;
+        use crate::value::JkInt;
+
+        let ctx = jinko! {
+            mut x = 0;
+            if false {
+                x = 1
+            } else {
+                x = 2
+            }
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"x".into()).unwrap().instance(),
+            JkInt::from(2).to_instance()
+        );
+    }
+
+    #[test]
+    fn tc_if_else_statement_and_value() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let mut if_else =
+            crate::parser::constructs::expr(crate::span!("if true { 15 } else { x = 14; }"))
+                .unwrap()
+                .1;
+
+        let err = ctx.type_check(if_else.as_mut()).unwrap_err().to_string();
+
+        assert!(err.contains("void"));
+        assert!(err.contains("int"));
+    }
+
+    #[test]
+    fn t_if_without_else_yields_void() {
+        use crate::value::JkBool;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let if_block = IfElse::new(Box::new(JkBool::from(false)), Block::new(), None);
+
+        assert!(if_block.execute(&mut ctx).unwrap().is_void());
+    }
+
+    fn typechecked_block(input: &str) -> Block {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let mut block = crate::parser::constructs::expr(nom_locate::LocatedSpan::new_extra(
+            input,
+            crate::location::Source::Empty,
+        ))
+        .unwrap()
+        .1;
+
+        ctx.type_check(block.as_mut()).unwrap();
+
+        block.downcast_ref::<Block>().unwrap().clone()
+    }
+
+    #[test]
+    fn t_fold_true_condition() {
+        let block = typechecked_block("{ if true { 15 } else { 14 } }");
+        let folded = block.instructions()[0].downcast_ref::<Block>().unwrap();
+
+        assert_eq!(folded.print(), "{\n    15\n}");
+    }
+
+    #[test]
+    fn t_fold_constant_comparison() {
+        let block = typechecked_block("{ if 1 > 2 { 15 } else { 14 } }");
+        let folded = block.instructions()[0].downcast_ref::<Block>().unwrap();
+
+        assert_eq!(folded.print(), "{\n    14\n}");
+    }
+
+    #[test]
+    fn t_fold_const_comparison() {
+        let block = typechecked_block("{ const LIMIT = 15; if LIMIT > 10 { 15 } else { 14 } }");
+        let folded = block.instructions()[1].downcast_ref::<Block>().unwrap();
+
+        assert_eq!(folded.print(), "{\n    15\n}");
+    }
+
+    #[test]
+    fn t_fold_false_condition_without_else() {
+        let block = typechecked_block("{ if 2 <= 1 { println(\"a\") } }");
+        let folded = block.instructions()[0].downcast_ref::<Block>().unwrap();
+
+        assert!(folded.instructions().is_empty());
+    }
+
+    #[test]
+    fn t_no_fold_non_constant_condition() {
+        let block = typechecked_block("{ x = 1; if x < 2 { 15 } else { 14 } }");
+
+        assert!(block.instructions()[1].downcast_ref::<IfElse>().is_some());
+    }
+
+    #[test]
+    fn t_folded_if_else_execute() {
+        use crate::instance::ToObjectInstance;
+        use crate::value::JkInt;
+
+        let ctx = jinko! {
+            x = if 2.5 > 1.0 { 15 } else { 14 };
+            mut y = 0;
+            if true { y = 1 } else { y = 2 }
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"x".into()).unwrap().instance(),
+            JkInt::from(15).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"y".into()).unwrap().instance(),
+            JkInt::from(1).to_instance()
+        );
+    }
+
+    #[test]
+    fn tc_folded_dead_branch_is_typechecked() {
+        jinko_fail! {
+            if true {
+                15
+            } else {
+                undeclared_variable
+            }
+        };
+    }
+
     #[test]
     fn tc_if_else_mismatched_types() {
         jinko_fail! {
@@ -264,4 +465,98 @@ mod tests {
             }
         };
     }
+
+    #[test]
+    fn t_if_else_assignment() {
+        use crate::instance::ToObjectInstance;
+        use crate::typechecker::TypeId;
+        use crate::value::JkInt;
+
+        let mut ctx = jinko! {
+            x = if true { 12 } else { 13 };
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"x".into()).unwrap().instance(),
+            JkInt::from(12).to_instance()
+        );
+        assert_eq!(
+            ctx.typechecker.get_var("x"),
+            Some(&CheckedType::Resolved(TypeId::from("int")))
+        );
+    }
+
+    #[test]
+    fn t_multiline_block_assignment() {
+        use crate::instance::ToObjectInstance;
+        use crate::value::JkInt;
+
+        let ctx = jinko! {
+            func pick(b: bool) -> int {
+                value = if b {
+                    base = 10;
+                    base * 2
+                } else {
+                    base = 3;
+                    base + 1
+                };
+                value
+            }
+
+            a = pick(true);
+            b = pick(false);
+            c = {
+                tmp = a + b;
+                tmp * 2
+            };
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkInt::from(20).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"b".into()).unwrap().instance(),
+            JkInt::from(4).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"c".into()).unwrap().instance(),
+            JkInt::from(48).to_instance()
+        );
+    }
+
+    #[test]
+    fn tc_if_else_assignment_mismatched_types() {
+        jinko_fail! {
+            func pick(b: bool) -> int {
+                x = if b { 12 } else { 'c' };
+                0
+            }
+        };
+    }
+
+    #[test]
+    fn t_conditional_expression() {
+        use crate::instance::FromObjectInstance;
+        use crate::value::JkInt;
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        let res = ctx.eval("1 < 2 ? 10 : 20").unwrap().unwrap();
+        assert_eq!(JkInt::from_instance(&res).rust_value(), 10);
+    }
+
+    #[test]
+    fn tc_conditional_expression_mismatched_arms() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        assert!(ctx.eval("true ? 1 : 'c'").is_err());
+        let ternary_err = ctx.error_handler.errors()[0].clone();
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        assert!(ctx.eval("if true { 1 } else { 'c' }").is_err());
+        let if_else_err = ctx.error_handler.errors()[0].clone();
+
+        assert_eq!(ternary_err.kind(), if_else_err.kind());
+        assert_eq!(ternary_err.msg(), if_else_err.msg());
+    }
 }
@@ -162,7 +162,7 @@ impl Instruction for Incl {
             instr.execute(ctx);
         });
 
-        None
+        Some(ObjectInstance::void())
     }
 
     fn location(&self) -> Option<&SpanTuple> {
@@ -173,17 +173,12 @@ impl Instruction for Incl {
 impl TypeCheck for Incl {
     // FIXME: We need to not add the path to the interpreter here
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        // Without an explicit base, includes are relative to the including file. If
+        // there is none, such as in dynamic mode, they are relative to where the
+        // context was started
         let base = match &self.base {
             Some(b) => b.clone(),
-            None => match ctx.path() {
-                // Get the parent directory of the context's source file. We can unwrap
-                // since there's always a base
-                Some(path) => path.parent().unwrap().to_owned(),
-                // The ctx doesn't have an associated source file. Therefore, we
-                // load from where the context was started. This is the case if we're
-                // in dynamic mode for example
-                None => PathBuf::new(),
-            },
+            None => ctx.includes().base_dir(),
         };
 
         let final_path = match self.get_final_path(&base) {
@@ -195,19 +190,32 @@ impl TypeCheck for Incl {
             }
         };
 
-        if ctx.is_included(&final_path) {
+        // Canonicalize the path so that the same file, reached through two different
+        // paths, only gets included once. This also makes includes performed by the
+        // included file relative to its own directory, regardless of the current
+        // working directory
+        let final_path = final_path.canonicalize().unwrap_or(final_path);
+
+        // Files which have been fully included are skipped. Files which are still
+        // being included are part of an include cycle, which `enter` reports
+        if ctx.includes().is_included(&final_path) {
             return Ok(CheckedType::Void);
         }
 
-        let instructions = self.fetch_instructions(&final_path, ctx.reader())?;
-
-        self.instructions = instructions;
+        // The included file becomes the current one while typechecking its content
+        ctx.includes()
+            .enter(final_path.clone())
+            .map_err(|e| e.with_loc(self.location.clone()))?;
 
-        let old_path = ctx.path().cloned();
-        ctx.include(final_path.clone());
+        let instructions = match self.fetch_instructions(&final_path, ctx.reader()) {
+            Ok(instructions) => instructions,
+            Err(e) => {
+                ctx.includes().exit();
+                return Err(e);
+            }
+        };
 
-        // Temporarily change the path of the context
-        ctx.set_path(Some(final_path));
+        self.instructions = instructions;
 
         self.instructions.iter_mut().for_each(|instr| {
             if let Err(e) = instr.type_of(ctx) {
@@ -215,8 +223,7 @@ impl TypeCheck for Incl {
             }
         });
 
-        // Reset the old path before leaving the instruction
-        ctx.set_path(old_path);
+        ctx.includes().exit();
 
         Ok(CheckedType::Void)
     }
@@ -236,6 +243,7 @@ impl TypeCheck for Incl {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::TempDir;
     use crate::{jinko, jinko_fail};
 
     #[test]
@@ -263,4 +271,155 @@ mod tests {
             incl stdlib as std;
         };
     }
+
+    /// Create a chain of `depth` source files in a temporary directory, each one
+    /// including the next, and return the directory
+    fn include_chain(name: &str, depth: usize) -> TempDir {
+        let dir = TempDir::new(name);
+
+        for i in 0..depth {
+            let content = match i + 1 == depth {
+                true => String::from("func leaf() {}"),
+                false => format!("incl link{}", i + 1),
+            };
+            dir.write(&format!("link{i}.jk"), &content);
+        }
+
+        dir
+    }
+
+    fn include_with_max_depth(dir: &Path, max_depth: usize) -> Result<(), Error> {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_path(Some(dir.join("main.jk")));
+        ctx.set_max_include_depth(max_depth);
+
+        crate::parser::parse(&mut ctx, "incl link0", Source::Input("incl link0"))?;
+
+        ctx.check()
+    }
+
+    #[test]
+    fn include_relative_to_including_file() {
+        let dir = TempDir::new("include_relative_to_including_file");
+        dir.write("sub/lib.jk", "incl helper");
+        dir.write("sub/helper.jk", "func helper() -> int { 15 }");
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_path(Some(dir.path().join("main.jk")));
+
+        let input = "incl sub; helper()";
+        crate::parser::parse(&mut ctx, input, Source::Input(input)).unwrap();
+
+        assert!(ctx.execute().is_ok());
+    }
+
+    #[test]
+    fn typechecker_shares_context_includes() {
+        let dir = TempDir::new("typechecker_shares_context_includes");
+        let helper = dir.write("helper.jk", "func helper() -> int { 15 }");
+
+        // Only the context is given a path: The typechecker resolves includes
+        // relative to it without being told about it
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_path(Some(dir.path().join("main.jk")));
+
+        assert_eq!(ctx.typechecker.includes().base_dir(), dir.path());
+
+        let input = "incl helper; incl helper; helper()";
+        crate::parser::parse(&mut ctx, input, Source::Input(input)).unwrap();
+
+        assert!(ctx.execute().is_ok());
+        assert!(ctx.is_included(&helper.canonicalize().unwrap()));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn include_same_file_through_different_paths() {
+        let dir = TempDir::new("include_same_file_through_different_paths");
+        dir.write("sub/lib.jk", "func helper() -> int { 15 }");
+
+        std::os::unix::fs::symlink(dir.path().join("sub"), dir.path().join("link")).unwrap();
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_path(Some(dir.path().join("main.jk")));
+
+        let input = "incl sub; incl link; helper()";
+        crate::parser::parse(&mut ctx, input, Source::Input(input)).unwrap();
+
+        assert!(ctx.execute().is_ok());
+    }
+
+    /// Create the given source files in a temporary directory and include `main` from
+    /// it, returning the errors emitted while typechecking
+    fn include_files(name: &str, files: &[(&str, &str)]) -> Vec<Error> {
+        let dir = TempDir::new(name);
+
+        for (file, content) in files {
+            dir.write(&format!("{file}.jk"), content);
+        }
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_path(Some(dir.path().join("main.jk")));
+
+        crate::parser::parse(&mut ctx, "incl a", Source::Input("incl a")).unwrap();
+
+        let _ = ctx.check();
+
+        ctx.error_handler.errors().to_vec()
+    }
+
+    fn is_cycle_error(e: &Error) -> bool {
+        e.kind() == ErrKind::Context
+            && e.msg()
+                .is_some_and(|msg| msg.starts_with("cyclic include detected"))
+    }
+
+    #[test]
+    fn include_two_file_cycle() {
+        let errs = include_files(
+            "include_two_file_cycle",
+            &[("a", "incl b"), ("b", "incl a")],
+        );
+
+        assert!(errs.iter().any(is_cycle_error));
+    }
+
+    #[test]
+    fn include_three_file_cycle() {
+        let errs = include_files(
+            "include_three_file_cycle",
+            &[("a", "incl b"), ("b", "incl c"), ("c", "incl a")],
+        );
+
+        assert!(errs.iter().any(is_cycle_error));
+    }
+
+    #[test]
+    fn include_diamond() {
+        let errs = include_files(
+            "include_diamond",
+            &[
+                ("a", "incl b; incl c"),
+                ("b", "incl d"),
+                ("c", "incl d"),
+                ("d", "func leaf() {}"),
+            ],
+        );
+
+        assert!(errs.is_empty());
+    }
+
+    #[test]
+    fn include_chain_within_max_depth() {
+        let dir = include_chain("include_chain_within_max_depth", 3);
+
+        assert!(include_with_max_depth(dir.path(), 3).is_ok());
+    }
+
+    #[test]
+    fn include_chain_exceeding_max_depth() {
+        let dir = include_chain("include_chain_exceeding_max_depth", 5);
+
+        assert!(include_with_max_depth(dir.path(), 3).is_err());
+    }
 }
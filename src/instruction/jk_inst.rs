@@ -5,10 +5,11 @@
 
 use crate::context::Context;
 use crate::error::{ErrKind, Error};
-use crate::instance::ObjectInstance;
+use crate::instance::{FromObjectInstance, ObjectInstance, ToObjectInstance};
 use crate::instruction::{FunctionCall, InstrKind, Instruction};
 use crate::location::SpanTuple;
-use crate::typechecker::{CheckedType, TypeCheck, TypeCtx};
+use crate::typechecker::{CheckedType, TypeCheck, TypeCtx, TypeId};
+use crate::value::{JkInt, JkString};
 
 /// The potential ctx instructions
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -16,13 +17,17 @@ pub enum JkInstKind {
     Dump,
     Quit,
     Ir,
+    Breakpoint,
+    Types,
+    Env,
 }
 
 #[derive(Clone)]
 pub struct JkInst {
     kind: JkInstKind,
-    _args: Vec<Box<dyn Instruction>>,
+    args: Vec<Box<dyn Instruction>>,
     location: Option<SpanTuple>,
+    cached_type: Option<CheckedType>,
 }
 
 impl JkInst {
@@ -34,24 +39,118 @@ impl JkInst {
             "dump" => JkInstKind::Dump,
             "quit" => JkInstKind::Quit,
             "ir" => JkInstKind::Ir,
-            // FIXME: Fix location
+            "breakpoint" => JkInstKind::Breakpoint,
+            "types" => JkInstKind::Types,
+            "env" => JkInstKind::Env,
             _ => {
                 return Err(Error::new(ErrKind::Parsing)
-                    .with_msg(format!("unknown ctx directive @{func_name}")))
+                    .with_msg(format!("unknown ctx directive @{func_name}"))
+                    .with_loc(fc.location().cloned()))
             }
         };
 
-        Ok(JkInst {
+        let inst = JkInst {
             kind,
-            _args: fc.args().clone(),
+            args: fc.args().clone(),
             location: fc.location().cloned(),
-        })
+            cached_type: None,
+        };
+
+        if inst.kind == JkInstKind::Env {
+            inst.env_var_name()?;
+        }
+
+        Ok(inst)
+    }
+
+    /// `@env` takes exactly one argument: The name of the environment variable to
+    /// read, as a string literal
+    fn env_var_name(&self) -> Result<&str, Error> {
+        match self.args.as_slice() {
+            [name] => match name.downcast_ref::<JkString>() {
+                Some(name) => Ok(name.0.as_str()),
+                None => Err(Error::new(ErrKind::Parsing)
+                    .with_msg(format!(
+                        "`@env` expects a string literal, found `{}`",
+                        name.print()
+                    ))
+                    .with_loc(name.location().cloned().or_else(|| self.location.clone()))),
+            },
+            _ => Err(Error::new(ErrKind::Parsing)
+                .with_msg(format!(
+                    "`@env` takes exactly one argument, got {}",
+                    self.args.len()
+                ))
+                .with_loc(self.location.clone())),
+        }
+    }
+
+    /// The name given to `@env` must be a `string`
+    fn resolve_env_var(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        let string = CheckedType::Resolved(TypeId::from("string"));
+
+        let name = match self.args.as_mut_slice() {
+            [name] => name,
+            _ => {
+                return Err(Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!(
+                        "`@env` takes exactly one argument, got {}",
+                        self.args.len()
+                    ))
+                    .with_loc(self.location.clone()))
+            }
+        };
+
+        match name.type_of(ctx)? {
+            ty if ty == string => Ok(string),
+            name_ty => Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "invalid variable name given to `@env`: expected `string`, found `{name_ty}`"
+                ))
+                .with_loc(name.location().cloned())),
+        }
+    }
+
+    /// `@quit` takes an optional exit code, which must be an integer
+    fn resolve_quit_code(&mut self, ctx: &mut TypeCtx) -> Result<(), Error> {
+        let code = match self.args.as_mut_slice() {
+            [] => return Ok(()),
+            [code] => code,
+            _ => {
+                return Err(Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!(
+                        "`@quit` takes at most one argument, got {}",
+                        self.args.len()
+                    ))
+                    .with_loc(self.location.clone()))
+            }
+        };
+
+        match code.type_of(ctx)? {
+            CheckedType::Resolved(ty) if ty.id() == "int" => Ok(()),
+            code_ty => Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "invalid exit code given to `@quit`: expected `int`, found `{code_ty}`"
+                ))
+                .with_loc(code.location().cloned())),
+        }
     }
 }
 
 impl Instruction for JkInst {
     fn kind(&self) -> InstrKind {
-        InstrKind::Statement
+        match self.kind {
+            JkInstKind::Env => InstrKind::Expression(None),
+            _ => InstrKind::Statement,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn Instruction> {
+        self.args.iter().map(|arg| arg.as_ref()).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        self.args.iter_mut().map(|arg| arg.as_mut()).collect()
     }
 
     fn print(&self) -> String {
@@ -59,21 +158,59 @@ impl Instruction for JkInst {
             JkInstKind::Dump => "@dump",
             JkInstKind::Quit => "@quit",
             JkInstKind::Ir => "@ir",
+            JkInstKind::Breakpoint => "@breakpoint",
+            JkInstKind::Types => "@types",
+            JkInstKind::Env => "@env",
         }
         .to_string()
     }
 
     fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
         match self.kind {
-            JkInstKind::Dump => println!("{}", ctx.print()),
-            JkInstKind::Quit => std::process::exit(0),
-            JkInstKind::Ir => eprintln!("usage: {:?} <statement|expr>", JkInstKind::Ir),
+            JkInstKind::Dump => {
+                let dump = ctx.print();
+                let _ = writeln!(ctx.stdout(), "{dump}");
+            }
+            JkInstKind::Quit => {
+                let code = match self.args.first() {
+                    Some(code) => {
+                        let value = JkInt::from_instance(&code.execute_expression(ctx)?).0;
+                        match i32::try_from(value) {
+                            Ok(code) => code,
+                            Err(_) => {
+                                ctx.error(
+                                    Error::new(ErrKind::Overflow)
+                                        .with_msg(format!("invalid exit code: `{value}`"))
+                                        .with_loc(code.location().cloned()),
+                                );
+                                return None;
+                            }
+                        }
+                    }
+                    None => 0,
+                };
+
+                ctx.quit(code);
+            }
+            JkInstKind::Ir => {
+                let _ = writeln!(ctx.stderr(), "usage: {:?} <statement|expr>", JkInstKind::Ir);
+            }
+            JkInstKind::Breakpoint => ctx.breakpoint(),
+            // The type context is only available during typechecking, which is when
+            // `@types` gets executed
+            JkInstKind::Types => {}
+            // An unset variable reads as an empty string
+            JkInstKind::Env => {
+                let name = JkString::from_instance(&self.args[0].execute_expression(ctx)?);
+                let value = std::env::var(name.0).unwrap_or_default();
+
+                return Some(JkString::from(value).to_instance());
+            }
         };
 
-        // FIXME: Is that true?
-        // JinkInsts cannot return anything. They simply act directly from the context,
-        // on the context.
-        None
+        // Apart from `@env`, JinkInsts are statements. They simply act directly
+        // from the context, on the context.
+        Some(ObjectInstance::void())
     }
 
     fn location(&self) -> Option<&SpanTuple> {
@@ -82,14 +219,26 @@ impl Instruction for JkInst {
 }
 
 impl TypeCheck for JkInst {
-    fn resolve_type(&mut self, _ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+    fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        match self.kind {
+            JkInstKind::Types => {
+                let types = ctx.print();
+                ctx.output_line(&types);
+            }
+            JkInstKind::Quit => self.resolve_quit_code(ctx)?,
+            JkInstKind::Env => return self.resolve_env_var(ctx),
+            _ => {}
+        }
+
         Ok(CheckedType::Void)
     }
 
-    fn set_cached_type(&mut self, _ty: CheckedType) {}
+    fn set_cached_type(&mut self, ty: CheckedType) {
+        self.cached_type = Some(ty);
+    }
 
     fn cached_type(&self) -> Option<&CheckedType> {
-        Some(&CheckedType::Void)
+        self.cached_type.as_ref()
     }
 }
 
@@ -97,7 +246,7 @@ impl TypeCheck for JkInst {
 mod tests {
     use super::*;
     use crate::parser::constructs;
-    use crate::{jinko, span};
+    use crate::{jinko, jinko_fail, span};
 
     #[test]
     fn t_invalid_jkinst() {
@@ -132,4 +281,232 @@ mod tests {
             @dump();
         };
     }
+
+    #[test]
+    fn t_quit_sets_quit_code() {
+        let ctx = jinko! {
+            mut a = 1;
+            @quit(3);
+            a = 2;
+        };
+
+        assert_eq!(ctx.quit_code(), Some(3));
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkInt::from(1).to_instance()
+        );
+    }
+
+    #[test]
+    fn t_quit_default_code() {
+        let ctx = jinko! {
+            @quit();
+        };
+
+        assert_eq!(ctx.quit_code(), Some(0));
+    }
+
+    #[test]
+    fn t_quit_from_function() {
+        let ctx = jinko! {
+            mut a = 1;
+            func stop() {
+                @quit(2);
+            }
+            stop();
+            a = 2;
+        };
+
+        assert_eq!(ctx.quit_code(), Some(2));
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkInt::from(1).to_instance()
+        );
+    }
+
+    #[test]
+    fn t_quit_code_overflow() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert!(ctx.eval("@quit(4294967297)").is_err());
+        assert_eq!(ctx.error_handler.errors()[0].kind(), ErrKind::Overflow);
+        assert_eq!(ctx.quit_code(), None);
+    }
+
+    #[test]
+    fn t_quit_does_not_stop_later_evaluations() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        ctx.eval("@quit(3)").unwrap();
+        assert_eq!(ctx.quit_code(), Some(3));
+
+        assert_eq!(ctx.eval("1 + 2"), Ok(Some(JkInt::from(3).to_instance())));
+        assert_eq!(ctx.quit_code(), None);
+    }
+
+    #[test]
+    fn t_quit_generic_call_argument() {
+        let ctx = jinko! {
+            func code[T]() -> int { 4 }
+            func stop[T]() {
+                @quit(code[T]());
+            }
+            stop[int]();
+        };
+
+        assert_eq!(ctx.quit_code(), Some(4));
+    }
+
+    #[test]
+    fn tc_quit_invalid_code() {
+        jinko_fail! {
+            @quit('c');
+        };
+
+        jinko_fail! {
+            @quit(1, 2);
+        };
+    }
+
+    #[test]
+    fn t_unknown_directive_location() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let input = "a = 15;\n  @crab()";
+
+        let err = crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input))
+            .unwrap_err();
+
+        let loc = err.loc().unwrap();
+        assert_eq!(loc.start().line(), 2);
+        assert_eq!(loc.start().column(), 3);
+    }
+
+    #[test]
+    fn t_types_prints_type_context() {
+        use crate::utils::SharedBuffer;
+
+        let stdout = SharedBuffer::default();
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_stdout(stdout.clone());
+
+        ctx.eval("a = 15; b = 'b'; @types(); c = 1.5").unwrap();
+
+        let types = stdout.contents();
+        let a = types.lines().find(|line| line.starts_with("a: ")).unwrap();
+        let b = types.lines().find(|line| line.starts_with("b: ")).unwrap();
+
+        assert!(a.contains("int"));
+        assert!(b.contains("char"));
+        assert!(types.contains("type int"));
+        // The type context is printed as it is when `@types` is typechecked
+        assert!(!types.lines().any(|line| line.starts_with("c: ")));
+    }
+
+    #[test]
+    fn t_directives_output_sinks() {
+        use crate::utils::SharedBuffer;
+
+        let stdout = SharedBuffer::default();
+        let stderr = SharedBuffer::default();
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_stdout(stdout.clone());
+        ctx.set_stderr(stderr.clone());
+
+        ctx.eval("dumped_var = 15; @dump()").unwrap();
+        assert!(stdout.contents().contains("dumped_var"));
+        assert!(stderr.contents().is_empty());
+
+        ctx.eval("@ir()").unwrap();
+        assert!(stderr.contents().contains("usage"));
+        assert!(!stdout.contents().contains("usage"));
+
+        ctx.eval("typed_var = 'c'; @types()").unwrap();
+        assert!(stdout.contents().contains("typed_var: char"));
+        assert!(!stderr.contents().contains("typed_var"));
+    }
+
+    #[test]
+    fn t_dump_prints_context() {
+        use crate::utils::SharedBuffer;
+
+        let stdout = SharedBuffer::default();
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_stdout(stdout.clone());
+
+        ctx.eval("func id(x: int) -> int { x } dumped_var = id(15); @dump()")
+            .unwrap();
+
+        assert_eq!(stdout.contents(), format!("{}\n", ctx.print()));
+        assert!(stdout.contents().contains("func id(x: int) -> int"));
+    }
+
+    #[test]
+    fn t_breakpoint_invokes_callback() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let hits = Rc::new(Cell::new(0));
+        let callback_hits = hits.clone();
+
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_breakpoint_callback(move |_| callback_hits.set(callback_hits.get() + 1));
+
+        let input = "a = 15; @breakpoint(); b = a";
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+        ctx.execute().unwrap();
+
+        assert_eq!(hits.get(), 1);
+    }
+
+    #[test]
+    fn t_env_reads_variable() {
+        std::env::set_var("JINKO_TEST_ENV_SET", "jinko");
+
+        let ctx = jinko! {
+            value = @env("JINKO_TEST_ENV_SET");
+            len = value.len();
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"value".into()).unwrap().instance(),
+            JkString::from("jinko").to_instance()
+        );
+    }
+
+    #[test]
+    fn t_env_unset_variable() {
+        std::env::remove_var("JINKO_TEST_ENV_UNSET");
+
+        let ctx = jinko! {
+            value = @env("JINKO_TEST_ENV_UNSET");
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"value".into()).unwrap().instance(),
+            JkString::from("").to_instance()
+        );
+    }
+
+    #[test]
+    fn t_env_invalid_arguments() {
+        for input in ["@env()", "@env(\"A\", \"B\")", "@env(15)", "@env(name)"] {
+            let input = nom_locate::LocatedSpan::new_extra(input, crate::location::Source::Empty);
+
+            assert!(constructs::expr(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn tc_env_is_string() {
+        jinko_fail! {
+            func double(x: int) -> int { x + x }
+            double(@env("HOME"));
+        };
+        jinko_fail! {
+            value = @env("HOME") + 1;
+        };
+    }
 }
@@ -0,0 +1,291 @@
+//! `Match`es dispatch on the value of an `int` or `bool` scrutinee. Each arm compares
+//! the scrutinee against a literal pattern, and the last arm is a mandatory default
+//! arm, executed when no other pattern matches.
+//!
+//! ```ignore
+//! x = match n {
+//!     0 => "zero",
+//!     1 => "one",
+//!     _ => "many",
+//! };
+//! ```
+//!
+//! Conceptually, a match is a chain of `if`/`else` comparisons, but its scrutinee is
+//! only ever evaluated once.
+
+use crate::context::Context;
+use crate::error::{ErrKind, Error};
+use crate::instance::ObjectInstance;
+use crate::instruction::{Block, InstrKind, Instruction};
+use crate::location::SpanTuple;
+use crate::typechecker::{CheckedType, TypeCheck, TypeCtx};
+
+/// Types that can be matched on
+const MATCH_TYPES: [&str; 2] = ["int", "bool"];
+
+/// An arm of a match: A literal pattern, and the body executed if the scrutinee is
+/// equal to that pattern
+pub type MatchArm = (Box<dyn Instruction>, Block);
+
+#[derive(Clone)]
+pub struct Match {
+    scrutinee: Box<dyn Instruction>,
+    arms: Vec<MatchArm>,
+    default: Block,
+    cached_type: Option<CheckedType>,
+    location: Option<SpanTuple>,
+}
+
+impl Match {
+    /// Create a new match over `scrutinee`. `default` is executed if none of the
+    /// patterns of `arms` match
+    pub fn new(scrutinee: Box<dyn Instruction>, arms: Vec<MatchArm>, default: Block) -> Match {
+        Match {
+            scrutinee,
+            arms,
+            default,
+            cached_type: None,
+            location: None,
+        }
+    }
+
+    pub fn set_location(&mut self, location: SpanTuple) {
+        self.location = Some(location)
+    }
+
+    /// Typecheck the scrutinee and the patterns of the match
+    fn check_patterns(&mut self, ctx: &mut TypeCtx) -> Result<(), Error> {
+        let scrutinee_ty = self.scrutinee.type_of(ctx)?;
+
+        match &scrutinee_ty {
+            CheckedType::Resolved(ty) if MATCH_TYPES.contains(&ty.id()) => {}
+            _ => {
+                return Err(Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!(
+                        "cannot match on a value of type `{scrutinee_ty}`: expected `int` or `bool`"
+                    ))
+                    .with_loc(self.scrutinee.location().cloned()))
+            }
+        }
+
+        for (pattern, _) in self.arms.iter_mut() {
+            let pattern_ty = pattern.type_of(ctx)?;
+            if pattern_ty != scrutinee_ty {
+                return Err(Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!(
+                        "invalid pattern `{}` of type `{pattern_ty}` when matching on a value of type `{scrutinee_ty}`",
+                        pattern.print()
+                    ))
+                    .with_loc(pattern.location().cloned().or_else(|| self.location.clone())));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Instruction for Match {
+    fn kind(&self) -> InstrKind {
+        // Like `IfElse`, the typechecker ensures that all arms return the same thing
+        self.default.kind()
+    }
+
+    fn diverges(&self) -> bool {
+        self.default.diverges() && self.arms.iter().all(|(_, body)| body.diverges())
+    }
+
+    fn children(&self) -> Vec<&dyn Instruction> {
+        let mut children: Vec<&dyn Instruction> = vec![self.scrutinee.as_ref()];
+        self.arms.iter().for_each(|(pattern, body)| {
+            children.push(pattern.as_ref());
+            children.push(body);
+        });
+        children.push(&self.default);
+
+        children
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        let mut children: Vec<&mut dyn Instruction> = vec![self.scrutinee.as_mut()];
+        self.arms.iter_mut().for_each(|(pattern, body)| {
+            children.push(pattern.as_mut());
+            children.push(body);
+        });
+        children.push(&mut self.default);
+
+        children
+    }
+
+    fn print(&self) -> String {
+        let arm = |pattern: String, body: &Block| {
+            format!(
+                "    {} => {},\n",
+                pattern,
+                body.print().replace('\n', "\n    ")
+            )
+        };
+
+        let mut base = format!("match {} {{\n", self.scrutinee.print());
+        self.arms
+            .iter()
+            .for_each(|(pattern, body)| base.push_str(&arm(pattern.print(), body)));
+        base.push_str(&arm(String::from("_"), &self.default));
+        base.push('}');
+
+        base
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
+        let value = self.scrutinee.execute_expression(ctx)?;
+
+        for (pattern, body) in self.arms.iter() {
+            if pattern.execute_expression(ctx)? == value {
+                return body.execute(ctx);
+            }
+        }
+
+        self.default.execute(ctx)
+    }
+
+    fn location(&self) -> Option<&SpanTuple> {
+        self.location.as_ref()
+    }
+}
+
+impl TypeCheck for Match {
+    fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        self.check_patterns(ctx)?;
+
+        let mut arm_tys = vec![];
+        for (_, body) in self.arms.iter_mut() {
+            arm_tys.push(body.type_of(ctx)?);
+        }
+        let default_ty = self.default.type_of(ctx)?;
+
+        let expected = arm_tys
+            .first()
+            .cloned()
+            .unwrap_or_else(|| default_ty.clone());
+        match arm_tys
+            .into_iter()
+            .chain(std::iter::once(default_ty))
+            .find(|ty| !ty.is_assignable_to(&expected))
+        {
+            Some(ty) => Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "incompatible types for `match` arms: {expected} and {ty}"
+                ))
+                .with_loc(self.location.clone())),
+            None => Ok(expected),
+        }
+    }
+
+    fn set_cached_type(&mut self, ty: CheckedType) {
+        self.cached_type = Some(ty)
+    }
+
+    fn cached_type(&self) -> Option<&CheckedType> {
+        self.cached_type.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::FromObjectInstance;
+    use crate::value::{JkBool, JkInt, JkString};
+    use crate::{jinko, jinko_fail};
+
+    fn arm(value: i64) -> Block {
+        Block::builder()
+            .instruction(Box::new(JkInt::from(value)))
+            .build()
+    }
+
+    #[test]
+    fn t_match_print() {
+        let jk_match = Match::new(
+            Box::new(JkBool::from(true)),
+            vec![(Box::new(JkBool::from(false)), arm(0))],
+            arm(1),
+        );
+
+        assert_eq!(
+            jk_match.print(),
+            "match true {\n    false => {\n        0\n    },\n    _ => {\n        1\n    },\n}"
+        );
+    }
+
+    #[test]
+    fn t_match_int() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let source = |n| format!("match {n} {{ 0 => \"zero\", 1 => \"one\", _ => \"many\" }}");
+
+        for (n, expected) in [(0, "zero"), (1, "one"), (15, "many")] {
+            let res = ctx.eval(&source(n)).unwrap().unwrap();
+            assert_eq!(JkString::from_instance(&res).0, expected);
+        }
+    }
+
+    #[test]
+    fn t_match_in_func() {
+        let ctx = jinko! {
+            func sign(n: int) -> int {
+                match n > 0 {
+                    true => 1,
+                    _ => match n {
+                        0 => 0,
+                        _ => -1,
+                    },
+                }
+            }
+            res = sign(0 - 12);
+        };
+
+        assert_eq!(
+            JkInt::from_instance(&ctx.get_variable(&"res".into()).unwrap().instance()).rust_value(),
+            -1
+        );
+    }
+
+    #[test]
+    fn tc_match_bool() {
+        jinko! {
+            b = true;
+            res = match b {
+                true => 1,
+                _ => 0,
+            };
+        };
+    }
+
+    #[test]
+    fn tc_match_mismatched_arms() {
+        jinko_fail! {
+            match 4 {
+                0 => 15,
+                1 => 'c',
+                _ => 14,
+            }
+        };
+    }
+
+    #[test]
+    fn tc_match_mismatched_pattern() {
+        jinko_fail! {
+            match 4 {
+                true => 15,
+                _ => 14,
+            }
+        };
+    }
+
+    #[test]
+    fn tc_match_invalid_scrutinee() {
+        jinko_fail! {
+            match 4.5 {
+                _ => 14,
+            }
+        };
+    }
+}
@@ -10,7 +10,7 @@
 //! ```
 
 use crate::context::Context;
-use crate::error::Error;
+use crate::error::{ErrKind, Error};
 use crate::instance::ObjectInstance;
 use crate::instruction::{InstrKind, Instruction};
 use crate::location::SpanTuple;
@@ -41,11 +41,23 @@ impl Return {
 impl Instruction for Return {
     fn kind(&self) -> InstrKind {
         match &self.value {
-            Some(val) => val.kind(),
+            Some(val) => val.kind().without_value(),
             None => InstrKind::Statement,
         }
     }
 
+    fn children(&self) -> Vec<&dyn Instruction> {
+        self.value.iter().map(|value| value.as_ref()).collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        self.value.iter_mut().map(|value| value.as_mut()).collect()
+    }
+
+    fn diverges(&self) -> bool {
+        true
+    }
+
     fn print(&self) -> String {
         let base = "return".to_string();
 
@@ -56,10 +68,14 @@ impl Instruction for Return {
     }
 
     fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
-        match &self.value {
+        let value = match &self.value {
             Some(val) => val.execute(ctx),
-            None => None,
-        }
+            None => Some(ObjectInstance::void()),
+        };
+
+        ctx.signal_return();
+
+        value
     }
 
     fn location(&self) -> Option<&SpanTuple> {
@@ -69,9 +85,19 @@ impl Instruction for Return {
 
 impl TypeCheck for Return {
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
-        match &mut self.value {
-            None => Ok(CheckedType::Void),
-            Some(v) => v.type_of(ctx),
+        let value_ty = match &mut self.value {
+            None => CheckedType::Void,
+            Some(v) => v.type_of(ctx)?,
+        };
+
+        // Returning from the top level is allowed, and gives the program's exit code
+        match ctx.current_return_type() {
+            Some(return_ty) if *return_ty != value_ty => Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "invalid type returned: expected type {return_ty}, found type {value_ty}"
+                ))
+                .with_loc(self.location.clone())),
+            _ => Ok(value_ty),
         }
     }
 
@@ -87,7 +113,7 @@ impl TypeCheck for Return {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::jk_execute;
+    use crate::{jinko, jinko_fail, jk_execute};
 
     #[test]
     fn t_return_kind() {
@@ -101,7 +127,7 @@ mod tests {
         use crate::value::JkInt;
         let return_inst = Return::new(Some(Box::new(JkInt::from(42))));
 
-        assert_eq!(return_inst.kind(), JkInt::from(42).kind());
+        assert_eq!(return_inst.kind(), InstrKind::Expression(None));
     }
 
     #[test]
@@ -140,4 +166,110 @@ mod tests {
 
         assert_eq!(res, Some(JkInt::from(42).to_instance()));
     }
+
+    #[test]
+    fn t_early_return() {
+        use crate::instance::ToObjectInstance;
+        use crate::value::JkInt;
+
+        let ctx = jinko! {
+            func early(b: bool) -> int {
+                if b {
+                    return 5;
+                };
+
+                15
+            }
+
+            a = early(true);
+            b = early(false);
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkInt::from(5).to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"b".into()).unwrap().instance(),
+            JkInt::from(15).to_instance()
+        );
+    }
+
+    #[test]
+    fn t_return_unwinds_loop() {
+        use crate::instance::ToObjectInstance;
+        use crate::value::JkInt;
+
+        let ctx = jinko! {
+            func five() -> int {
+                loop {
+                    return 5
+                }
+            }
+
+            a = five();
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"a".into()).unwrap().instance(),
+            JkInt::from(5).to_instance()
+        );
+    }
+
+    #[test]
+    fn tc_return_invalid_type() {
+        jinko_fail! {
+            func f() -> int {
+                return "not an int"
+            }
+        };
+    }
+
+    #[test]
+    fn tc_return_value_in_void_function() {
+        jinko_fail! {
+            func f() {
+                return 5;
+            }
+        };
+    }
+
+    #[test]
+    fn tc_return_in_nested_function() {
+        jinko! {
+            func outer() -> int {
+                func inner() -> bool {
+                    return true;
+                };
+                if inner() {
+                    return 1;
+                };
+                0
+            }
+        };
+    }
+
+    #[test]
+    fn tc_return_outer_type_in_nested_function() {
+        jinko_fail! {
+            func outer() -> int {
+                func inner() -> bool {
+                    return 1;
+                };
+                0
+            }
+        };
+    }
+
+    #[test]
+    fn tc_return_after_nested_function() {
+        jinko_fail! {
+            func outer() -> int {
+                func inner() -> bool {
+                    true
+                };
+                return inner();
+            }
+        };
+    }
 }
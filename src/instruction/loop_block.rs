@@ -2,9 +2,9 @@
 //! different kinds, `for`, `while` or `loop`.
 
 use crate::context::Context;
-use crate::error::Error;
+use crate::error::{ErrKind, Error};
 use crate::instance::{FromObjectInstance, ObjectInstance};
-use crate::instruction::{Block, FunctionCall, InstrKind, Instruction, Var};
+use crate::instruction::{Block, FunctionCall, InstrKind, Instruction, Range, Var};
 use crate::location::SpanTuple;
 use crate::typechecker::{CheckedType, TypeCheck, TypeCtx};
 use crate::value::JkBool;
@@ -42,6 +42,32 @@ impl Loop {
     pub fn set_location(&mut self, location: SpanTuple) {
         self.location = Some(location)
     }
+
+    /// Get the part of the loop preceding its block, such as `while cond`
+    pub fn header(&self) -> String {
+        match &self.kind {
+            LoopKind::For(var, range) => format!("for {} in {}", var.name(), range.print()),
+            LoopKind::While(condition) => format!("while {}", condition.print()),
+            LoopKind::Loop => String::from("loop"),
+        }
+    }
+
+    /// Get a reference to the block repeated by the loop
+    pub fn block(&self) -> &Block {
+        &self.block
+    }
+
+    /// Count an iteration of the loop against the context's budget. Returns `None`
+    /// and records an error if the budget is exhausted
+    fn iterate(&self, ctx: &mut Context) -> Option<()> {
+        match ctx.loop_iteration() {
+            Ok(()) => Some(()),
+            Err(e) => {
+                ctx.error(e.with_loc(self.location.clone()));
+                None
+            }
+        }
+    }
 }
 
 impl Instruction for Loop {
@@ -49,31 +75,93 @@ impl Instruction for Loop {
         self.block.kind()
     }
 
-    fn print(&self) -> String {
+    fn children(&self) -> Vec<&dyn Instruction> {
         match &self.kind {
-            LoopKind::For(var, range) => format!(
-                "for {} in {} {}\n",
-                var.name(),
-                range.print(),
-                self.block.print()
-            ),
-            LoopKind::While(condition) => {
-                format!("while {} {}\n", condition.print(), self.block.print())
-            }
-            LoopKind::Loop => format!("loop {}\n", self.block.print()),
+            LoopKind::For(_, range) => vec![range.as_ref(), &self.block],
+            LoopKind::While(condition) => vec![condition.as_ref(), &self.block],
+            LoopKind::Loop => vec![&self.block],
         }
     }
 
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        match &mut self.kind {
+            LoopKind::For(_, range) => vec![range.as_mut(), &mut self.block],
+            LoopKind::While(condition) => vec![condition.as_mut(), &mut self.block],
+            LoopKind::Loop => vec![&mut self.block],
+        }
+    }
+
+    fn diverges(&self) -> bool {
+        // There is no way to break out of a `loop`. `for` and `while` loops might
+        // not execute their block at all
+        matches!(self.kind, LoopKind::Loop)
+    }
+
+    fn print(&self) -> String {
+        format!("{} {}\n", self.header(), self.block.print())
+    }
+
     fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
         match &self.kind {
+            // Blocks ending with a statement produce a void value, so only errors stop
+            // the loop
             LoopKind::Loop => loop {
-                self.block.execute(ctx)?;
+                self.iterate(ctx)?;
+
+                let res = self.block.execute(ctx);
+                if ctx.is_returning() {
+                    return res;
+                }
+                if ctx.has_errors() {
+                    return None;
+                }
+            },
+            LoopKind::While(cond) => loop {
+                let cond = cond.execute_expression(ctx)?;
+                if !JkBool::from_instance(&cond).rust_value() {
+                    break;
+                }
+
+                self.iterate(ctx)?;
+
+                let res = self.block.execute(ctx);
+                if ctx.is_returning() {
+                    return res;
+                }
+                if ctx.has_errors() {
+                    return None;
+                }
             },
-            LoopKind::While(cond) => {
-                let cond = cond.execute(ctx)?;
-                while JkBool::from_instance(&cond).rust_value() {
-                    self.block.execute(ctx)?;
+            LoopKind::For(var, range) if range.downcast_ref::<Range>().is_some() => {
+                let range = range.downcast_ref::<Range>().unwrap();
+                let values = range.values(ctx)?;
+
+                // The loop variable lives in its own scope, so that it is not visible
+                // once the loop is done
+                ctx.scope_enter();
+
+                for value in values {
+                    if self.iterate(ctx).is_none() {
+                        ctx.scope_exit();
+                        return None;
+                    }
+
+                    let mut iter_value = Var::new(var.name().to_owned());
+                    iter_value.set_instance(value);
+                    ctx.replace_variable(iter_value).unwrap();
+
+                    let res = self.block.execute(ctx);
+                    if ctx.is_returning() {
+                        ctx.scope_exit();
+                        return res;
+                    }
+                    if ctx.has_errors() {
+                        ctx.scope_exit();
+                        return None;
+                    }
                 }
+
+                ctx.scope_exit();
             }
             LoopKind::For(var, range_expression) => {
                 // Let's break down the implementation for the following loop
@@ -166,14 +254,27 @@ impl Instruction for Loop {
 
                 if maybe_is_nothing {
                     ctx.scope_exit();
-                    return None;
+                    return Some(ObjectInstance::void());
                 }
 
                 iter_value.set_instance(maybe_unpack.execute(ctx).unwrap());
                 ctx.replace_variable(iter_value.clone()).unwrap();
 
                 loop {
-                    self.block.execute(ctx);
+                    if self.iterate(ctx).is_none() {
+                        ctx.scope_exit();
+                        return None;
+                    }
+
+                    let res = self.block.execute(ctx);
+                    if ctx.is_returning() {
+                        ctx.scope_exit();
+                        return res;
+                    }
+                    if ctx.has_errors() {
+                        ctx.scope_exit();
+                        return None;
+                    }
 
                     iterator.set_instance(iterator_next.execute(ctx).unwrap());
                     ctx.replace_variable(iterator.clone()).unwrap();
@@ -197,8 +298,8 @@ impl Instruction for Loop {
             }
         }
 
-        // FIXME: Add logic. Right now they only return on error, not the actual value
-        None
+        // Loops are statements, and only return a value when a `return` is executed
+        Some(ObjectInstance::void())
     }
 
     fn location(&self) -> Option<&SpanTuple> {
@@ -208,6 +309,26 @@ impl Instruction for Loop {
 
 impl TypeCheck for Loop {
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        if let LoopKind::For(var, range) = &mut self.kind {
+            if let Some(range) = range.downcast_mut::<Range>() {
+                let value_ty = range.value_type(ctx)?;
+
+                ctx.scope_enter();
+                let declared = ctx.declare_var(var.name().to_owned(), value_ty);
+                let body_ty = declared.and_then(|_| self.block.type_of(ctx));
+                ctx.scope_exit();
+
+                return match body_ty? {
+                    CheckedType::Void => Ok(CheckedType::Void),
+                    body_ty => Err(Error::new(ErrKind::TypeChecker)
+                        .with_msg(format!(
+                            "the body of a `for` loop must be `void`, found `{body_ty}`"
+                        ))
+                        .with_loc(self.block.location().cloned())),
+                };
+            }
+        }
+
         // FIXME: This is invalid
         self.block.type_of(ctx)
     }
@@ -278,10 +399,39 @@ mod tests {
             }
         };
 
-        let counter = ctx.get_variable("counter").unwrap();
+        let counter = ctx.get_variable(&"counter".into()).unwrap();
         assert_eq!(counter.instance(), JkInt::from(15).to_instance());
     }
 
+    #[test]
+    fn loops_yield_void() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let while_false = Loop::new(LoopKind::While(Box::new(JkBool::from(false))), Block::new());
+        let empty_range = Loop::new(
+            LoopKind::For(
+                Box::new(Var::new("i".to_owned())),
+                Box::new(Range::new(
+                    Box::new(JkInt::from(0)),
+                    Box::new(JkInt::from(0)),
+                    false,
+                )),
+            ),
+            Block::new(),
+        );
+
+        assert!(while_false.execute(&mut ctx).unwrap().is_void());
+        assert!(empty_range.execute(&mut ctx).unwrap().is_void());
+    }
+
+    #[test]
+    fn failing_range_loop_stops_at_first_error() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert!(ctx.eval("for i in 0..1000000 { x = 1 / 0; }").is_err());
+        assert_eq!(ctx.error_handler.errors().len(), 1);
+        assert_eq!(ctx.error_handler.errors()[0].kind(), ErrKind::DivByZero);
+    }
+
     #[test]
     fn valid_for_block_without_execution() {
         jinko! {
@@ -289,4 +439,27 @@ mod tests {
             }
         };
     }
+
+    #[test]
+    fn infinite_loop_exceeds_iteration_limit() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_max_loop_iterations(100);
+
+        assert!(ctx.eval("while true {}").is_err());
+        assert_eq!(ctx.error_handler.errors()[0].kind(), ErrKind::Context);
+    }
+
+    #[test]
+    fn iteration_limit_is_per_execution() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_max_loop_iterations(10);
+
+        let first = "mut first = 0; while first < 10 { first = first + 1 }; first";
+        assert_eq!(ctx.eval(first), Ok(Some(JkInt::from(10).to_instance())));
+
+        let second = "mut second = 0; for i in 0..10 { second = second + 1 }; second";
+        assert_eq!(ctx.eval(second), Ok(Some(JkInt::from(10).to_instance())));
+
+        assert!(ctx.eval("for i in 0..11 {}").is_err());
+    }
 }
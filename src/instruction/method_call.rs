@@ -39,6 +39,14 @@ impl Instruction for MethodCall {
         InstrKind::Expression(None)
     }
 
+    fn children(&self) -> Vec<&dyn Instruction> {
+        vec![self.var.as_ref(), &self.method]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        vec![self.var.as_mut(), &mut self.method]
+    }
+
     fn print(&self) -> String {
         format!("{}.{}", self.var.print(), self.method.print())
     }
@@ -61,6 +69,10 @@ impl Instruction for MethodCall {
 
 impl TypeCheck for MethodCall {
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        // Typecheck the instance in place, as it is cloned into the call both here and
+        // when executing
+        self.var.type_of(ctx)?;
+
         let mut call = self.method.clone();
 
         call.add_arg_front(self.var.clone());
@@ -1,6 +1,10 @@
 //! Operators used by jinko's BinaryOp struct. This module is not public, and is only
 //! used by the BinaryOp structure.
 
+use std::str::FromStr;
+
+use crate::error::{ErrKind, Error};
+
 /// All the binary operators available
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,29 +19,55 @@ pub enum Operator {
     GtEq,
     Equals,
     NotEquals,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
     LeftParenthesis,
     RightParenthesis,
 }
 
-impl Operator {
-    /// Create a new operator from a given character
-    pub fn new(op_str: &str) -> Operator {
+impl FromStr for Operator {
+    type Err = Error;
+
+    /// Create a new operator from its representation, erroring out if the string
+    /// does not represent any operator
+    fn from_str(op_str: &str) -> Result<Operator, Error> {
         match op_str {
-            "+" => Operator::Add,
-            "-" => Operator::Sub,
-            "*" => Operator::Mul,
-            "/" => Operator::Div,
-            "<" => Operator::Lt,
-            ">" => Operator::Gt,
-            "<=" => Operator::LtEq,
-            ">=" => Operator::GtEq,
-            "==" => Operator::Equals,
-            "!=" => Operator::NotEquals,
-            "(" => Operator::LeftParenthesis,
-            ")" => Operator::RightParenthesis,
-            _ => unreachable!("Invalid operator: {}", op_str),
+            "+" => Ok(Operator::Add),
+            "-" => Ok(Operator::Sub),
+            "*" => Ok(Operator::Mul),
+            "/" => Ok(Operator::Div),
+            "<" => Ok(Operator::Lt),
+            ">" => Ok(Operator::Gt),
+            "<=" => Ok(Operator::LtEq),
+            ">=" => Ok(Operator::GtEq),
+            "==" => Ok(Operator::Equals),
+            "!=" => Ok(Operator::NotEquals),
+            "&" => Ok(Operator::BitAnd),
+            "|" => Ok(Operator::BitOr),
+            "^" => Ok(Operator::BitXor),
+            "<<" => Ok(Operator::Shl),
+            ">>" => Ok(Operator::Shr),
+            "(" => Ok(Operator::LeftParenthesis),
+            ")" => Ok(Operator::RightParenthesis),
+            _ => {
+                Err(Error::new(ErrKind::Parsing).with_msg(format!("invalid operator: `{op_str}`")))
+            }
         }
     }
+}
+
+impl Operator {
+    /// Create a new operator from a given character. This panics if the string does
+    /// not represent any operator, and should only be used with known-good strings.
+    /// Use [`Operator::from_str`] otherwise
+    pub fn new(op_str: &str) -> Operator {
+        op_str
+            .parse()
+            .unwrap_or_else(|_| unreachable!("Invalid operator: {}", op_str))
+    }
 
     /// Return the operator's representation
     pub fn as_str(&self) -> &str {
@@ -52,30 +82,84 @@ impl Operator {
             Operator::GtEq => ">=",
             Operator::Equals => "==",
             Operator::NotEquals => "!=",
+            Operator::BitAnd => "&",
+            Operator::BitOr => "|",
+            Operator::BitXor => "^",
+            Operator::Shl => "<<",
+            Operator::Shr => ">>",
             Operator::LeftParenthesis => "(",
             Operator::RightParenthesis => ")",
         }
     }
 
-    /// Return the operator's precedence according to the Shunting Yard algorithm
-    pub fn precedence(&self) -> u8 {
+    /// Name of the method implementing the operator for custom types. For example,
+    /// `a + b` calls `add(a, b)` when `a` is not of a primitive type
+    pub fn method_name(&self) -> Option<&'static str> {
+        match self {
+            Operator::Add => Some("add"),
+            Operator::Sub => Some("sub"),
+            Operator::Mul => Some("mul"),
+            Operator::Div => Some("div"),
+            Operator::Lt => Some("lt"),
+            Operator::Gt => Some("gt"),
+            Operator::LtEq => Some("le"),
+            Operator::GtEq => Some("ge"),
+            Operator::Equals => Some("eq"),
+            Operator::NotEquals => Some("ne"),
+            // Bitwise operators are only available on integers
+            Operator::BitAnd
+            | Operator::BitOr
+            | Operator::BitXor
+            | Operator::Shl
+            | Operator::Shr => None,
+            Operator::LeftParenthesis | Operator::RightParenthesis => None,
+        }
+    }
+
+    /// Return the operator's precedence according to the Shunting Yard algorithm.
+    /// Parentheses are structural tokens rather than real operators: they have no
+    /// precedence, so they can never be compared to an operator numerically
+    pub fn precedence(&self) -> Option<u8> {
         match self {
             // Classic SY operator precedence
-            Operator::Mul | Operator::Div => 3,
-            Operator::Add | Operator::Sub => 2,
+            Operator::Mul | Operator::Div => Some(7),
+            Operator::Add | Operator::Sub => Some(6),
+            Operator::Shl | Operator::Shr => Some(5),
+            // Like in C, `&` binds tighter than `^`, which binds tighter than `|`
+            Operator::BitAnd => Some(4),
+            Operator::BitXor => Some(3),
+            Operator::BitOr => Some(2),
             Operator::Lt
             | Operator::Gt
             | Operator::LtEq
             | Operator::GtEq
             | Operator::Equals
-            | Operator::NotEquals => 1,
+            | Operator::NotEquals => Some(1),
 
-            // Special operators. They don't really have a precedence value, and it's
-            // never used
-            Operator::LeftParenthesis | Operator::RightParenthesis => 0,
+            Operator::LeftParenthesis | Operator::RightParenthesis => None,
         }
     }
 
+    /// Should the operator, sitting on top of the Shunting Yard operator stack, be
+    /// applied before pushing the `incoming` one. This is never the case when either
+    /// of them is a parenthesis
+    pub fn has_precedence_over(&self, incoming: &Operator) -> bool {
+        match (self.precedence(), incoming.precedence()) {
+            (Some(top), Some(incoming_prec)) => {
+                top > incoming_prec || (top == incoming_prec && incoming.is_left_associative())
+            }
+            _ => false,
+        }
+    }
+
+    /// Is the operator a bitwise one, including shifts, which only applies to integers
+    pub fn is_bitwise(&self) -> bool {
+        matches!(
+            self,
+            Operator::BitAnd | Operator::BitOr | Operator::BitXor | Operator::Shl | Operator::Shr
+        )
+    }
+
     /// Is the operator a left associative one
     pub fn is_left_associative(&self) -> bool {
         // FIXME: Not entirely true
@@ -87,3 +171,65 @@ impl Operator {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_valid() {
+        let ops = [
+            "+", "-", "*", "/", "<", ">", "<=", ">=", "==", "!=", "&", "|", "^", "<<", ">>", "(",
+            ")",
+        ];
+
+        ops.iter().for_each(|op| {
+            let parsed = Operator::from_str(op);
+
+            assert!(parsed.is_ok());
+            assert_eq!(parsed.unwrap().as_str(), *op);
+        });
+    }
+
+    #[test]
+    fn from_str_invalid() {
+        let err = Operator::from_str("%%").unwrap_err();
+
+        assert_eq!(err.kind(), ErrKind::Parsing);
+    }
+
+    #[test]
+    fn parentheses_have_no_precedence() {
+        assert_eq!(Operator::LeftParenthesis.precedence(), None);
+        assert_eq!(Operator::RightParenthesis.precedence(), None);
+        assert_eq!(Operator::Equals.precedence(), Some(1));
+    }
+
+    #[test]
+    fn parentheses_never_take_precedence() {
+        let paren = Operator::LeftParenthesis;
+        let equals = Operator::Equals;
+
+        assert!(!paren.has_precedence_over(&equals));
+        assert!(!equals.has_precedence_over(&paren));
+        assert!(equals.has_precedence_over(&Operator::NotEquals));
+        assert!(Operator::Mul.has_precedence_over(&Operator::Add));
+        assert!(!Operator::Add.has_precedence_over(&Operator::Mul));
+    }
+
+    #[test]
+    fn bitwise_precedence() {
+        assert!(Operator::Add.has_precedence_over(&Operator::BitAnd));
+        assert!(Operator::BitOr.has_precedence_over(&Operator::Lt));
+        assert!(!Operator::BitXor.has_precedence_over(&Operator::Mul));
+        assert!(Operator::BitAnd.has_precedence_over(&Operator::BitXor));
+        assert!(Operator::BitXor.has_precedence_over(&Operator::BitOr));
+        assert!(!Operator::BitOr.has_precedence_over(&Operator::BitAnd));
+    }
+
+    #[test]
+    fn shift_precedence() {
+        assert!(Operator::Add.has_precedence_over(&Operator::Shl));
+        assert!(Operator::Shr.has_precedence_over(&Operator::BitAnd));
+    }
+}
@@ -0,0 +1,252 @@
+//! Ranges produce successive values between two `int` or `char` endpoints. They are
+//! iterated over by `for` loops.
+//! ```ignore
+//! for i in 0..3 { /* 0, 1, 2 */ }
+//! for c in 'a'..='c' { /* 'a', 'b', 'c' */ }
+//! ```
+//! The end of the range is excluded, unless the range is written with `..=`.
+
+use crate::context::Context;
+use crate::error::{ErrKind, Error};
+use crate::instance::{ObjectInstance, ToObjectInstance};
+use crate::instruction::{InstrKind, Instruction};
+use crate::location::SpanTuple;
+use crate::typechecker::{CheckedType, TypeCheck, TypeCtx};
+use crate::value::{JkChar, JkInt};
+
+/// Types that can be used as the endpoints of a range
+const RANGE_TYPES: [&str; 2] = ["int", "char"];
+
+#[derive(Clone)]
+pub struct Range {
+    start: Box<dyn Instruction>,
+    end: Box<dyn Instruction>,
+    inclusive: bool,
+    cached_type: Option<CheckedType>,
+    location: Option<SpanTuple>,
+}
+
+impl Range {
+    /// Create a new range going from `start` to `end`. `end` is only part of the
+    /// range if it is inclusive
+    pub fn new(start: Box<dyn Instruction>, end: Box<dyn Instruction>, inclusive: bool) -> Range {
+        Range {
+            start,
+            end,
+            inclusive,
+            cached_type: None,
+            location: None,
+        }
+    }
+
+    pub fn set_location(&mut self, location: SpanTuple) {
+        self.location = Some(location)
+    }
+
+    /// Typecheck the endpoints of the range and return the type of the values it
+    /// produces
+    pub fn value_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        let start_ty = self.start.type_of(ctx)?;
+        let end_ty = self.end.type_of(ctx)?;
+
+        if start_ty != end_ty {
+            return Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "range endpoints must have the same type: found `{start_ty}` and `{end_ty}`"
+                ))
+                .with_loc(self.location.clone()));
+        }
+
+        match &start_ty {
+            CheckedType::Resolved(ty) if RANGE_TYPES.contains(&ty.id()) => Ok(start_ty),
+            _ => Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "cannot create a range of `{start_ty}`: expected `int` or `char` endpoints"
+                ))
+                .with_loc(self.location.clone())),
+        }
+    }
+
+    /// Execute the endpoints of the range and return an iterator over the values it
+    /// produces
+    pub fn values(&self, ctx: &mut Context) -> Option<Box<dyn Iterator<Item = ObjectInstance>>> {
+        let start = self.start.execute_expression(ctx)?;
+        let end = self.end.execute_expression(ctx)?;
+
+        if let (Some(start), Some(end)) = (start.downcast::<JkInt>(), end.downcast::<JkInt>()) {
+            let (start, end) = (start.rust_value(), end.rust_value());
+            let to_instance = |i| JkInt::from(i).to_instance();
+
+            return Some(match self.inclusive {
+                true => Box::new((start..=end).map(to_instance)),
+                false => Box::new((start..end).map(to_instance)),
+            });
+        }
+
+        if let (Some(start), Some(end)) = (start.downcast::<JkChar>(), end.downcast::<JkChar>()) {
+            let (start, end) = (start.rust_value(), end.rust_value());
+            let to_instance = |c| JkChar::from(c).to_instance();
+
+            return Some(match self.inclusive {
+                true => Box::new((start..=end).map(to_instance)),
+                false => Box::new((start..end).map(to_instance)),
+            });
+        }
+
+        ctx.error(
+            Error::new(ErrKind::Context)
+                .with_msg(format!("invalid range endpoints: `{}`", self.print()))
+                .with_loc(self.location.clone()),
+        );
+
+        None
+    }
+}
+
+impl Instruction for Range {
+    fn kind(&self) -> InstrKind {
+        InstrKind::Expression(None)
+    }
+
+    fn print(&self) -> String {
+        let dots = match self.inclusive {
+            true => "..=",
+            false => "..",
+        };
+
+        format!("{}{}{}", self.start.print(), dots, self.end.print())
+    }
+
+    fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
+        ctx.error(
+            Error::new(ErrKind::Context)
+                .with_msg(String::from(
+                    "ranges can only be iterated over by `for` loops",
+                ))
+                .with_loc(self.location.clone()),
+        );
+
+        None
+    }
+
+    fn location(&self) -> Option<&SpanTuple> {
+        self.location.as_ref()
+    }
+}
+
+impl TypeCheck for Range {
+    fn resolve_type(&mut self, _ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        // Ranges are not values yet: They are handled by `for` loops directly, using
+        // `Range::value_type`
+        Err(Error::new(ErrKind::TypeChecker)
+            .with_msg(String::from(
+                "ranges can only be iterated over by `for` loops",
+            ))
+            .with_loc(self.location.clone()))
+    }
+
+    fn set_cached_type(&mut self, ty: CheckedType) {
+        self.cached_type = Some(ty)
+    }
+
+    fn cached_type(&self) -> Option<&CheckedType> {
+        self.cached_type.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::JkString;
+    use crate::{jinko, jinko_fail};
+
+    #[test]
+    fn pretty_print() {
+        let exclusive = Range::new(Box::new(JkInt::from(0)), Box::new(JkInt::from(3)), false);
+        let inclusive = Range::new(
+            Box::new(JkChar::from('a')),
+            Box::new(JkChar::from('d')),
+            true,
+        );
+
+        assert_eq!(exclusive.print(), "0..3");
+        assert_eq!(inclusive.print(), "a..=d");
+    }
+
+    #[test]
+    fn char_range() {
+        let ctx = jinko! {
+            mut visited = "";
+            for c in 'a'..'d' {
+                visited = visited.concat(fmt_char(c))
+            }
+        };
+
+        let visited = ctx.get_variable(&"visited".into()).unwrap();
+        assert_eq!(visited.instance(), JkString::from("abc").to_instance());
+    }
+
+    #[test]
+    fn inclusive_char_range() {
+        let ctx = jinko! {
+            mut visited = "";
+            for c in 'a'..='d' {
+                visited = visited.concat(fmt_char(c))
+            }
+        };
+
+        let visited = ctx.get_variable(&"visited".into()).unwrap();
+        assert_eq!(visited.instance(), JkString::from("abcd").to_instance());
+    }
+
+    #[test]
+    fn int_range() {
+        let ctx = jinko! {
+            mut count = 0;
+            mut sum = 0;
+            for i in 0..3 {
+                count = count + 1;
+                sum = sum + i;
+            }
+        };
+
+        let count = ctx.get_variable(&"count".into()).unwrap();
+        let sum = ctx.get_variable(&"sum".into()).unwrap();
+        assert_eq!(count.instance(), JkInt::from(3).to_instance());
+        assert_eq!(sum.instance(), JkInt::from(3).to_instance());
+    }
+
+    #[test]
+    fn empty_range() {
+        let ctx = jinko! {
+            mut count = 0;
+            for i in 3..3 {
+                count = count + 1;
+            }
+        };
+
+        let count = ctx.get_variable(&"count".into()).unwrap();
+        assert_eq!(count.instance(), JkInt::from(0).to_instance());
+    }
+
+    #[test]
+    fn tc_mismatched_endpoints() {
+        jinko_fail! {
+            for i in 0..'c' {}
+        };
+    }
+
+    #[test]
+    fn tc_invalid_endpoints() {
+        jinko_fail! {
+            for i in 0.5..1.5 {}
+        };
+    }
+
+    #[test]
+    fn tc_body_must_be_void() {
+        jinko_fail! {
+            for i in 0..3 { i }
+        };
+    }
+}
@@ -0,0 +1,189 @@
+//! Type aliases give an alternative name to an existing type, so that domain types
+//! read better. An alias and the type it refers to can be used interchangeably.
+//! ```ignore
+//! type Meters = int;
+//!
+//! func double(distance: Meters) -> Meters { distance + distance }
+//! double(15)
+//! ```
+//! Aliases are resolved by the typechecker and do not exist at runtime.
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::instance::ObjectInstance;
+use crate::instruction::{InstrKind, Instruction};
+use crate::location::SpanTuple;
+use crate::typechecker::{CheckedType, TypeCheck, TypeCtx, TypeId};
+
+#[derive(Clone)]
+pub struct TypeAlias {
+    name: String,
+    target: TypeId,
+    typechecked: bool,
+    location: Option<SpanTuple>,
+}
+
+impl TypeAlias {
+    /// Create a new alias named `name`, referring to the `target` type
+    pub fn new(name: String, target: TypeId) -> TypeAlias {
+        TypeAlias {
+            name,
+            target,
+            typechecked: false,
+            location: None,
+        }
+    }
+
+    /// Get a reference to the name of the alias
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get a reference to the type the alias refers to
+    pub fn target(&self) -> &TypeId {
+        &self.target
+    }
+
+    pub fn set_location(&mut self, location: SpanTuple) {
+        self.location = Some(location)
+    }
+}
+
+impl Instruction for TypeAlias {
+    fn kind(&self) -> InstrKind {
+        InstrKind::Statement
+    }
+
+    fn execute(&self, _ctx: &mut Context) -> Option<ObjectInstance> {
+        // All usages of the alias have been replaced by its target when typechecking
+        Some(ObjectInstance::void())
+    }
+
+    fn print(&self) -> String {
+        format!("type {} = {}", self.name, self.target.id())
+    }
+
+    fn location(&self) -> Option<&SpanTuple> {
+        self.location.as_ref()
+    }
+}
+
+impl TypeCheck for TypeAlias {
+    fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        ctx.declare_type_alias(
+            self.name.clone(),
+            CheckedType::Resolved(self.target.clone()),
+        )
+        .map_err(|e| e.with_loc(self.location.clone()))?;
+
+        Ok(CheckedType::Void)
+    }
+
+    fn set_cached_type(&mut self, _ty: CheckedType) {
+        self.typechecked = true
+    }
+
+    fn cached_type(&self) -> Option<&CheckedType> {
+        match self.typechecked {
+            true => Some(&CheckedType::Void),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::ToObjectInstance;
+    use crate::value::JkInt;
+    use crate::{jinko, jinko_fail};
+
+    #[test]
+    fn pretty_print() {
+        let alias = TypeAlias::new(String::from("Meters"), TypeId::from("int"));
+
+        assert_eq!(alias.print(), "type Meters = int");
+    }
+
+    #[test]
+    fn t_alias_accepts_target_values() {
+        let ctx = jinko! {
+            type Meters = int;
+
+            func to_meters(value: int) -> Meters { value }
+            func from_meters(distance: Meters) -> int { distance }
+
+            distance = to_meters(15);
+            value = from_meters(distance);
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"value".into()).unwrap().instance(),
+            JkInt::from(15).to_instance()
+        );
+    }
+
+    #[test]
+    fn t_alias_binary_op() {
+        let ctx = jinko! {
+            type Meters = int;
+
+            func double(distance: Meters) -> Meters { distance + distance }
+            doubled = double(15);
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"doubled".into()).unwrap().instance(),
+            JkInt::from(30).to_instance()
+        );
+    }
+
+    #[test]
+    fn t_alias_to_custom_type() {
+        let ctx = jinko! {
+            type Point(x: int, y: int);
+            type Position = Point;
+            type Coordinate = int;
+
+            func make_position(x: Coordinate) -> Position { Position(x: x, y: 2) }
+            x = make_position(1).x;
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"x".into()).unwrap().instance(),
+            JkInt::from(1).to_instance()
+        );
+    }
+
+    #[test]
+    fn tc_alias_type_mismatch() {
+        jinko_fail! {
+            type Meters = int;
+
+            func double(distance: Meters) -> Meters { distance + distance }
+            double(15.0);
+        };
+    }
+
+    #[test]
+    fn tc_alias_shadowing_primitive() {
+        jinko_fail! {
+            type int = float;
+        };
+    }
+
+    #[test]
+    fn tc_alias_to_undeclared_type() {
+        jinko_fail! {
+            type Meters = Distance;
+        };
+    }
+
+    #[test]
+    fn tc_alias_redeclared() {
+        jinko_fail! {
+            type Meters = int;
+            type Meters = float;
+        };
+    }
+}
@@ -61,7 +61,7 @@ impl Instruction for TypeDec {
         }
 
         // Declaring a type is always a statement (for now)
-        None
+        Some(ObjectInstance::void())
     }
 
     // FIXME: Really unefficient
@@ -104,6 +104,10 @@ impl Instruction for TypeDec {
 
 impl TypeCheck for TypeDec {
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        for field in self.fields.iter_mut() {
+            field.set_type(ctx.resolve_alias(field.get_type()));
+        }
+
         ctx.declare_custom_type(self.name.clone(), self.clone())?;
 
         Ok(CheckedType::Void)
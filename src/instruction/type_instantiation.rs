@@ -3,6 +3,7 @@
 
 use crate::context::Context;
 use crate::error::{ErrKind, Error};
+use crate::generics::GenericMap;
 use crate::instance::{Name, ObjectInstance};
 use crate::instruction::{InstrKind, Instruction, TypeDec, VarAssign};
 use crate::location::SpanTuple;
@@ -92,6 +93,28 @@ impl Instruction for TypeInstantiation {
         InstrKind::Expression(None)
     }
 
+    fn children(&self) -> Vec<&dyn Instruction> {
+        self.fields
+            .iter()
+            .map(|field| field as &dyn Instruction)
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        self.fields
+            .iter_mut()
+            .map(|field| field as &mut dyn Instruction)
+            .collect()
+    }
+
+    fn resolve_self(&mut self, type_map: &GenericMap, _ctx: &mut TypeCtx) {
+        self.generics = self
+            .generics
+            .iter()
+            .map(|generic| type_map.specialize(generic))
+            .collect();
+    }
+
     fn print(&self) -> String {
         let mut base = format!("{}(", self.type_name.id());
         let mut first_arg = true;
@@ -116,20 +139,40 @@ impl Instruction for TypeInstantiation {
             return None;
         }
 
+        // Fields are evaluated in the order they were given, but laid out in the
+        // order of the type's declaration
+        let mut values: Vec<(&str, ObjectInstance)> = Vec::new();
+        for named_arg in self.fields.iter() {
+            let instance = named_arg.value().execute_expression(ctx)?;
+            values.push((named_arg.symbol(), instance));
+        }
+
         let mut size: usize = 0;
         let mut data: Vec<u8> = Vec::new();
         let mut fields: Vec<(Name, ObjectInstance)> = Vec::new();
-        for named_arg in self.fields.iter() {
-            // FIXME: Need to assign the correct field to the field that corresponds
-            // in the typedec
-            let field_instr = named_arg.value();
-            let field_name = named_arg.symbol();
+        for field_dec in type_dec.fields() {
+            let instance = match values
+                .iter()
+                .position(|(name, _)| *name == field_dec.name())
+            {
+                Some(idx) => values.swap_remove(idx).1,
+                None => {
+                    ctx.error(
+                        Error::new(ErrKind::Context)
+                            .with_msg(format!(
+                                "missing field `{}` in instantiation of type `{}`",
+                                field_dec.name(),
+                                self.name().id()
+                            ))
+                            .with_loc(self.location.clone()),
+                    );
+                    return None;
+                }
+            };
 
-            let instance = field_instr.execute_expression(ctx)?;
             size += instance.size();
-
             data.append(&mut instance.data().to_vec());
-            fields.push((field_name.to_string(), instance));
+            fields.push((field_dec.name().to_string(), instance));
         }
 
         Some(ObjectInstance::new(
@@ -144,6 +187,8 @@ impl Instruction for TypeInstantiation {
 
 impl TypeCheck for TypeInstantiation {
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        self.type_name = ctx.resolve_alias(&self.type_name);
+
         let dec = match ctx.get_custom_type(self.type_name.id()) {
             Some(ty) => ty.clone(),
             None => {
@@ -162,10 +207,51 @@ impl TypeCheck for TypeInstantiation {
         // }
 
         let mut errors = vec![];
-        for (field_dec, var_assign) in dec.fields().iter().zip(self.fields.iter_mut()) {
+
+        // Fields are named, so they may be given in any order. Look each of them up
+        // in the declaration rather than relying on their position
+        let mut seen: Vec<&str> = vec![];
+        for var_assign in self.fields.iter_mut() {
+            let field_name = var_assign.symbol().to_string();
+
+            if seen.contains(&field_name.as_str()) {
+                errors.push(
+                    Error::new(ErrKind::TypeChecker)
+                        .with_msg(format!(
+                            "field `{}` specified more than once in instantiation of type `{}`",
+                            field_name,
+                            self.type_name.id()
+                        ))
+                        .with_loc(var_assign.location().cloned()),
+                );
+                continue;
+            }
+
+            let field_dec = match dec.fields().iter().find(|f| f.name() == field_name) {
+                Some(field_dec) => field_dec,
+                None => {
+                    errors.push(
+                        Error::new(ErrKind::TypeChecker)
+                            .with_msg(format!(
+                                "type `{}` has no field named `{}`",
+                                self.type_name.id(),
+                                field_name
+                            ))
+                            .with_loc(var_assign.location().cloned())
+                            .with_hint(
+                                Error::hint()
+                                    .with_msg(format!("type `{}` declared here", dec.name()))
+                                    .with_loc(dec.location().cloned()),
+                            ),
+                    );
+                    continue;
+                }
+            };
+            seen.push(field_dec.name());
+
             let expected_ty = CheckedType::Resolved(field_dec.get_type().clone());
             let value_ty = var_assign.value_mut().type_of(ctx)?;
-            if expected_ty != value_ty {
+            if value_ty != CheckedType::Later && !value_ty.is_assignable_to(&expected_ty) {
                 errors.push(
                     Error::new(ErrKind::TypeChecker)
                         .with_msg(format!(
@@ -180,10 +266,29 @@ impl TypeCheck for TypeInstantiation {
                                 .with_loc(field_dec.location().cloned()),
                         ),
                 );
-                // FIXME: Add hint for typedec argument later
             }
         }
 
+        dec.fields()
+            .iter()
+            .filter(|field_dec| !seen.contains(&field_dec.name()))
+            .for_each(|field_dec| {
+                errors.push(
+                    Error::new(ErrKind::TypeChecker)
+                        .with_msg(format!(
+                            "missing field `{}` in instantiation of type `{}`",
+                            field_dec.name(),
+                            self.type_name.id()
+                        ))
+                        .with_loc(self.location.clone())
+                        .with_hint(
+                            Error::hint()
+                                .with_msg(format!("field `{}` declared here", field_dec.name()))
+                                .with_loc(field_dec.location().cloned()),
+                        ),
+                )
+            });
+
         // Propagate all errors at once in the context
         errors.into_iter().for_each(|err| ctx.error(err));
 
@@ -202,7 +307,7 @@ impl TypeCheck for TypeInstantiation {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{jinko_fail, symbol::Symbol};
+    use crate::{jinko, jinko_fail, symbol::Symbol};
 
     #[test]
     fn t_fields_number() {
@@ -332,4 +437,80 @@ mod test {
             i = int(no_fields: 15);
         };
     }
+
+    #[test]
+    fn tc_valid_instantiation() {
+        jinko! {
+            type Point(x: int, y: float);
+            p = Point(x: 1, y: 2.0);
+        };
+    }
+
+    #[test]
+    fn t_fields_in_any_order() {
+        let ctx = jinko! {
+            type Point(x: int, y: float);
+            in_order = Point(x: 1, y: 2.0);
+            out_of_order = Point(y: 2.0, x: 1);
+            x = out_of_order.x;
+        };
+
+        let in_order = ctx.get_variable(&"in_order".into()).unwrap();
+        let out_of_order = ctx.get_variable(&"out_of_order".into()).unwrap();
+        assert_eq!(in_order.instance(), out_of_order.instance());
+
+        use crate::instance::ToObjectInstance;
+        use crate::value::JkInt;
+
+        let x = ctx.get_variable(&"x".into()).unwrap();
+        assert_eq!(x.instance(), JkInt::from(1).to_instance());
+    }
+
+    #[test]
+    fn tc_missing_field() {
+        jinko_fail! {
+            type Point(x: int, y: int);
+            p = Point(x: 1);
+        };
+    }
+
+    #[test]
+    fn tc_extra_field() {
+        jinko_fail! {
+            type Point(x: int, y: int);
+            p = Point(x: 1, y: 2, z: 3);
+        };
+    }
+
+    #[test]
+    fn tc_unknown_field_instead_of_declared_one() {
+        jinko_fail! {
+            type Point(x: int, y: int);
+            p = Point(x: 1, z: 2);
+        };
+    }
+
+    #[test]
+    fn tc_duplicate_field() {
+        jinko_fail! {
+            type Point(x: int, y: int);
+            p = Point(x: 1, x: 2);
+        };
+    }
+
+    #[test]
+    fn tc_wrong_field_type() {
+        jinko_fail! {
+            type Point(x: int, y: int);
+            p = Point(x: 1, y: "two");
+        };
+    }
+
+    #[test]
+    fn tc_wrong_field_type_out_of_order() {
+        jinko_fail! {
+            type Point(x: int, y: string);
+            p = Point(y: 1, x: "two");
+        };
+    }
 }
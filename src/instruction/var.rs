@@ -3,6 +3,8 @@
 //! need to keep an option of an instance. A variable is either there, fully initialized,
 //! or it's not.
 
+use std::rc::Rc;
+
 use crate::context::Context;
 use crate::error::{ErrKind, Error};
 use crate::instance::ObjectInstance;
@@ -11,13 +13,17 @@ use crate::instruction::{InstrKind, Instruction};
 use crate::location::SpanTuple;
 use crate::typechecker::{CheckedType, TypeCheck, TypeCtx};
 
+use symbol::Symbol;
+
 #[derive(Clone)]
 pub struct Var {
-    name: String,
+    name: Symbol,
     mutable: bool,
     // FIXME: Do not keep the instance here directly, keep a reference to it
     // in the garbage collector
-    instance: ObjectInstance,
+    instance: Rc<ObjectInstance>,
+    /// Is the variable a constant, whose value is known before execution
+    constant: bool,
     cached_type: Option<CheckedType>,
     location: Option<SpanTuple>,
 }
@@ -25,10 +31,16 @@ pub struct Var {
 impl Var {
     /// Create a new variable usage with the given name
     pub fn new(name: String) -> Var {
+        Var::from_symbol(Symbol::from(name))
+    }
+
+    /// Create a new variable usage from an already interned name
+    pub fn from_symbol(name: Symbol) -> Var {
         Var {
             name,
             mutable: false,
-            instance: ObjectInstance::empty(),
+            instance: Rc::new(ObjectInstance::empty()),
+            constant: false,
             cached_type: None,
             location: None,
         }
@@ -36,11 +48,22 @@ impl Var {
 
     /// Return the name of the variable
     pub fn name(&self) -> &str {
+        self.name.access()
+    }
+
+    /// Return the interned name of the variable
+    pub fn symbol(&self) -> &Symbol {
         &self.name
     }
 
-    /// Return a copy of the variable's instance
+    /// Return a copy of the variable's instance. The copy shares the instance's data
+    /// instead of duplicating it
     pub fn instance(&self) -> ObjectInstance {
+        self.instance.as_ref().clone()
+    }
+
+    /// Return a shared reference to the variable's instance
+    pub fn shared_instance(&self) -> Rc<ObjectInstance> {
         self.instance.clone()
     }
 
@@ -51,6 +74,11 @@ impl Var {
 
     /// Set the instance contained in a variable
     pub fn set_instance(&mut self, instance: ObjectInstance) {
+        self.instance = Rc::new(instance);
+    }
+
+    /// Make the variable share an instance with another variable, without copying it
+    pub fn set_shared_instance(&mut self, instance: Rc<ObjectInstance>) {
         self.instance = instance;
     }
 
@@ -60,7 +88,8 @@ impl Var {
     }
 
     pub fn set_type(&mut self, ty: TypeDec) {
-        self.instance.set_ty(CheckedType::Resolved(ty.into()))
+        // Copies of the variable keep the instance they were created with
+        Rc::make_mut(&mut self.instance).set_ty(CheckedType::Resolved(ty.into()))
     }
 
     pub fn set_location(&mut self, location: SpanTuple) {
@@ -70,25 +99,29 @@ impl Var {
 
 impl Instruction for Var {
     fn kind(&self) -> InstrKind {
-        InstrKind::Expression(None)
+        match self.constant {
+            true => InstrKind::Expression(Some(self.instance())),
+            false => InstrKind::Expression(None),
+        }
     }
 
     fn print(&self) -> String {
-        let mut base = self.name.clone();
-        if let CheckedType::Resolved(ty) = self.instance.ty() {
-            base = format!("{} /* : {} */", base, ty.id());
+        let mut base = self.name.to_string();
+        if let Some(ty) = self.instance.ty().resolved_id() {
+            base = format!("{} /* : {} */", base, ty);
         }
 
         format!("{} = {}", base, self.instance.as_string())
     }
 
     fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
-        let var = match ctx.get_variable(self.name()) {
+        let var = match ctx.get_variable(self.symbol()) {
             Some(v) => v,
             None => {
                 ctx.error(
-                    Error::new(ErrKind::Context)
-                        .with_msg(format!("variable has not been declared: {}", self.name)),
+                    Error::new(ErrKind::UndeclaredVariable)
+                        .with_msg(format!("variable has not been declared: {}", self.name))
+                        .with_loc(self.location.clone()),
                 );
 
                 return None;
@@ -105,10 +138,20 @@ impl Instruction for Var {
 
 impl TypeCheck for Var {
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
+        ctx.record_read(self.name());
+
+        // The value of constants is known, which allows folding the expressions
+        // using them
+        if let Some(value) = ctx.constant_value(self.name()) {
+            self.set_instance(value.clone());
+            self.constant = true;
+        }
+
         match ctx.get_var(self.name()) {
             Some(var_ty) => Ok(var_ty.clone()),
             None => Err(Error::new(ErrKind::TypeChecker)
-                .with_msg(format!("use of undeclared variable: `{}`", self.name()))),
+                .with_msg(format!("use of undeclared variable: `{}`", self.name()))
+                .with_loc(self.location.clone())),
         }
     }
 
@@ -131,7 +174,7 @@ impl Default for Var {
 mod tests {
     use super::*;
     use crate::instance::ToObjectInstance;
-    use crate::value::JkInt;
+    use crate::value::{JkInt, JkString};
     use crate::{jinko, jinko_fail};
 
     #[test]
@@ -162,4 +205,83 @@ mod tests {
             a
         };
     }
+
+    #[test]
+    fn tc_undeclared_variable_location() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let input = "a = 15;\nb = a + c;";
+
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+        assert!(ctx.execute().is_err());
+
+        let loc = ctx.error_handler.errors()[0].loc().unwrap();
+        assert_eq!(loc.start().line(), 2);
+        assert_eq!(loc.start().column(), 9);
+    }
+
+    #[test]
+    fn undeclared_variable_runtime_error() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert!(Var::new("a".to_string()).execute(&mut ctx).is_none());
+
+        let err = &ctx.error_handler.errors()[0];
+        assert_eq!(err.kind(), ErrKind::UndeclaredVariable);
+        assert_eq!(err.msg(), Some("variable has not been declared: a"));
+    }
+
+    #[test]
+    fn shared_instance() {
+        let mut v = Var::new("a".to_string());
+        v.set_instance(JkString::from("jinko").to_instance());
+
+        let copy = v.clone();
+
+        assert!(Rc::ptr_eq(&v.shared_instance(), &copy.shared_instance()));
+        assert_eq!(
+            v.instance().data().as_ptr(),
+            copy.instance().data().as_ptr()
+        );
+    }
+
+    #[test]
+    fn set_instance_keeps_copies() {
+        let mut v = Var::new("a".to_string());
+        v.set_instance(JkInt::from(1).to_instance());
+
+        let copy = v.clone();
+        v.set_instance(JkInt::from(2).to_instance());
+
+        assert_eq!(copy.instance(), JkInt::from(1).to_instance());
+        assert_eq!(v.instance(), JkInt::from(2).to_instance());
+    }
+
+    #[test]
+    fn t_assignment_copies_value() {
+        let ctx = jinko! {
+            type Point(x: int, y: int);
+
+            s = "jinko";
+            mut s_copy = s;
+            s_copy = s_copy.concat(" is fun");
+
+            p = Point(x: 1, y: 2);
+            mut p_copy = p;
+            p_copy = Point(x: 3, y: 4);
+            x = p.x;
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"s".into()).unwrap().instance(),
+            JkString::from("jinko").to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"s_copy".into()).unwrap().instance(),
+            JkString::from("jinko is fun").to_instance()
+        );
+        assert_eq!(
+            ctx.get_variable(&"x".into()).unwrap().instance(),
+            JkInt::from(1).to_instance()
+        );
+    }
 }
@@ -1,4 +1,5 @@
-//! The VarAssign struct is used when assigning values to variables.
+//! The VarAssign struct is used when assigning values to variables. Assigning to
+//! `_` discards the value instead of declaring a variable.
 
 use crate::context::Context;
 use crate::error::{ErrKind, Error};
@@ -7,13 +8,18 @@ use crate::instruction::{InstrKind, Instruction, Var};
 use crate::location::SpanTuple;
 use crate::typechecker::{CheckedType, TypeCheck, TypeCtx};
 
+use symbol::Symbol;
+
+/// Symbol used to discard the value of an expression
+const DISCARD: &str = "_";
+
 #[derive(Clone)]
 pub struct VarAssign {
     /// Is the variable mutable ? This is only useful on variable declaration
     mutable: bool,
 
     /// The "name" of the variable
-    symbol: String,
+    symbol: Symbol,
 
     value: Box<dyn Instruction>,
     typechecked: bool,
@@ -24,13 +30,19 @@ impl VarAssign {
     pub fn new(mutable: bool, symbol: String, value: Box<dyn Instruction>) -> VarAssign {
         VarAssign {
             mutable,
-            symbol,
+            symbol: Symbol::from(symbol),
             value,
             typechecked: false,
             location: None,
         }
     }
 
+    /// Create an assignment which executes `value` and discards its result, without
+    /// declaring any variable
+    pub fn discard(value: Box<dyn Instruction>) -> VarAssign {
+        VarAssign::new(false, String::from(DISCARD), value)
+    }
+
     pub fn with_loc(self, location: SpanTuple) -> VarAssign {
         VarAssign {
             location: Some(location),
@@ -40,7 +52,12 @@ impl VarAssign {
 
     /// Get a reference to the symbol of the variable assignment
     pub fn symbol(&self) -> &str {
-        &self.symbol
+        self.symbol.access()
+    }
+
+    /// Is the assignment discarding its value
+    pub fn is_discard(&self) -> bool {
+        self.symbol() == DISCARD
     }
 
     /// Is a variable is declared as mutable or not
@@ -68,6 +85,14 @@ impl Instruction for VarAssign {
         InstrKind::Statement
     }
 
+    fn children(&self) -> Vec<&dyn Instruction> {
+        vec![self.value.as_ref()]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn Instruction> {
+        vec![self.value.as_mut()]
+    }
+
     fn print(&self) -> String {
         let base = if self.mutable {
             String::from("mut ")
@@ -78,45 +103,49 @@ impl Instruction for VarAssign {
     }
 
     fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
-        // Are we creating the variable or not
-        let mut var_creation = false;
-
-        let mut var = match ctx.get_variable(&self.symbol) {
-            Some(v) => v.clone(),
-            None => {
-                let mut new_v = Var::new(self.symbol().to_string());
-                new_v.set_mutable(self.mutable());
+        if self.is_discard() {
+            self.value.execute(ctx)?;
+            return Some(ObjectInstance::void());
+        }
 
-                var_creation = true;
+        let mutable = ctx.get_variable(&self.symbol).map(Var::mutable);
 
-                new_v
-            }
-        };
-
-        match (var_creation, var.mutable()) {
-            (false, false) => {
-                // The variable already exists. So we need to error out if it isn't
-                // mutable
+        match mutable {
+            // The variable already exists. So we need to error out if it isn't
+            // mutable
+            Some(false) => {
                 ctx.error(
                     Error::new(ErrKind::Context)
                         .with_msg(format!(
                             "trying to assign value to non mutable variable `{}`: `{}`",
-                            var.name(),
+                            self.symbol,
                             self.value.print()
                         ))
                         .with_loc(self.location.clone()),
                 );
                 return None;
             }
-            (true, _) | (_, true) => var.set_instance(self.value.execute_expression(ctx)?),
-        }
+            // Assign the new value in place, without copying the variable
+            Some(true) => {
+                let instance = self.value.execute_expression(ctx)?;
+                // We can unwrap safely since we checked that the variable exists
+                ctx.get_variable_mut(&self.symbol)
+                    .unwrap()
+                    .set_instance(instance);
+            }
+            None => {
+                let mut var = Var::from_symbol(self.symbol.clone());
+                var.set_mutable(self.mutable());
+                var.set_instance(self.value.execute_expression(ctx)?);
 
-        // We can unwrap safely since we checked that the variable does not
-        // exist
-        ctx.replace_variable(var).unwrap();
+                // We can unwrap safely since we checked that the variable does not
+                // exist
+                ctx.add_variable(var).unwrap();
+            }
+        }
 
         // A variable assignment is always a statement
-        None
+        Some(ObjectInstance::void())
     }
 
     fn location(&self) -> Option<&SpanTuple> {
@@ -126,7 +155,21 @@ impl Instruction for VarAssign {
 
 impl TypeCheck for VarAssign {
     fn resolve_type(&mut self, ctx: &mut TypeCtx) -> Result<CheckedType, Error> {
-        let var_ty = match ctx.get_var(&self.symbol) {
+        if self.is_discard() {
+            self.value.type_of(ctx)?;
+            return Ok(CheckedType::Void);
+        }
+
+        if ctx.is_constant(self.symbol()) {
+            return Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!(
+                    "trying to assign value to constant `{}`",
+                    self.symbol()
+                ))
+                .with_loc(self.location.clone()));
+        }
+
+        let var_ty = match ctx.get_var(self.symbol()) {
             // FIXME: Remove clone?
             Some(checked_ty) => {
                 // If `self` is mutable, then it means that we are creating the variable
@@ -154,7 +197,12 @@ impl TypeCheck for VarAssign {
                         ))
                         .with_loc(self.location.clone()));
                 }
-                ctx.declare_var(self.symbol.clone(), instance_ty)?;
+                ctx.declare_var(self.symbol().to_owned(), instance_ty)?;
+                ctx.record_declaration(self.symbol(), self.location.clone());
+
+                if self.mutable() {
+                    ctx.record_store(self.symbol(), self.location.clone());
+                }
 
                 // We can return here since it's a new variable. This avoids checking
                 // the type later on
@@ -172,7 +220,7 @@ impl TypeCheck for VarAssign {
                 .with_loc(self.location.clone()));
         }
 
-        if var_ty != value_ty {
+        if !value_ty.is_assignable_to(&var_ty) {
             return Err(Error::new(ErrKind::TypeChecker)
                 .with_msg(format!(
                     "trying to assign value of type `{value_ty}` to variable of type `{var_ty}`"
@@ -180,6 +228,8 @@ impl TypeCheck for VarAssign {
                 .with_loc(self.location.clone()));
         }
 
+        ctx.record_store(self.symbol(), self.location.clone());
+
         Ok(CheckedType::Void)
     }
 
@@ -201,7 +251,7 @@ mod tests {
     use crate::instance::ToObjectInstance;
     use crate::parser::constructs;
     use crate::value::{JkInt, JkString};
-    use crate::{jinko, jinko_fail, span};
+    use crate::{jinko, jinko_fail, jinko_warn, span};
 
     #[test]
     fn non_mutable() {
@@ -237,6 +287,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn assign_yields_void() {
+        let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let va = constructs::expr(span!("a = 13")).unwrap().1;
+
+        assert!(va.execute(&mut i).unwrap().is_void());
+    }
+
+    #[test]
+    fn failed_assign_yields_none() {
+        let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let va_init = constructs::expr(span!("a = 13")).unwrap().1;
+        let va_0 = constructs::expr(span!("a = 15")).unwrap().1;
+
+        va_init.execute(&mut i);
+
+        assert!(va_0.execute(&mut i).is_none());
+    }
+
     #[test]
     fn assign_immutable() {
         let mut i = Context::new(Box::new(crate::io_trait::JkStdReader));
@@ -269,7 +338,7 @@ mod tests {
         };
 
         assert_eq!(
-            ctx.get_variable("x").unwrap().instance(),
+            ctx.get_variable(&"x".into()).unwrap().instance(),
             JkInt::from(0).to_instance()
         );
     }
@@ -286,13 +355,141 @@ mod tests {
         };
 
         assert_eq!(
-            ctx.get_variable("x").unwrap().instance(),
+            ctx.get_variable(&"x".into()).unwrap().instance(),
             JkInt::from(0).to_instance()
         );
     }
 
     #[test]
-    #[ignore = "Generics not re-implemented yet #587"]
+    fn discard_value() {
+        let mut ctx = jinko! {
+            _ = 1 + 1;
+        };
+
+        assert!(ctx.get_variable(&"_".into()).is_none());
+        assert!(ctx.eval("a = _").is_err());
+    }
+
+    #[test]
+    fn discard_value_executes_rhs() {
+        let ctx = jinko! {
+            mut x = 1;
+            func incr() -> int {
+                x = x + 1;
+                x
+            }
+
+            _ = incr();
+        };
+
+        assert_eq!(
+            ctx.get_variable(&"x".into()).unwrap().instance(),
+            JkInt::from(2).to_instance()
+        );
+    }
+
+    #[test]
+    fn dead_store_warns() {
+        let ctx = jinko! {
+            mut x = 1;
+            x = 2;
+            x
+        };
+
+        let warnings = ctx.error_handler.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].msg().unwrap().contains("`x`"));
+    }
+
+    #[test]
+    fn read_before_reassignment_does_not_warn() {
+        let ctx = jinko! {
+            mut x = 1;
+            y = x;
+            x = 2;
+            x
+        };
+
+        assert!(ctx.error_handler.warnings().is_empty());
+    }
+
+    #[test]
+    fn conditional_reassignment_does_not_warn() {
+        let ctx = jinko! {
+            mut x = 1;
+            if true {
+                x = 2;
+            };
+            x
+        };
+
+        assert!(ctx.error_handler.warnings().is_empty());
+    }
+
+    #[test]
+    fn unused_variable_warns() {
+        let ctx = jinko_warn! {
+            func f() -> int {
+                unused = 1;
+                2
+            }
+            f()
+        };
+
+        let warnings = ctx.error_handler.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].msg(), Some("unused variable: `unused`"));
+    }
+
+    #[test]
+    fn used_variable_does_not_warn() {
+        let ctx = jinko! {
+            func f() -> int {
+                used = 1;
+                used + 1
+            }
+            f()
+        };
+
+        assert!(ctx.error_handler.warnings().is_empty());
+    }
+
+    #[test]
+    fn underscore_variable_does_not_warn() {
+        let ctx = jinko! {
+            func f() -> int {
+                _unused = 1;
+                2
+            }
+            f()
+        };
+
+        assert!(ctx.error_handler.warnings().is_empty());
+    }
+
+    #[test]
+    fn unused_variable_in_block_warns() {
+        let ctx = jinko_warn! {
+            if true {
+                x = 1;
+            };
+        };
+
+        let warnings = ctx.error_handler.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].msg(), Some("unused variable: `x`"));
+    }
+
+    #[test]
+    fn global_variable_does_not_warn() {
+        let ctx = jinko! {
+            global = 1;
+        };
+
+        assert!(ctx.error_handler.warnings().is_empty());
+    }
+
+    #[test]
     fn generic_builtin_for_var_assign() {
         jinko! {
             a = 156;
@@ -16,7 +16,9 @@ enum Kind {
 #[derive(Clone)]
 pub struct VarOrEmptyType {
     kind: Kind,
-    symbol: String,
+    symbol: Symbol,
+    /// Value of the variable if it is a constant
+    constant: Option<ObjectInstance>,
     // FIXME: We can probably avoid keeping a `cached_type` and a `kind`. Only one
     // is enough. Refactor later
     cached_type: Option<CheckedType>,
@@ -27,19 +29,20 @@ impl VarOrEmptyType {
     pub fn new(symbol: String) -> VarOrEmptyType {
         VarOrEmptyType {
             kind: Kind::Unknown,
-            symbol,
+            symbol: Symbol::from(symbol),
+            constant: None,
             cached_type: None,
             location: None,
         }
     }
 
     fn resolve_kind(&self, ctx: &mut TypeCtx) -> Kind {
-        let resolved = ctx.get_custom_type(&self.symbol);
+        let resolved = ctx.get_custom_type(self.symbol.access());
         if resolved.is_some() {
             return Kind::EmptyTypeInst;
         }
 
-        let resolved = ctx.get_var(&self.symbol);
+        let resolved = ctx.get_var(self.symbol.access());
         if resolved.is_some() {
             return Kind::VarAccess;
         }
@@ -54,22 +57,26 @@ impl VarOrEmptyType {
 
 impl Instruction for VarOrEmptyType {
     fn kind(&self) -> InstrKind {
-        InstrKind::Expression(None)
+        InstrKind::Expression(self.constant.clone())
     }
 
     fn print(&self) -> String {
-        self.symbol.clone()
+        self.symbol.to_string()
     }
 
     fn execute(&self, ctx: &mut Context) -> Option<ObjectInstance> {
-        let symbol_type_id = TypeId::new(Symbol::from(self.symbol.clone()));
+        let symbol_type_id = TypeId::new(self.symbol.clone());
         match ctx.get_type(&symbol_type_id) {
             Some(_) => {
                 let ty_inst = TypeInstantiation::new(symbol_type_id);
                 ty_inst.execute(ctx)
             }
             None => {
-                let var_inst = Var::new(self.symbol.clone());
+                let mut var_inst = Var::from_symbol(self.symbol.clone());
+                if let Some(location) = &self.location {
+                    var_inst.set_location(location.clone());
+                }
+
                 var_inst.execute(ctx)
             }
         }
@@ -89,18 +96,23 @@ impl TypeCheck for VarOrEmptyType {
         };
 
         match kind {
-            Kind::Unknown => Ok(CheckedType::Error),
-            Kind::EmptyTypeInst => Ok(CheckedType::Resolved(TypeId::new(Symbol::from(
-                self.symbol.clone(),
-            )))),
-            Kind::VarAccess => ctx.get_var(&self.symbol).cloned().ok_or_else(|| {
-                Error::new(ErrKind::TypeChecker)
-                    .with_msg(format!(
-                        "trying to access undeclared variable `{}`",
-                        &self.symbol
-                    ))
-                    .with_loc(self.location().cloned())
-            }),
+            Kind::Unknown => Err(Error::new(ErrKind::TypeChecker)
+                .with_msg(format!("use of undeclared variable: `{}`", &self.symbol))
+                .with_loc(self.location().cloned())),
+            Kind::EmptyTypeInst => Ok(CheckedType::Resolved(TypeId::new(self.symbol.clone()))),
+            Kind::VarAccess => {
+                ctx.record_read(self.symbol.access());
+                self.constant = ctx.constant_value(self.symbol.access()).cloned();
+
+                ctx.get_var(self.symbol.access()).cloned().ok_or_else(|| {
+                    Error::new(ErrKind::TypeChecker)
+                        .with_msg(format!(
+                            "trying to access undeclared variable `{}`",
+                            &self.symbol
+                        ))
+                        .with_loc(self.location().cloned())
+                })
+            }
         }
     }
 
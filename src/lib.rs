@@ -6,8 +6,8 @@ pub mod debug;
 pub mod error;
 #[cfg(feature = "ffi")]
 mod ffi;
-// FIXME: Re-add once we reimplement generics
-// pub mod generics;
+pub mod format;
+pub mod generics;
 mod indent;
 pub mod instance;
 pub mod instruction;
@@ -19,9 +19,8 @@ pub mod value;
 
 pub use crate::error::{ErrKind, Error};
 pub use builtins::Builtins;
-pub use context::{Context, Scope, ScopeMap};
-// FIXME: Re-add once we reimplement generics
-// pub use generics::GenericUser;
+pub use context::{Context, NativeFunction, Scope, ScopeMap, ScopeSnapshot};
+pub use format::{format_source, SourceFormatter};
 pub use indent::Indent;
 pub use instance::{FromObjectInstance, ObjectInstance, ToObjectInstance};
 pub use instruction::{InstrKind, Instruction};
@@ -31,3 +30,116 @@ pub use parser::{constructs, parse, ParseInput};
 pub use symbol;
 pub use typechecker::{CheckedType, TypeCheck, TypeCtx, TypeId};
 pub use value::{JkBool, JkChar, JkConstant, JkFloat, JkInt, JkString, Value};
+
+/// Parse and typecheck some jinko source code, without ever executing it. All the
+/// errors found are returned instead of being emitted. The standard library is not
+/// included
+/// ```
+/// assert!(jinko::typecheck("a = 15; b = a + 4").is_ok());
+/// assert!(jinko::typecheck("a = 15 + 'c'").is_err());
+/// ```
+pub fn typecheck(source: &str) -> Result<(), Vec<Error>> {
+    let mut ctx = Context::new(Box::new(io_trait::JkStdReader));
+    parse(&mut ctx, source, location::Source::Input(source)).map_err(|e| vec![e])?;
+
+    match ctx.check_quietly() {
+        Ok(()) => Ok(()),
+        Err(_) => Err(ctx.error_handler.errors().to_vec()),
+    }
+}
+
+/// Parse, typecheck and execute some jinko source code, and return the value of its
+/// last expression, or `None` if the code ends with a statement. If anything fails,
+/// all the errors found are returned. The standard library is not included
+/// ```
+/// use jinko::{FromObjectInstance, JkInt};
+///
+/// let value = jinko::eval("a = 15; a + 4").unwrap().unwrap();
+/// assert_eq!(JkInt::from_instance(&value).rust_value(), 19);
+/// assert!(jinko::eval("a = 15 + 'c'").is_err());
+/// ```
+pub fn eval(source: &str) -> Result<Option<ObjectInstance>, Vec<Error>> {
+    let mut ctx = Context::new(Box::new(io_trait::JkStdReader));
+
+    ctx.eval(source).map_err(|e| {
+        let mut errors = ctx.error_handler.errors().to_vec();
+        if errors.is_empty() {
+            errors.push(e);
+        }
+
+        errors
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typecheck_valid_source() {
+        let source = "func add(a: int, b: int) -> int { a + b } sum = add(1, 2); println(sum)";
+
+        assert!(typecheck(source).is_ok());
+    }
+
+    #[test]
+    fn typecheck_returns_errors() {
+        let errors = typecheck("1 + '4'").unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), ErrKind::TypeChecker);
+    }
+
+    #[test]
+    fn typecheck_does_not_execute() {
+        assert!(typecheck("@quit(3); println(1)").is_ok());
+        assert!(typecheck("func f() -> int { f() } f()").is_ok());
+    }
+
+    #[test]
+    fn typecheck_matches_check() {
+        let sources = [
+            "a = 15; b = a + 4",
+            "a = 15 + 'c'",
+            "func f() -> int { g() } func g() -> int { 4 } f()",
+            "{ a = 1; } b = a",
+        ];
+
+        for source in sources {
+            let mut ctx = Context::new(Box::new(io_trait::JkStdReader));
+            parse(&mut ctx, source, location::Source::Input(source)).unwrap();
+
+            assert_eq!(typecheck(source).is_ok(), ctx.check().is_ok(), "{source}");
+        }
+    }
+
+    #[test]
+    fn typecheck_parse_error() {
+        assert!(typecheck("func (").is_err());
+    }
+
+    #[test]
+    fn eval_returns_last_value() {
+        let value = eval("1 + 2").unwrap().unwrap();
+
+        assert_eq!(JkInt::from_instance(&value).rust_value(), 3);
+    }
+
+    #[test]
+    fn eval_statement_has_no_value() {
+        assert!(eval("a = 1 + 2").unwrap().is_none());
+    }
+
+    #[test]
+    fn eval_returns_errors() {
+        let errors = eval("1 + '4'").unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), ErrKind::TypeChecker);
+    }
+
+    #[test]
+    fn eval_parse_error() {
+        assert!(eval("func (").is_err());
+    }
+}
@@ -8,6 +8,8 @@ use crate::location::Source;
 
 mod constant_construct;
 pub mod constructs;
+#[cfg(all(test, feature = "fuzz"))]
+mod fuzz;
 mod tokens;
 
 pub use constant_construct::ConstantConstruct;
@@ -11,7 +11,7 @@ use crate::location::{Location, SpanTuple};
 use crate::parser::{ParseInput, ParseResult, Token};
 use crate::value::{JkBool, JkChar, JkFloat, JkInt, JkString};
 
-use nom::bytes::complete::take;
+use nom::branch::alt;
 use nom::sequence::terminated;
 use nom::Err::Error as NomError;
 use nom::Slice;
@@ -66,7 +66,7 @@ impl ConstantConstruct {
     }
 
     /// | '{' expr '}'
-    /// | '\' CHAR
+    /// | '\' ( '{' | '}' | ESCAPE ) (* ESCAPE being any escape allowed in a char *)
     /// | CHAR (* anything except "{\ *)
     fn special(
         input: ParseInput,
@@ -80,24 +80,16 @@ impl ConstantConstruct {
             if input.is_empty() {
                 return ConstantConstruct::special(input, start_loc);
             }
-            let (input, special) = take(1usize)(input)?;
-            let escaped = match *special.fragment() {
-                "\"" => "\"",
-                "{" => "{",
-                "}" => "}",
-                "n" => "\n",
-                "r" => "\r",
-                "t" => "\t",
-                _ => {
-                    return Err(NomError(
-                        Error::new(ErrKind::Parsing)
-                            .with_msg(String::from("Unknown character escape")),
-                    ))
-                }
-            };
+            // Brackets only need to be escaped in strings, where they delimit
+            // formatted expressions
+            let (input, escaped) =
+                match alt((Token::left_curly_bracket, Token::right_curly_bracket))(input) {
+                    Ok((input, bracket)) => (input, bracket),
+                    Err(_) => Token::escaped_char(input)?,
+                };
 
             let (input, end_loc) = position(input)?;
-            let mut string = JkString::from(escaped);
+            let mut string = JkString::from(escaped.to_string());
             string.set_location(SpanTuple::with_source_ref(
                 input.extra,
                 start_loc,
@@ -262,4 +254,58 @@ mod tests {
 
         assert!(ConstantConstruct::string_constant(input).is_err());
     }
+
+    fn eval_string(input: &str) -> String {
+        use crate::instance::FromObjectInstance;
+
+        let mut ctx = crate::context::Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.init_stdlib().unwrap();
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+        let value = ctx.execute().unwrap().unwrap();
+
+        JkString::from_instance(&value).0
+    }
+
+    #[test]
+    fn escape_sequences() {
+        assert_eq!(eval_string(r#""line1\nline2""#), "line1\nline2");
+        assert_eq!(eval_string(r#""tab\there""#), "tab\there");
+        assert_eq!(eval_string(r#""carriage\rreturn""#), "carriage\rreturn");
+        assert_eq!(eval_string(r#""quote\"""#), "quote\"");
+        assert_eq!(eval_string(r#""single\'quote""#), "single'quote");
+        assert_eq!(eval_string(r#""back\\slash""#), "back\\slash");
+        assert_eq!(eval_string(r#""nul\0""#), "nul\0");
+        assert_eq!(eval_string(r#""\u{1F600}""#), "\u{1F600}");
+        assert_eq!(eval_string(r#""\{not formatted\}""#), "{not formatted}");
+    }
+
+    #[test]
+    fn unterminated_string_with_escape() {
+        let input = span!("\"line1\\nline2");
+
+        assert!(ConstantConstruct::string_constant(input).is_err());
+    }
+
+    #[test]
+    fn invalid_escape_location() {
+        let input = span!("\"valid \\q\"");
+
+        match ConstantConstruct::string_constant(input) {
+            Err(nom::Err::Failure(e)) => {
+                assert_eq!(e.kind(), ErrKind::Parsing);
+                assert_eq!(e.loc().unwrap().start().column(), 9);
+            }
+            _ => panic!("invalid escapes should be hard parsing errors"),
+        }
+    }
+
+    #[test]
+    fn invalid_unicode_escape() {
+        let input = span!("\"\\u{110000}\"");
+
+        assert!(matches!(
+            ConstantConstruct::string_constant(input),
+            Err(nom::Err::Failure(_))
+        ));
+    }
 }
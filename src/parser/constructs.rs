@@ -20,16 +20,17 @@ use nom::{
 };
 use nom_locate::position;
 
-use crate::error::Error;
+use crate::error::{ErrKind, Error};
 use crate::instruction::{
-    BinaryOp, Block, DecArg, FieldAccess, FunctionCall, FunctionDec, FunctionKind, IfElse, Incl,
-    Instruction, JkInst, Loop, LoopKind, MethodCall, Operator, Return, TypeDec, TypeInstantiation,
-    Var, VarAssign, VarOrEmptyType,
+    BinaryOp, Block, ConstDec, DecArg, FieldAccess, FunctionCall, FunctionDec, FunctionKind,
+    IfElse, Incl, Instruction, JkInst, Loop, LoopKind, Match, MatchArm, MethodCall, Operator,
+    Range, Return, TypeAlias, TypeDec, TypeInstantiation, Var, VarAssign, VarOrEmptyType,
 };
 use crate::location::{Location, SpanTuple};
 use crate::parser::{ConstantConstruct, ParseInput, ParseResult, Token};
 use crate::symbol::Symbol;
 use crate::typechecker::TypeId;
+use crate::value::JkInt;
 
 /// Parse as many instructions as possible
 /// many_expr = ( expr_semicolon )*
@@ -56,83 +57,170 @@ pub fn expr_semicolon(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Inst
     Ok((input, expr))
 }
 
-// expr = cmp ( '<' cmp | '>' cmp | '<=' cmp | '>=' cmp | '==' cmp | '!=' cmp)*
+/// Convert an operator token into an [`Operator`]
+fn operator(op: ParseInput) -> Result<Operator, nom::Err<Error>> {
+    op.fragment().parse().map_err(|e: Error| {
+        NomError(e.with_loc(Some(SpanTuple::with_source_ref(
+            op.extra,
+            op.into(),
+            op.into(),
+        ))))
+    })
+}
+
+/// Parse an expression, which can be a conditional expression. `cond ? a : b` is
+/// lowered to an if-else block evaluating either `a` or `b`.
+///
+/// expr = binop [ '?' expr ':' expr ]
 pub fn expr(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instruction>> {
     let input = next(input);
     let (input, start_loc) = position(input)?;
-    let (mut input, mut expr) = cmp(input)?;
-    while let Ok((new_input, op)) = alt((
+    let (input, cond) = binop(input)?;
+    let input = next(input);
+    if let Ok((input, _)) = Token::question_mark(input) {
+        let (input, if_value) = expr(input)?;
+        let (input, _) = Token::colon(next(input))?;
+        let (input, else_value) = expr(input)?;
+        let (input, end_loc) = position(input)?;
+
+        let if_body = Block::builder().instruction(if_value).build();
+        let else_body = Block::builder().instruction(else_value).build();
+
+        let mut if_else = IfElse::new(cond, if_body, Some(else_body));
+        if_else.set_location(SpanTuple::with_source_ref(
+            input.extra,
+            start_loc.into(),
+            end_loc.into(),
+        ));
+        Ok((input, Box::new(if_else)))
+    } else {
+        Ok((input, cond))
+    }
+}
+
+/// Parse a binary operator. Longer operators are tried first, so that `<<` is not
+/// parsed as `<` followed by another `<`
+fn binary_operator(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+    alt((
+        Token::left_shift,
+        Token::right_shift,
         Token::lt_eq,
         Token::gt_eq,
         Token::equals,
         Token::not_equals,
         Token::lt,
         Token::gt,
+        Token::bit_and,
+        Token::bit_or,
+        Token::bit_xor,
+        Token::add,
+        Token::sub,
+        Token::mul,
+        Token::div,
     ))(input)
-    {
-        let (new_input, rhs) = cmp(new_input)?;
-        let (new_input, end_loc) = position(new_input)?;
-        input = new_input;
-        let mut b_op = BinaryOp::new(expr, rhs, Operator::new(op.fragment()));
-        b_op.set_location(SpanTuple::with_source_ref(
-            input.extra,
-            start_loc.into(),
-            end_loc.into(),
-        ));
-        expr = Box::new(b_op);
-    }
-    Ok((input, expr))
 }
 
-pub fn cmp(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instruction>> {
+/// An operand of a binary operation, along with the location it spans
+type Operand = (Location, Location, Box<dyn Instruction>);
+
+/// operand = next factor next
+fn operand(input: ParseInput) -> ParseResult<ParseInput, Operand> {
     let input = next(input);
     let (input, start_loc) = position(input)?;
-    let (mut input, mut expr) = term(input)?;
-    while let Ok((new_input, op)) = alt((Token::add, Token::sub))(input) {
-        let (new_input, rhs) = term(new_input)?;
-        let (new_input, end_loc) = position(new_input)?;
-        input = new_input;
-        let mut b_op = BinaryOp::new(expr, rhs, Operator::new(op.fragment()));
-        b_op.set_location(SpanTuple::with_source_ref(
-            input.extra,
-            start_loc.into(),
-            end_loc.into(),
-        ));
-        expr = Box::new(b_op);
-    }
-    Ok((input, expr))
+    let (input, factor) = factor(input)?;
+    let (input, end_loc) = position(input)?;
+
+    Ok((next(input), (start_loc.into(), end_loc.into(), factor)))
 }
 
-/// term = factor next ( '*' factor next | '/' factor next )*
-fn term(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instruction>> {
-    let input = next(input);
-    let (input, start_loc) = position(input)?;
-    let (input, mut term) = factor(input)?;
-    let mut input = next(input);
-    while let Ok((new_input, op)) = alt((Token::mul, Token::div))(input) {
-        let (new_input, rhs) = factor(new_input)?;
-        let (new_input, end_loc) = position(new_input)?;
-        let new_input = next(new_input);
+/// Replace the last two operands with a binary operation applying `op` to them
+fn reduce(input: ParseInput, operands: &mut Vec<Operand>, op: Operator) {
+    // The parser always pushes an operand after each operator
+    let (_, end_loc, rhs) = operands.pop().unwrap();
+    let (start_loc, _, lhs) = operands.pop().unwrap();
+
+    let mut b_op = BinaryOp::new(lhs, rhs, op);
+    b_op.set_location(SpanTuple::with_source_ref(
+        input.extra,
+        start_loc.clone(),
+        end_loc.clone(),
+    ));
+
+    operands.push((start_loc, end_loc, Box::new(b_op)));
+}
+
+/// Parse binary operations using the Shunting Yard algorithm. Operators are kept on
+/// a stack, and the ones on top of it are applied as long as they take precedence
+/// over the incoming operator, as decided by [`Operator::has_precedence_over`]
+///
+/// binop = operand ( BINARY_OPERATOR operand )*
+fn binop(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instruction>> {
+    let (mut input, first) = operand(input)?;
+    let mut operands = vec![first];
+    let mut operators: Vec<Operator> = vec![];
+
+    while let Ok((new_input, op)) = binary_operator(input) {
+        let op = operator(op)?;
+        while operators
+            .last()
+            .is_some_and(|top| top.has_precedence_over(&op))
+        {
+            let top = operators.pop().unwrap();
+            reduce(new_input, &mut operands, top);
+        }
+        operators.push(op);
+
+        let (new_input, rhs) = operand(new_input)?;
         input = new_input;
-        let mut b_op = BinaryOp::new(term, rhs, Operator::new(op.fragment()));
-        b_op.set_location(SpanTuple::with_source_ref(
-            input.extra,
-            start_loc.into(),
-            end_loc.into(),
-        ));
-        term = Box::new(b_op);
+        operands.push(rhs);
+    }
+
+    while let Some(op) = operators.pop() {
+        reduce(input, &mut operands, op);
     }
-    Ok((input, term))
+
+    // There is always one operand left, the root of the binary operations
+    let (_, _, expr) = operands.pop().unwrap();
+
+    Ok((input, expr))
 }
 
-/// factor = next unit factor_rest
+/// Parse a factor. A minus sign directly followed by digits is part of an integer or
+/// float literal, otherwise it negates the factor following it, such as in `-(5)`
+///
+/// factor = next '-' factor (* negation *)
+///        | next unit factor_rest
 fn factor(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instruction>> {
     let input = next(input);
     let (input, start_loc) = position(input)?;
+    if let Ok((operand_input, _)) = Token::sub(input) {
+        if !operand_input.starts_with(|c: char| c.is_ascii_digit()) {
+            return negation(operand_input, start_loc.into());
+        }
+    }
+
     let (input, unit) = unit(input)?;
     factor_rest(input, unit, start_loc.into())
 }
 
+/// Negations are represented as a subtraction from zero. Integer literals are
+/// promoted when subtracted from floats, so this works for both numeric types
+fn negation(
+    input: ParseInput,
+    start_loc: Location,
+) -> ParseResult<ParseInput, Box<dyn Instruction>> {
+    let (input, operand) = factor(input)?;
+    let (input, end_loc) = position(input)?;
+    let mut negation = BinaryOp::new(Box::new(JkInt::from(0)), operand, Operator::Sub);
+    negation.set_location(SpanTuple::with_source_ref(
+        input.extra,
+        start_loc,
+        end_loc.into(),
+    ));
+
+    Ok((input, Box::new(negation)))
+}
+
 /// factor_rest = '.' IDENTIFIER next method_or_field factor_rest
 ///             | ε
 fn factor_rest(
@@ -202,7 +290,8 @@ fn method_or_field(
 /// unit = '_f' expr block next [ 'else' next block ]
 ///      | 'while' expr block
 ///      | 'loop' next block
-///      | 'for' spaced_identifier '_n' expr block
+///      | 'for' spaced_identifier '_n' for_iterable block
+///      | 'match' expr '{' match_arms
 ///
 ///      | 'func' function_declaration block
 ///      | 'test' function_declaration block
@@ -211,6 +300,8 @@ fn method_or_field(
 ///      | 'type' type_id '(' named_args
 ///      | 'incl' spaced_identifier [ 'as' next IDENTIFIER ]
 ///      | 'mut' spaced_identifier '=' expr (* mutable variable assigment *)
+///      | 'const' spaced_identifier '=' expr
+///      | '_' next '=' expr                (* discarded value *)
 ///      | '@' spaced_identifier '(' args
 ///
 ///      | 'extern' 'func' function_declaration ';'
@@ -237,6 +328,8 @@ fn unit(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instruction>> {
         unit_loop(input, start_loc.into())
     } else if let Ok((input, _)) = Token::for_tok(input) {
         unit_for(input, start_loc.into())
+    } else if let Ok((input, _)) = Token::match_tok(input) {
+        unit_match(input, start_loc.into())
     } else if let Ok((input, kind)) =
         alt((Token::func_tok, Token::test_tok, Token::mock_tok))(input)
     {
@@ -247,6 +340,10 @@ fn unit(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instruction>> {
         unit_type_decl(input, start_loc.into())
     } else if let Ok((input, _)) = Token::mut_tok(input) {
         unit_mut_var(input)
+    } else if let Ok((input, _)) = Token::const_tok(input) {
+        unit_const(input, start_loc.into())
+    } else if let Ok((input, _)) = Token::discard(input) {
+        unit_discard(input, start_loc.into())
     } else if let Ok((input, _)) = Token::at_sign(input) {
         unit_jk_inst(input, start_loc.into())
     } else if let Ok((input, _)) = Token::ext_tok(input) {
@@ -256,9 +353,15 @@ fn unit(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instruction>> {
     } else if let Ok((input, _)) = Token::left_curly_bracket(input) {
         unit_block(input, start_loc.into())
     } else if let Ok((input, _)) = Token::left_parenthesis(input) {
-        terminated(expr, Token::right_parenthesis)(input)
-    } else if let Ok(res) = constant(input) {
-        Ok(res)
+        let (input, mut expr) = terminated(expr, Token::right_parenthesis)(input)?;
+        if let Some(b_op) = expr.downcast_mut::<BinaryOp>() {
+            b_op.set_parenthesized();
+        }
+
+        Ok((input, expr))
+    } else if let res @ (Ok(_) | Err(nom::Err::Failure(_))) = constant(input) {
+        // Failures are malformed constants, which must not be parsed as identifiers
+        res
     } else {
         let (input, id) = Token::identifier(input)?;
         let input = next(input);
@@ -337,13 +440,41 @@ fn unit_loop(
     Ok((input, Box::new(loop_loop)))
 }
 
+/// for_iterable = expr next '..=' expr
+///              | expr next '..' expr
+///              | expr
+fn for_iterable(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instruction>> {
+    let (input, start_loc) = position(input)?;
+    let (input, start) = expr(input)?;
+    let input = next(input);
+
+    let (input, inclusive) = match Token::dot_dot_equal(input) {
+        Ok((input, _)) => (input, true),
+        Err(_) => match Token::dot_dot(input) {
+            Ok((input, _)) => (input, false),
+            Err(_) => return Ok((input, start)),
+        },
+    };
+
+    let (input, end) = expr(input)?;
+    let (input, end_loc) = position(input)?;
+    let mut range = Range::new(start, end, inclusive);
+    range.set_location(SpanTuple::with_source_ref(
+        input.extra,
+        start_loc.into(),
+        end_loc.into(),
+    ));
+
+    Ok((input, Box::new(range)))
+}
+
 fn unit_for(
     input: ParseInput,
     start_loc: Location,
 ) -> ParseResult<ParseInput, Box<dyn Instruction>> {
     let (input, (id, _)) = spaced_identifier(input)?;
     let (input, _) = Token::in_tok(input)?;
-    let (input, expr) = expr(input)?;
+    let (input, expr) = for_iterable(input)?;
     let (input, block) = block(input)?;
     let (input, end_loc) = position(input)?;
     let var = Var::new(id);
@@ -357,6 +488,79 @@ fn unit_for(
     Ok((input, Box::new(for_loop)))
 }
 
+fn unit_match(
+    input: ParseInput,
+    start_loc: Location,
+) -> ParseResult<ParseInput, Box<dyn Instruction>> {
+    let (input, scrutinee) = expr(input)?;
+    let (input, _) = Token::left_curly_bracket(next(input))?;
+    let (input, (arms, default)) = match_arms(input)?;
+    let (input, end_loc) = position(input)?;
+    let mut jk_match = Match::new(scrutinee, arms, default);
+    jk_match.set_location(SpanTuple::with_source_ref(
+        input.extra,
+        start_loc,
+        end_loc.into(),
+    ));
+
+    Ok((input, Box::new(jk_match)))
+}
+
+/// Parse the arms of a match. The last arm must be the default arm
+///
+/// match_arms = next '_' next '=>' match_arm_body next [ ',' ] next '}'
+///            | next ( INT | 'true' | 'false' ) next '=>' match_arm_body next ',' match_arms
+fn match_arms(mut input: ParseInput) -> ParseResult<ParseInput, (Vec<MatchArm>, Block)> {
+    let mut arms = vec![];
+    loop {
+        input = next(input);
+        if let Ok((input, _)) = Token::discard(input) {
+            let (input, _) = Token::fat_arrow(next(input))?;
+            let (input, default) = match_arm_body(input)?;
+            let (input, _) = opt(Token::comma)(next(input))?;
+            let (input, _) = Token::right_curly_bracket(next(input))?;
+
+            return Ok((input, (arms, default)));
+        }
+
+        if let Ok((end, _)) = Token::right_curly_bracket(input) {
+            return Err(NomError(
+                Error::new(ErrKind::Parsing)
+                    .with_msg(String::from(
+                        "`match` expressions must end with a default `_` arm",
+                    ))
+                    .with_loc(Some(SpanTuple::with_source_ref(
+                        input.extra,
+                        input.into(),
+                        end.into(),
+                    ))),
+            ));
+        }
+
+        let (new_input, pattern) = alt((
+            ConstantConstruct::int_constant,
+            ConstantConstruct::bool_constant,
+        ))(input)?;
+        let (new_input, _) = Token::fat_arrow(next(new_input))?;
+        let (new_input, body) = match_arm_body(new_input)?;
+        let (new_input, _) = Token::comma(next(new_input))?;
+
+        input = new_input;
+        arms.push((pattern, body));
+    }
+}
+
+/// match_arm_body = expr
+fn match_arm_body(input: ParseInput) -> ParseResult<ParseInput, Block> {
+    let (input, body) = expr(input)?;
+    let body = match body.downcast::<Block>() {
+        Ok(block) => *block,
+        Err(body) => Block::builder().instruction(body).build(),
+    };
+
+    Ok((input, body))
+}
+
 fn unit_func<'i>(
     input: ParseInput<'i>,
     kind: ParseInput<'i>,
@@ -455,12 +659,27 @@ fn type_id(input: ParseInput) -> ParseResult<ParseInput, TypeId> {
 }
 
 /// type_id '(' type_inst_arg (',' type_inst_arg)* ')'
+///   | spaced_identifier '=' type_id
 fn unit_type_decl(
     input: ParseInput,
     start_loc: Location,
 ) -> ParseResult<ParseInput, Box<dyn Instruction>> {
     // FIXME: This needs to use TypeIds
     let (input, (name, _)) = spaced_identifier(input)?;
+    if let Ok((input, _)) = Token::equal(input) {
+        let (input, target) = type_id(input)?;
+        let (input, end_loc) = position(input)?;
+
+        let mut alias = TypeAlias::new(name, target);
+        alias.set_location(SpanTuple::with_source_ref(
+            input.extra,
+            start_loc,
+            end_loc.into(),
+        ));
+
+        return Ok((input, Box::new(alias)));
+    }
+
     let (input, generics) = maybe_generic_list(input)?;
     let (input, mut type_dec) = if let Ok((input, _)) = Token::left_parenthesis(input) {
         let (input, first_arg) = typed_arg(input)?;
@@ -501,6 +720,44 @@ fn unit_mut_var(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Instructio
     Ok((input, Box::new(assignment)))
 }
 
+/// spaced_identifier '=' expr
+fn unit_const(
+    input: ParseInput,
+    start_loc: Location,
+) -> ParseResult<ParseInput, Box<dyn Instruction>> {
+    let (input, (name, _)) = spaced_identifier(input)?;
+    let (input, _) = Token::equal(input)?;
+    let (input, value) = expr(input)?;
+    let (input, end_loc) = position(input)?;
+
+    let mut constant = ConstDec::new(name, value);
+    constant.set_location(SpanTuple::with_source_ref(
+        input.extra,
+        start_loc,
+        end_loc.into(),
+    ));
+
+    Ok((input, Box::new(constant)))
+}
+
+fn unit_discard(
+    input: ParseInput,
+    start_loc: Location,
+) -> ParseResult<ParseInput, Box<dyn Instruction>> {
+    let (input, _) = Token::equal(next(input))?;
+    let (input, value) = expr(input)?;
+    let (input, end_loc) = position(input)?;
+
+    let mut assignment = VarAssign::discard(value);
+    assignment.set_location(SpanTuple::with_source_ref(
+        input.extra,
+        start_loc,
+        end_loc.into(),
+    ));
+
+    Ok((input, Box::new(assignment)))
+}
+
 /// IDENTIFIER next '(' next args
 fn unit_jk_inst(
     input: ParseInput,
@@ -643,22 +900,29 @@ pub fn block(input: ParseInput) -> ParseResult<ParseInput, Block> {
 /// inner_block = '}'
 ///             | expr '}'                  (* The only case where block is an expr *)
 ///             | expr ';' next inner_block
-fn inner_block(input: ParseInput) -> ParseResult<ParseInput, Block> {
-    if let Ok((input, _)) = Token::right_curly_bracket(input) {
-        return Ok((input, Block::new()));
-    }
+fn inner_block(mut input: ParseInput) -> ParseResult<ParseInput, Block> {
+    // Statements are collected iteratively rather than recursively, so that
+    // long blocks do not exhaust the stack
+    let mut block = Block::new();
+
+    loop {
+        // The block is either empty or its last instruction was followed by a
+        // semicolon: Its value is discarded
+        if let Ok((input, _)) = Token::right_curly_bracket(input) {
+            block.set_statement(true);
+            return Ok((input, block));
+        }
 
-    let (input, inst) = expr(input)?;
-    if let Ok((input, _)) = Token::right_curly_bracket(input) {
-        let mut block = Block::new();
+        let (next_input, inst) = expr(input)?;
         block.add_instruction(inst);
-        block.set_statement(false);
-        return Ok((input, block));
-    }
 
-    let (input, mut block) = preceded(Token::semicolon, preceded(nom_next, inner_block))(input)?;
-    block.push_front_instruction(inst);
-    Ok((input, block))
+        if let Ok((input, _)) = Token::right_curly_bracket(next_input) {
+            return Ok((input, block));
+        }
+
+        let (next_input, _) = preceded(Token::semicolon, nom_next)(next_input)?;
+        input = next_input;
+    }
 }
 
 /// func_type_or_var = '(' next func_or_type_inst_args
@@ -765,6 +1029,9 @@ fn generic_func_or_type_inst_args(
 
 /// args = expr ( ',' expr )* ')'
 ///      | ')'
+///
+/// Named arguments (`f(x = 1)`) are parsed as variable assignments and mapped to
+/// the function's arguments when the call is typechecked or executed
 fn args(input: ParseInput) -> ParseResult<ParseInput, Vec<Box<dyn Instruction>>> {
     if let Ok((input, _)) = Token::right_parenthesis(input) {
         return Ok((input, vec![]));
@@ -809,16 +1076,21 @@ fn multi_type(input: ParseInput) -> ParseResult<ParseInput, TypeId> {
     Ok((input, first_type))
 }
 
-/// typed_arg = spaced_identifier ':' spaced_identifier
+/// typed_arg = spaced_identifier ':' spaced_identifier [ '=' expr ]
 fn typed_arg(input: ParseInput) -> ParseResult<ParseInput, DecArg> {
     let (input, (id, start_loc)) = spaced_identifier(input)?;
     let (input, _) = Token::colon(input)?;
     let input = next(input);
     let (input, types) = multi_type(input)?;
     let input = next(input);
+    let (input, default) = opt(preceded(Token::equal, expr))(input)?;
+    let input = next(input);
     let (input, end_loc) = position(input)?;
 
     let mut dec_arg = DecArg::new(id, types);
+    if let Some(default) = default {
+        dec_arg.set_default(default);
+    }
     dec_arg.set_location(SpanTuple::with_source_ref(
         input.extra,
         start_loc,
@@ -895,8 +1167,9 @@ pub(crate) fn constant(input: ParseInput) -> ParseResult<ParseInput, Box<dyn Ins
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::instruction::InstrKind;
     use crate::span;
-    use crate::value::{JkFloat, JkInt};
+    use crate::value::JkFloat;
 
     #[test]
     fn consume_whitespace() {
@@ -936,6 +1209,102 @@ mod tests {
         assert_eq!(rhs.print(), "9.999");
     }
 
+    #[test]
+    fn scientific_float() {
+        let mut ctx = crate::context::Context::new(Box::new(crate::io_trait::JkStdReader));
+        let (input, mut expr) = expr(span!("2.5e-3")).unwrap();
+        let float: &JkFloat = expr.downcast_ref().unwrap();
+
+        assert_eq!(*input.fragment(), "");
+        assert_eq!(float.rust_value(), 0.0025);
+        assert_eq!(
+            ctx.type_check(expr.as_mut()).unwrap(),
+            crate::typechecker::CheckedType::Resolved("float".into())
+        );
+    }
+
+    #[test]
+    fn scientific_float_missing_exponent() {
+        let mut ctx = crate::context::Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert_eq!(ctx.eval("1e").unwrap_err().kind(), ErrKind::Parsing);
+        assert_eq!(ctx.eval("1e+").unwrap_err().kind(), ErrKind::Parsing);
+    }
+
+    #[test]
+    fn separated_numbers() {
+        let (_, expr) = expr(span!("1_000_000 + 1.234_567")).unwrap();
+        let operation: &BinaryOp = expr.downcast_ref().unwrap();
+        let lhs: &JkInt = operation.lhs().downcast_ref().unwrap();
+        let rhs: &JkFloat = operation.rhs().downcast_ref().unwrap();
+
+        assert_eq!(lhs.rust_value(), 1_000_000);
+        assert_eq!(rhs.rust_value(), 1.234_567);
+    }
+
+    #[test]
+    fn misplaced_separators() {
+        let mut ctx = crate::context::Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert_eq!(ctx.eval("1_").unwrap_err().kind(), ErrKind::Parsing);
+        assert_eq!(ctx.eval("1__0").unwrap_err().kind(), ErrKind::Parsing);
+    }
+
+    #[test]
+    fn radix_int() {
+        let mut ctx = crate::context::Context::new(Box::new(crate::io_trait::JkStdReader));
+        let (input, mut expr) = expr(span!("0xFF_FF")).unwrap();
+        let int: &JkInt = expr.downcast_ref().unwrap();
+
+        assert_eq!(*input.fragment(), "");
+        assert_eq!(int.rust_value(), 0xFFFF);
+        assert_eq!(
+            ctx.type_check(expr.as_mut()).unwrap(),
+            crate::typechecker::CheckedType::Resolved("int".into())
+        );
+    }
+
+    #[test]
+    fn radix_int_overflow() {
+        let mut ctx = crate::context::Context::new(Box::new(crate::io_trait::JkStdReader));
+
+        assert_eq!(
+            ctx.eval("0xFFFF_FFFF_FFFF_FFFF").unwrap_err().kind(),
+            ErrKind::Parsing
+        );
+    }
+
+    #[test]
+    fn min_int_literal() {
+        let (input, expr) = expr(span!("-9223372036854775808")).unwrap();
+        let int: &JkInt = expr.downcast_ref().unwrap();
+
+        assert_eq!(*input.fragment(), "");
+        assert_eq!(int.rust_value(), i64::MIN);
+    }
+
+    #[test]
+    fn negation() {
+        use crate::instance::FromObjectInstance;
+
+        let mut ctx = crate::context::Context::new(Box::new(crate::io_trait::JkStdReader));
+        let (_, negation) = expr(span!("-(5)")).unwrap();
+
+        assert!(negation.downcast_ref::<BinaryOp>().is_some());
+
+        let eval_int = |ctx: &mut crate::context::Context, input| {
+            JkInt::from_instance(&ctx.eval(input).unwrap().unwrap()).rust_value()
+        };
+
+        assert_eq!(eval_int(&mut ctx, "-(5)"), -5);
+        assert_eq!(eval_int(&mut ctx, "a = 3; -a * 2"), -6);
+        assert_eq!(eval_int(&mut ctx, "b = 3; 4 - -b"), 7);
+        assert_eq!(
+            JkFloat::from_instance(&ctx.eval("c = 2.5; -c").unwrap().unwrap()).rust_value(),
+            -2.5
+        );
+    }
+
     #[test]
     fn chained_sum_sub() {
         let (input, expr) = expr(span!("239 + 809 - 1004")).unwrap();
@@ -976,6 +1345,19 @@ mod tests {
         assert_eq!(fourth.print(), "1004");
     }
 
+    #[test]
+    fn parenthesized_comparison() {
+        let (input, expr) = expr(span!("(1 == 2)")).unwrap();
+        let equals: &BinaryOp = expr.downcast_ref().unwrap();
+        let lhs: &JkInt = equals.lhs().downcast_ref().unwrap();
+        let rhs: &JkInt = equals.rhs().downcast_ref().unwrap();
+
+        assert_eq!(*input.fragment(), "");
+        assert_eq!(equals.operator(), Operator::Equals);
+        assert_eq!(lhs.print(), "1");
+        assert_eq!(rhs.print(), "2");
+    }
+
     #[test]
     fn tricky_math_precedence() {
         let (input, expr) = expr(span!("5.9 + 128 / 809.1 - 1 * 1.1")).unwrap();
@@ -1002,6 +1384,83 @@ mod tests {
         assert_eq!(fifth.print(), "1.1");
     }
 
+    /// Print an expression with each binary operation surrounded by parentheses, to
+    /// make the nesting of the tree visible
+    fn nesting(expr: &dyn Instruction) -> String {
+        match expr.downcast_ref::<BinaryOp>() {
+            Some(b_op) => format!(
+                "({} {} {})",
+                nesting(b_op.lhs()),
+                b_op.operator().as_str(),
+                nesting(b_op.rhs())
+            ),
+            None => expr.print(),
+        }
+    }
+
+    fn assert_nesting(input: &str, expected: &str) {
+        let span = nom_locate::LocatedSpan::new_extra(input, crate::location::Source::Input(input));
+        let (input, expr) = expr(span).unwrap();
+
+        assert_eq!(*input.fragment(), "");
+        assert_eq!(nesting(expr.as_ref()), expected);
+    }
+
+    #[test]
+    fn mixed_arithmetic_precedence() {
+        assert_nesting("1 + 2 * 3 - 4 / 2", "((1 + (2 * 3)) - (4 / 2))");
+    }
+
+    #[test]
+    fn arithmetic_left_associativity() {
+        assert_nesting("1 - 2 - 3", "((1 - 2) - 3)");
+        assert_nesting("8 / 4 / 2", "((8 / 4) / 2)");
+        assert_nesting("8 / 4 * 2", "((8 / 4) * 2)");
+    }
+
+    #[test]
+    fn comparison_precedence() {
+        assert_nesting("1 + 2 < 3 * 4", "((1 + 2) < (3 * 4))");
+        assert_nesting("1 == 2 != 3", "((1 == 2) != 3)");
+    }
+
+    #[test]
+    fn shift_precedence() {
+        assert_nesting("1 << 2 + 3", "(1 << (2 + 3))");
+        assert_nesting("1 * 2 >> 3 - 1", "((1 * 2) >> (3 - 1))");
+    }
+
+    #[test]
+    fn bitwise_precedence() {
+        assert_nesting("1 | 2 << 3 == 4", "((1 | (2 << 3)) == 4)");
+        assert_nesting("1 & 2 ^ 3", "((1 & 2) ^ 3)");
+    }
+
+    #[test]
+    fn mixed_bitwise_precedence() {
+        assert_nesting("1 | 2 & 3", "(1 | (2 & 3))");
+        assert_nesting("1 ^ 2 & 3", "(1 ^ (2 & 3))");
+        assert_nesting("1 | 2 ^ 3", "(1 | (2 ^ 3))");
+        assert_nesting("1 & 2 | 3 ^ 4 & 5", "((1 & 2) | (3 ^ (4 & 5)))");
+        assert_nesting("1 | 2 | 3", "((1 | 2) | 3)");
+    }
+
+    #[test]
+    fn parentheses_precedence() {
+        assert_nesting("(1 + 2) * 3", "((1 + 2) * 3)");
+        assert_nesting("1 - (2 - 3)", "(1 - (2 - 3))");
+        assert_nesting("(1 == 2) != (3 == 4)", "((1 == 2) != (3 == 4))");
+    }
+
+    #[test]
+    fn parentheses_are_printed() {
+        let (_, grouped) = expr(span!("(1 + 2) * 3")).unwrap();
+        let (_, ungrouped) = expr(span!("1 + 2 * 3")).unwrap();
+
+        assert_eq!(grouped.print(), "(1 + 2) * 3");
+        assert_eq!(ungrouped.print(), "1 + 2 * 3");
+    }
+
     #[test]
     fn method_call_no_arg() {
         let (input, expr) = expr(span!("a.call( )")).unwrap();
@@ -1323,10 +1782,38 @@ mod tests {
 
     #[test]
     fn t_binary_op_invalid() {
-        let (input, expr) = expr(span!("a ? 12")).unwrap();
+        let (input, expr) = expr(span!("a $ 12")).unwrap();
 
         assert!(expr.downcast_ref::<BinaryOp>().is_none());
-        assert_eq!(*input.fragment(), "? 12");
+        assert_eq!(*input.fragment(), "$ 12");
+    }
+
+    #[test]
+    fn t_conditional_expr_valid() {
+        let (input, expr) = expr(span!("a < 12 ? a : 12")).unwrap();
+
+        assert!(expr.downcast_ref::<IfElse>().is_some());
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn t_match_valid() {
+        let (input, expr) = expr(span!("match a { 0 => 1, 1 => { 2 }, _ => 3, }")).unwrap();
+
+        assert!(expr.downcast_ref::<Match>().is_some());
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn t_match_without_default() {
+        assert!(expr(span!("match a { 0 => 1, 1 => 2 }")).is_err());
+        assert!(expr(span!("match a { _ => 3, 0 => 1 }")).is_err());
+    }
+
+    #[test]
+    fn t_conditional_expr_invalid() {
+        assert!(expr(span!("a ? 12")).is_err());
+        assert!(expr(span!("a ? : 12")).is_err());
     }
 
     #[test]
@@ -1349,6 +1836,46 @@ mod tests {
         assert!(assign.mutable());
     }
 
+    #[test]
+    fn discard_assignment() {
+        let (input, expr) = expr(span!("_ = 1 + 1")).unwrap();
+        let assign = expr.downcast_ref::<VarAssign>().unwrap();
+
+        assert_eq!(*input.fragment(), "");
+        assert!(assign.is_discard());
+    }
+
+    #[test]
+    fn underscore_prefixed_identifier_is_not_discard() {
+        let (input, expr) = expr(span!("_a = 1")).unwrap();
+        let assign = expr.downcast_ref::<VarAssign>().unwrap();
+
+        assert_eq!(*input.fragment(), "");
+        assert!(!assign.is_discard());
+    }
+
+    #[test]
+    fn long_block_does_not_overflow() {
+        let input = format!("{{ {} }}", "a = 1;".repeat(5000));
+        let input = nom_locate::LocatedSpan::new_extra(
+            input.as_str(),
+            crate::location::Source::Input(&input),
+        );
+        let (input, block) = block(input).unwrap();
+
+        assert_eq!(*input.fragment(), "");
+        assert_eq!(block.instructions().len(), 5000);
+        assert_eq!(block.kind(), InstrKind::Statement);
+    }
+
+    #[test]
+    fn block_trailing_expression() {
+        let (_, block) = block(span!("{ a = 1; a }")).unwrap();
+
+        assert_eq!(block.instructions().len(), 2);
+        assert_ne!(block.kind(), InstrKind::Statement);
+    }
+
     #[test]
     fn jk_inst_no_arg() {
         let (input, expr) = expr(span!("@quit ( )")).unwrap();
@@ -1658,6 +2185,18 @@ func void() { }"##
         assert!(expr(span!("a = CustomType;")).is_ok())
     }
 
+    #[test]
+    fn type_alias() {
+        let (_, alias) = expr(span!("type Meters = int")).unwrap();
+        let alias = alias.downcast_ref::<TypeAlias>().unwrap();
+
+        assert_eq!(alias.name(), "Meters");
+        assert_eq!(alias.target(), &TypeId::from("int"));
+
+        assert!(expr(span!("type Ids=Vec[int]")).is_ok());
+        assert!(expr(span!("type Meters = ")).is_err());
+    }
+
     #[test]
     fn generic_type_decl() {
         assert!(expr(span!("type Generic[T](inner: T);")).is_ok());
@@ -0,0 +1,70 @@
+//! Randomized robustness checks for both parsers. Inputs are built by gluing
+//! together keywords, tokens and garbage, and neither parser is allowed to panic
+//! on them: every failure must be reported as a parsing error. Run with
+//! `cargo test --features fuzz fuzz`, and set `JINKO_FUZZ_SEED` and
+//! `JINKO_FUZZ_ITERATIONS` to explore other inputs.
+
+use std::panic;
+
+use crate::context::Context;
+use crate::io_trait::JkStdReader;
+use crate::location::Source;
+
+const PIECES: &[&str] = &[
+    "func", "test", "mock", "type", "ext", "for", "in", "while", "loop", "mut", "true", "false",
+    "incl", "as", "return", "if", "else", "(", ")", "{", "}", "[", "]", ",", ";", ":", "::", "=",
+    "==", "!=", "<", ">", "<=", ">=", "+", "-", "*", "/", "@", "'", "\"", "a", "b", "x1", "_", "0",
+    "12", "1.5", ".", "->", " ", "\n", "//", "/*", "*/", "#", "\\", "é", "'c'", "\"s\"",
+];
+
+const MAX_PIECES: u64 = 30;
+
+/// Small xorshift generator, so that failures can be replayed from their seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn input(&mut self) -> String {
+        let len = self.next() % MAX_PIECES;
+
+        (0..len).fold(String::new(), |mut input, _| {
+            input.push_str(PIECES[self.next() as usize % PIECES.len()]);
+            if self.next().is_multiple_of(3) {
+                input.push(' ');
+            }
+            input
+        })
+    }
+}
+
+fn env_or(var: &str, default: u64) -> u64 {
+    std::env::var(var)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn parse_both(input: &str) {
+    let mut ctx = Context::new(Box::new(JkStdReader));
+    let _ = crate::parser::parse(&mut ctx, input, Source::Input(input));
+    let _ = xparser::parse(input, Source::Input(input));
+}
+
+#[test]
+fn fuzz_parsers_do_not_panic() {
+    let mut rng = Rng(env_or("JINKO_FUZZ_SEED", 0x6a696e6b6f).max(1));
+    let iterations = env_or("JINKO_FUZZ_ITERATIONS", 20_000);
+
+    let crashes: Vec<String> = (0..iterations)
+        .map(|_| rng.input())
+        .filter(|input| panic::catch_unwind(|| parse_both(input)).is_err())
+        .collect();
+
+    assert!(crashes.is_empty(), "parsers panicked on {:#?}", crashes);
+}
@@ -4,19 +4,21 @@
 
 use nom::Err::Error as NomError;
 use nom::{
-    branch::alt, bytes::complete::tag, bytes::complete::take_until, bytes::complete::take_while,
-    bytes::complete::take_while1, character::complete::anychar, character::complete::char,
-    character::is_alphanumeric, character::is_digit, combinator::not, combinator::opt,
-    combinator::peek, multi::many0, sequence::delimited, sequence::pair,
+    branch::alt, bytes::complete::tag, bytes::complete::tag_no_case, bytes::complete::take_until,
+    bytes::complete::take_while, bytes::complete::take_while1, bytes::complete::take_while_m_n,
+    character::complete::anychar, character::complete::char, character::is_alphanumeric,
+    combinator::not, combinator::opt, combinator::peek, combinator::value, multi::many0,
+    sequence::delimited, sequence::pair, sequence::preceded,
 };
 
 use crate::error::{ErrKind, Error};
+use crate::location::SpanTuple;
 use crate::parser::{ParseInput, ParseResult};
 
 /// Reserved Keywords by jinko
-const RESERVED_KEYWORDS: [&str; 14] = [
+const RESERVED_KEYWORDS: [&str; 16] = [
     "func", "test", "mock", "type", "ext", "for", "while", "loop", "mut", "true", "false", "incl",
-    "as", "return",
+    "as", "return", "const", "match",
 ];
 
 pub struct Token;
@@ -105,6 +107,10 @@ impl Token {
         Token::specific_char(input, ':')
     }
 
+    pub fn question_mark(input: ParseInput) -> ParseResult<ParseInput, char> {
+        Token::specific_char(input, '?')
+    }
+
     pub fn semicolon(input: ParseInput) -> ParseResult<ParseInput, char> {
         Token::specific_char(input, ';')
     }
@@ -113,6 +119,10 @@ impl Token {
         Token::specific_char(input, '@')
     }
 
+    pub fn discard(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::specific_token(input, "_")
+    }
+
     pub fn func_tok(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
         Token::specific_token(input, "func")
     }
@@ -149,6 +159,10 @@ impl Token {
         Token::specific_token(input, "mut")
     }
 
+    pub fn const_tok(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::specific_token(input, "const")
+    }
+
     pub fn if_tok(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
         Token::specific_token(input, "if")
     }
@@ -157,6 +171,10 @@ impl Token {
         Token::specific_token(input, "else")
     }
 
+    pub fn match_tok(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::specific_token(input, "match")
+    }
+
     pub fn return_tok(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
         Token::specific_token(input, "return")
     }
@@ -226,10 +244,26 @@ impl Token {
         Token::token(input, "!=")
     }
 
-    pub fn _left_shift(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+    pub fn bit_and(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::token(input, "&")
+    }
+
+    pub fn bit_or(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::token(input, "|")
+    }
+
+    pub fn bit_xor(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::token(input, "^")
+    }
+
+    pub fn left_shift(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
         Token::token(input, "<<")
     }
 
+    pub fn right_shift(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::token(input, ">>")
+    }
+
     pub fn true_tok(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
         let (input, t) = Token::specific_token(input, "true")?;
 
@@ -246,6 +280,10 @@ impl Token {
         Token::specific_token(input, "->")
     }
 
+    pub fn fat_arrow(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::token(input, "=>")
+    }
+
     pub fn comment_multi_start(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
         let comment = tag("/*")(input)?;
 
@@ -271,7 +309,18 @@ impl Token {
     }
 
     pub fn dot(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
-        Token::token(input, ".")
+        let (input, dot) = Token::token(input, ".")?;
+        peek(not(char('.')))(input)?;
+
+        Ok((input, dot))
+    }
+
+    pub fn dot_dot(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::token(input, "..")
+    }
+
+    pub fn dot_dot_equal(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
+        Token::token(input, "..=")
     }
 
     pub fn inner_identifer(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
@@ -324,10 +373,47 @@ impl Token {
         Ok((input, identifier))
     }
 
-    fn non_neg_num(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
-        let num = take_while1(|c| is_digit(c as u8))(input)?;
+    /// Error for a malformed literal going from `start` to `end`. The error is a
+    /// failure, so that the literal does not get parsed as something else instead
+    fn literal_failure(msg: String, start: ParseInput, end: ParseInput) -> nom::Err<Error> {
+        nom::Err::Failure(Error::new(ErrKind::Parsing).with_msg(msg).with_loc(Some(
+            SpanTuple::with_source_ref(start.extra, start.into(), end.into()),
+        )))
+    }
 
-        Ok(num)
+    /// Parse a non-negative decimal number
+    fn non_neg_num(input: ParseInput) -> ParseResult<ParseInput, String> {
+        Token::digits(input, 10)
+    }
+
+    /// Parse digits in the given radix. They can be separated by single underscores
+    /// for readability, such as in `1_000_000`. The underscores are stripped from the
+    /// returned digits
+    fn digits(input: ParseInput, radix: u32) -> ParseResult<ParseInput, String> {
+        peek(take_while1(|c: char| c.is_digit(radix)))(input)?;
+        let (input, num) = take_while1(|c: char| c.is_digit(radix) || c == '_')(input)?;
+
+        if num.ends_with('_') || num.contains("__") {
+            return Err(Token::literal_failure(
+                format!("invalid number `{num}`: underscores must be placed between digits"),
+                num,
+                input,
+            ));
+        }
+
+        Ok((input, num.replace('_', "")))
+    }
+
+    /// Parse the prefix of an integer literal, such as `0x`, and return the radix it
+    /// indicates. Integers without a prefix are decimal
+    fn radix_prefix(input: ParseInput) -> ParseResult<ParseInput, u32> {
+        let (input, radix) = opt(alt((
+            value(16, tag_no_case("0x")),
+            value(8, tag_no_case("0o")),
+            value(2, tag_no_case("0b")),
+        )))(input)?;
+
+        Ok((input, radix.unwrap_or(10)))
     }
 
     pub fn bool_constant(input: ParseInput) -> ParseResult<ParseInput, bool> {
@@ -337,49 +423,184 @@ impl Token {
         Ok((input, b.parse::<bool>().unwrap()))
     }
 
+    /// Parse the exponent of a floating point number, such as `e10`, `E6` or `e-3`.
+    /// An exponent marker which is not followed by digits is a hard error, so that
+    /// `1e` does not get parsed as an integer followed by an identifier
+    fn float_exponent(input: ParseInput) -> ParseResult<ParseInput, Option<String>> {
+        let (input, marker) = opt(alt((char('e'), char('E'))))(input)?;
+        if marker.is_none() {
+            return Ok((input, None));
+        }
+
+        let (input, sign) = opt(alt((char('+'), char('-'))))(input)?;
+        match Token::non_neg_num(input) {
+            Ok((input, digits)) => {
+                let sign = sign.map(String::from).unwrap_or_default();
+                Ok((input, Some(format!("e{sign}{digits}"))))
+            }
+            Err(nom::Err::Error(_)) => Err(Token::literal_failure(
+                String::from("missing digits in the exponent of a floating point number"),
+                input,
+                input,
+            )),
+            Err(e) => Err(e),
+        }
+    }
+
     pub fn float_constant(input: ParseInput) -> ParseResult<ParseInput, f64> {
         let (input, negative_sign) = opt(char('-'))(input)?;
-        let (input, whole) = Token::int_constant(input)?;
-        let (input, _) = char('.')(input)?;
-        let (input, decimal) = Token::non_neg_num(input)?;
+        let (input, whole) = Token::non_neg_num(input)?;
+        let (input, decimal) = opt(preceded(char('.'), Token::non_neg_num))(input)?;
+        let (input, exponent) = Token::float_exponent(input)?;
+
+        let number = match (decimal, exponent) {
+            (None, None) => {
+                return Err(NomError(
+                    Error::new(ErrKind::Parsing)
+                        .with_msg(format!("not a floating point number: {whole}")),
+                ))
+            }
+            (decimal, exponent) => format!(
+                "{whole}{}{}",
+                decimal.map(|d| format!(".{d}")).unwrap_or_default(),
+                exponent.unwrap_or_default()
+            ),
+        };
 
-        match format!("{whole}.{decimal}").parse::<f64>() {
+        match number.parse::<f64>() {
             Ok(value) => match negative_sign {
                 Some(_) => Ok((input, -value)),
                 None => Ok((input, value)),
             },
-            Err(_) => Err(NomError(Error::new(ErrKind::Parsing).with_msg(format!(
-                "invalid floating point number: {whole}.{decimal}"
-            )))),
+            Err(_) => Err(NomError(
+                Error::new(ErrKind::Parsing)
+                    .with_msg(format!("invalid floating point number: {number}")),
+            )),
         }
     }
 
     pub fn int_constant(input: ParseInput) -> ParseResult<ParseInput, i64> {
         let (input, negative_sign) = opt(char('-'))(input)?;
-        let (input, num) = Token::non_neg_num(input)?;
+        let start = input;
+        let literal = |end: ParseInput| {
+            let len = end.location_offset() - start.location_offset();
+            start.fragment()[..len].to_string()
+        };
 
-        match num.parse::<i64>() {
-            Ok(value) => match negative_sign {
-                Some(_) => Ok((input, -value)),
-                None => Ok((input, value)),
-            },
-            Err(_) => Err(NomError(
-                Error::new(ErrKind::Parsing).with_msg(format!("invalid integer: {num}")),
+        let (input, radix) = Token::radix_prefix(input)?;
+        let (input, num) = match Token::digits(input, radix) {
+            Err(nom::Err::Error(_)) if radix != 10 => {
+                return Err(Token::literal_failure(
+                    format!("missing digits in integer literal `{}`", literal(input)),
+                    start,
+                    input,
+                ))
+            }
+            res => res?,
+        };
+
+        if radix != 10 && input.starts_with(|c: char| c.is_alphanumeric()) {
+            return Err(Token::literal_failure(
+                format!(
+                    "invalid digit in base {radix} integer literal `{}`",
+                    literal(input)
+                ),
+                start,
+                input,
+            ));
+        }
+
+        // The sign is parsed along with the digits, so that `i64::MIN` does not overflow
+        let num = match negative_sign {
+            Some(_) => format!("-{num}"),
+            None => num,
+        };
+
+        match i64::from_str_radix(&num, radix) {
+            Ok(value) => Ok((input, value)),
+            Err(_) => Err(Token::literal_failure(
+                format!("invalid integer: {}", literal(input)),
+                start,
+                input,
             )),
         }
     }
 
-    /// Parse a single character constant and return the character inside the quotes
+    /// Parse a single character constant and return the character inside the quotes.
+    /// The character can be escaped, such as in `'\n'` or `'\u{1F600}'`
     pub fn char_constant(input: ParseInput) -> ParseResult<ParseInput, char> {
         let (input, _) = Token::single_quote(input)?;
-        let (input, character) = anychar(input)?;
+        let (input, character) = match Token::backslash(input) {
+            Ok((input, _)) => Token::escaped_char(input)?,
+            Err(_) => anychar(input)?,
+        };
         let (input, _) = Token::single_quote(input)?;
 
-        // FIXME: Handle escaping as well
+        Ok((input, character))
+    }
+
+    /// Parse the escape sequence following a backslash in a character or string
+    /// literal, and return the character it represents. Unknown escape sequences and
+    /// invalid unicode code points are hard errors
+    pub fn escaped_char(input: ParseInput) -> ParseResult<ParseInput, char> {
+        let start = input;
+        let (input, escape) = anychar(input)?;
+        let character = match escape {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            'u' => return Token::unicode_escape(input, start),
+            _ => {
+                return Err(Token::literal_failure(
+                    format!("unknown character escape `\\{escape}`"),
+                    start,
+                    input,
+                ))
+            }
+        };
 
         Ok((input, character))
     }
 
+    /// Parse the code point of a unicode escape sequence, such as `{1F600}` in
+    /// `\u{1F600}`. `start` is the position of the escape sequence
+    fn unicode_escape<'i>(
+        input: ParseInput<'i>,
+        start: ParseInput<'i>,
+    ) -> ParseResult<ParseInput<'i>, char> {
+        let code_point: ParseResult<ParseInput, ParseInput> = delimited(
+            char('{'),
+            take_while_m_n(1, 6, |c: char| c.is_ascii_hexdigit()),
+            char('}'),
+        )(input);
+        let (input, code_point) = code_point.map_err(|_| {
+            Token::literal_failure(
+                String::from(
+                    "invalid unicode escape: expected up to six hexadecimal digits between braces, such as `\\u{1F600}`",
+                ),
+                start,
+                input,
+            )
+        })?;
+
+        // The digits were already checked, so the code point fits in a `u32`
+        match u32::from_str_radix(code_point.fragment(), 16)
+            .ok()
+            .and_then(char::from_u32)
+        {
+            Some(character) => Ok((input, character)),
+            None => Err(Token::literal_failure(
+                format!("invalid unicode code point `{}`", code_point.fragment()),
+                start,
+                input,
+            )),
+        }
+    }
+
     /// Parse a string constant and return the characters between the double quotes
     pub fn string_constant(input: ParseInput) -> ParseResult<ParseInput, ParseInput> {
         // FIXME: This does not allow for string escaping yet
@@ -450,8 +671,40 @@ mod tests {
             frag_first!(Token::char_constant(span!("'9'"))),
             Ok(("", '9'))
         );
+    }
+
+    #[test]
+    fn t_char_constant_escapes() {
+        let escapes = [
+            ("'\\n'", '\n'),
+            ("'\\r'", '\r'),
+            ("'\\t'", '\t'),
+            ("'\\0'", '\0'),
+            ("'\\\\'", '\\'),
+            ("'\\''", '\''),
+            ("'\\\"'", '"'),
+            ("'\\u{1F600}'", '\u{1F600}'),
+            ("'\\u{e9}'", '\u{e9}'),
+        ];
+
+        for (input, expected) in escapes {
+            let input = nom_locate::LocatedSpan::new_extra(input, crate::location::Source::Empty);
+            assert_eq!(frag_first!(Token::char_constant(input)), Ok(("", expected)));
+        }
+    }
+
+    #[test]
+    fn t_char_constant_invalid_escapes() {
+        let invalid = |input| match Token::char_constant(input) {
+            Err(nom::Err::Failure(e)) => e.kind() == ErrKind::Parsing,
+            _ => false,
+        };
 
-        // FIXME: Add escaping
+        assert!(invalid(span!("'\\q'")));
+        assert!(invalid(span!("'\\u{110000}'")));
+        assert!(invalid(span!("'\\u{D800}'")));
+        assert!(invalid(span!("'\\u{1234567}'")));
+        assert!(invalid(span!("'\\u1F600'")));
     }
 
     #[test]
@@ -498,11 +751,103 @@ mod tests {
         );
     }
 
+    #[test]
+    fn t_int_constant_min() {
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("-9223372036854775808"))),
+            Ok(("", i64::MIN))
+        );
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("-0x8000_0000_0000_0000"))),
+            Ok(("", i64::MIN))
+        );
+        assert!(Token::int_constant(span!("9223372036854775808")).is_err());
+    }
+
     #[test]
     fn t_int_constant_invalid() {
         assert!(Token::int_constant(span!("ff2")).is_err());
     }
 
+    #[test]
+    fn t_int_constant_separators() {
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("1_000_000"))),
+            Ok(("", 1_000_000))
+        );
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("-4_5"))),
+            Ok(("", -45))
+        );
+    }
+
+    #[test]
+    fn t_int_constant_invalid_separators() {
+        let invalid = |input| match Token::int_constant(input) {
+            Err(nom::Err::Error(e) | nom::Err::Failure(e)) => e.kind() == ErrKind::Parsing,
+            _ => false,
+        };
+
+        assert!(invalid(span!("_1")));
+        assert!(invalid(span!("1_")));
+        assert!(invalid(span!("1__0")));
+    }
+
+    #[test]
+    fn t_int_constant_radix() {
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("0xFF"))),
+            Ok(("", 255))
+        );
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("0o755"))),
+            Ok(("", 0o755))
+        );
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("0b1010"))),
+            Ok(("", 10))
+        );
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("-0x10"))),
+            Ok(("", -16))
+        );
+    }
+
+    #[test]
+    fn t_int_constant_radix_case() {
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("0XaB"))),
+            Ok(("", 0xab))
+        );
+        assert_eq!(frag_first!(Token::int_constant(span!("0B11"))), Ok(("", 3)));
+    }
+
+    #[test]
+    fn t_int_constant_radix_separators() {
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("0xFF_FF"))),
+            Ok(("", 0xFFFF))
+        );
+        assert_eq!(
+            frag_first!(Token::int_constant(span!("0b1111_0000"))),
+            Ok(("", 0xF0))
+        );
+    }
+
+    #[test]
+    fn t_int_constant_radix_invalid() {
+        let invalid = |input| match Token::int_constant(input) {
+            Err(nom::Err::Failure(e)) => e.kind() == ErrKind::Parsing,
+            _ => false,
+        };
+
+        assert!(invalid(span!("0x")));
+        assert!(invalid(span!("0b102")));
+        assert!(invalid(span!("0o8")));
+        assert!(invalid(span!("0x8000_0000_0000_0000")));
+        assert!(invalid(span!("99999999999999999999")));
+    }
+
     #[test]
     fn t_float_constant_valid() {
         assert_eq!(
@@ -522,6 +867,63 @@ mod tests {
         assert!(Token::float_constant(span!("12")).is_err());
     }
 
+    #[test]
+    fn t_float_constant_exponent() {
+        assert_eq!(
+            frag_first!(Token::float_constant(span!("1e10"))),
+            Ok(("", 1e10f64))
+        );
+        assert_eq!(
+            frag_first!(Token::float_constant(span!("2.5e-3"))),
+            Ok(("", 2.5e-3f64))
+        );
+        assert_eq!(
+            frag_first!(Token::float_constant(span!("1E6"))),
+            Ok(("", 1e6f64))
+        );
+        assert_eq!(
+            frag_first!(Token::float_constant(span!("-4.5e+2"))),
+            Ok(("", -450f64))
+        );
+    }
+
+    #[test]
+    fn t_float_constant_separators() {
+        assert_eq!(
+            frag_first!(Token::float_constant(span!("1.234_567"))),
+            Ok(("", 1.234_567f64))
+        );
+        assert_eq!(
+            frag_first!(Token::float_constant(span!("1_000.5"))),
+            Ok(("", 1_000.5f64))
+        );
+    }
+
+    #[test]
+    fn t_float_constant_invalid_separators() {
+        let invalid = |input| match Token::float_constant(input) {
+            Err(nom::Err::Failure(e)) => e.kind() == ErrKind::Parsing,
+            _ => false,
+        };
+
+        assert!(invalid(span!("1_.5")));
+        assert!(invalid(span!("1.5_")));
+        assert!(invalid(span!("1.5__0")));
+        assert!(Token::float_constant(span!("1._5")).is_err());
+    }
+
+    #[test]
+    fn t_float_constant_invalid_exponent() {
+        let missing_exponent = |input| match Token::float_constant(input) {
+            Err(nom::Err::Failure(e)) => e.kind() == ErrKind::Parsing,
+            _ => false,
+        };
+
+        assert!(missing_exponent(span!("1e")));
+        assert!(missing_exponent(span!("1e+")));
+        assert!(missing_exponent(span!("2.5E-")));
+    }
+
     #[test]
     fn t_id() {
         assert_eq!(
@@ -5,30 +5,57 @@
 mod type_id;
 pub use type_id::{TypeId, PRIMITIVE_TYPES};
 
-use crate::context::ScopeMap;
+use crate::builtins;
+use crate::context::{IncludeCtx, ScopeMap};
 use crate::error::{ErrKind, Error, ErrorHandler};
-use crate::instruction::{FunctionDec, Instruction, TypeDec};
+use crate::generics;
+use crate::instance::ObjectInstance;
+use crate::instruction::{DecArg, FunctionDec, FunctionKind, Instruction, TypeDec};
 use crate::io_trait::JkReader;
+use crate::location::{SourceOwned, SpanTuple};
 
 use colored::Colorize;
+use symbol::Symbol;
 
 use std::{
-    collections::HashSet,
+    cell::{RefCell, RefMut},
+    collections::HashMap,
     fmt::{Display, Formatter, Result as FmtResult},
-    path::{Path, PathBuf},
+    path::Path,
+    rc::Rc,
 };
 
 /// The [`CheckedType`] enum contains three possible states about the type. Either the
 /// type has been properly resolved to something, or it corresponds to a Void type. If the
 /// type has not been resolved yet, it can be unknown.
-#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// Checked types are ordered so that diagnostics can be sorted deterministically:
+/// `Later` < `Error` < `Void` < `Resolved`, with resolved types ordered by their
+/// [`TypeId`]. The order of the variants must be kept in sync with this.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
 pub enum CheckedType {
-    Resolved(TypeId),
-    // Should we remove this for Resolved(TypeId::void())?
-    Void,
     Later,
     #[default]
     Error,
+    // Should we remove this for Resolved(TypeId::void())?
+    Void,
+    Resolved(TypeId),
+}
+
+impl CheckedType {
+    /// Can a value of type `self` be used where a value of type `other` is expected?
+    /// This is the single place in which type compatibility is decided, and is where
+    /// coercions should be added
+    pub fn is_assignable_to(&self, other: &CheckedType) -> bool {
+        self == other
+    }
+
+    /// Name of the type if it is resolved, and `None` otherwise
+    pub fn resolved_id(&self) -> Option<&str> {
+        match self {
+            CheckedType::Resolved(ty) => Some(ty.id()),
+            CheckedType::Void | CheckedType::Error | CheckedType::Later => None,
+        }
+    }
 }
 
 impl Display for CheckedType {
@@ -50,6 +77,13 @@ pub enum SpecializedNode {
     Type(TypeDec),
 }
 
+/// Kind of a declaration tracked until it gets used
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Declaration {
+    Variable,
+    Function,
+}
+
 // TODO: Should we factor this into a `Context` trait? All contexts will share some
 // similarities, such as the ability to emit errors or enter and exit scopes
 /// The [`TypeCtx`]'s role is to keep track of declared types based on the scope they
@@ -70,30 +104,93 @@ pub struct TypeCtx {
     /// as we go and then use them in the calling context
     // FIXME: Turn this into a hashset?
     generated: Vec<SpecializedNode>,
-    // FIXME: Remove both of these fields...
-    /// Path from which the typechecking context was instantiated
-    path: Option<PathBuf>,
-    included: HashSet<PathBuf>,
+    /// In strict mode, values which are not used are rejected
+    strict: bool,
+    /// Return types of the functions currently being typechecked. The last element
+    /// corresponds to the innermost function
+    return_types: Vec<CheckedType>,
+    /// Assignments to mutable variables whose value has not been read yet, for each
+    /// block currently being typechecked. The last element corresponds to the
+    /// innermost block
+    stores: Vec<HashMap<String, Option<SpanTuple>>>,
+    /// Variables and functions declared in each block currently being typechecked
+    /// which have not been used yet, in declaration order. The last element
+    /// corresponds to the innermost block
+    unused: Vec<Vec<(Declaration, String, Option<SpanTuple>)>>,
+    /// Functions declared in the outermost block which were never called. They are
+    /// kept until [`TypeCtx::report_unused_functions`] is called, as they might
+    /// come from a file whose functions are meant to be used by other files
+    unused_functions: Vec<(String, Option<SpanTuple>)>,
+    /// Source files included so far. This is shared with the [`Context`] owning the
+    /// type context
+    ///
+    /// [`Context`]: crate::Context
+    includes: Rc<RefCell<IncludeCtx>>,
     /// A reader, able to read files from, whatever the platform
     reader: Box<dyn JkReader>,
+    /// Output produced while typechecking, such as by `@types`. It is written to the
+    /// stdout sink of the [`Context`] owning the type context once typechecking is
+    /// done
+    ///
+    /// [`Context`]: crate::Context
+    output: String,
 }
 
 impl TypeCtx {
     /// Create a new empty [`TypeCtx`]
     pub fn new(reader: Box<dyn JkReader>) -> TypeCtx {
-        // FIXME: This doesn't contain builtins
         let mut ctx = TypeCtx {
             error_handler: ErrorHandler::default(),
             types: ScopeMap::new(),
             generated: vec![],
-            path: None,
-            included: HashSet::new(),
+            strict: false,
+            return_types: vec![],
+            stores: vec![],
+            unused: vec![],
+            unused_functions: vec![],
+            includes: Rc::new(RefCell::new(IncludeCtx::default())),
             reader,
+            output: String::new(),
         };
 
+        // Builtins live in their own outermost scope, so that declaring them again,
+        // as the standard library does, only shadows them
+        ctx.scope_enter();
+        ctx.register_builtins();
+        ctx.scope_enter();
+        ctx.declare_primitives();
+
+        ctx
+    }
+
+    /// Declare the signatures of the builtin functions, so that calls to them can be
+    /// typechecked without requiring a declaration
+    fn register_builtins(&mut self) {
+        for builtin in builtins::BUILTINS {
+            let generics = builtin.generics.iter().map(|g| TypeId::from(*g)).collect();
+            let args = builtin
+                .args
+                .iter()
+                .map(|(name, ty)| DecArg::new(String::from(*name), TypeId::from(*ty)))
+                .collect();
+
+            let mut dec = FunctionDec::new(
+                String::from(builtin.name),
+                builtin.return_ty.map(TypeId::from),
+                generics,
+                args,
+            );
+            dec.set_kind(FunctionKind::Ext);
+
+            self.declare_function(String::from(builtin.name), dec)
+                .unwrap();
+        }
+    }
+
+    fn declare_primitives(&mut self) {
         macro_rules! declare_primitive {
             ($ty_name:ident) => {
-                ctx.declare_custom_type(
+                self.declare_custom_type(
                     String::from(stringify!($ty_name)),
                     TypeDec::new(stringify!($ty_name).to_string(), vec![], vec![]),
                 )
@@ -101,32 +198,62 @@ impl TypeCtx {
             };
         }
 
-        ctx.scope_enter();
-
         declare_primitive!(bool);
         declare_primitive!(int);
         declare_primitive!(float);
         declare_primitive!(char);
         declare_primitive!(string);
+    }
 
-        ctx
+    /// Remove all declarations and errors from the type context, apart from the
+    /// primitive types. The configuration of the type context is kept
+    pub fn reset(&mut self) {
+        self.types.clear();
+        self.register_builtins();
+        self.scope_enter();
+        self.declare_primitives();
+
+        self.error_handler.clear();
+        self.generated.clear();
+        self.return_types.clear();
+        self.stores.clear();
+        self.unused.clear();
+        self.unused_functions.clear();
+        self.includes.borrow_mut().clear();
+    }
+
+    /// Remove all errors and warnings from the type context, as well as the state kept
+    /// while typechecking functions and blocks, which a failed typecheck might not have
+    /// unwound. Declarations are kept
+    pub fn reset_errors(&mut self) {
+        self.error_handler.clear();
+        self.return_types.clear();
+        self.stores.clear();
     }
 
-    // FIXME: Remove these three functions
-    pub fn set_path(&mut self, path: Option<PathBuf>) {
-        self.path = path
+    /// Get a reference to the scopes containing the declarations known to the type
+    /// context
+    pub(crate) fn scopes(&self) -> &ScopeMap<CheckedType, FunctionDec, TypeDec> {
+        &self.types
     }
 
-    pub fn path(&self) -> Option<&PathBuf> {
-        self.path.as_ref()
+    /// Replace the scopes containing the declarations known to the type context
+    pub(crate) fn set_scopes(&mut self, types: ScopeMap<CheckedType, FunctionDec, TypeDec>) {
+        self.types = types
     }
 
-    pub fn include(&mut self, path: PathBuf) {
-        self.included.insert(path);
+    /// Access the source files included so far, in order to resolve and track
+    /// includes
+    pub fn includes(&self) -> RefMut<'_, IncludeCtx> {
+        self.includes.borrow_mut()
     }
 
-    pub fn is_included(&self, path: &Path) -> bool {
-        self.included.contains(path)
+    /// Share the include tracking of another context, such as the [`Context`] owning
+    /// the type context
+    ///
+    /// [`Context`]: crate::Context
+    pub fn set_includes(&mut self, includes: Rc<RefCell<IncludeCtx>>) {
+        self.includes = includes
     }
 
     pub fn reader(&self) -> &dyn JkReader {
@@ -143,16 +270,201 @@ impl TypeCtx {
         self.types.scope_exit()
     }
 
+    /// Emit a warning for each function declared in the outermost block from the
+    /// file at `path` which was never called. Functions of other files, such as the
+    /// standard library, are meant to be used by the files including them
+    pub fn report_unused_functions(&mut self, path: &Path) {
+        std::mem::take(&mut self.unused_functions)
+            .into_iter()
+            .filter(|(_, location)| {
+                matches!(location.as_ref().map(SpanTuple::source), Some(SourceOwned::Path(p)) if p == path)
+            })
+            .for_each(|(name, location)| {
+                self.error_handler.warn(
+                    Error::new(ErrKind::TypeChecker)
+                        .with_msg(format!("unused function: `{name}`"))
+                        .with_loc(location),
+                )
+            });
+    }
+
+    /// Enable or disable strict mode, in which unused values are rejected
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict
+    }
+
+    /// Is the type context in strict mode
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Enter a function's body, whose declared return type is `return_ty`
+    pub fn enter_function(&mut self, return_ty: CheckedType) {
+        self.return_types.push(return_ty)
+    }
+
+    /// Exit the body of the function that was entered last
+    pub fn exit_function(&mut self) {
+        self.return_types.pop();
+    }
+
+    /// Return type of the function currently being typechecked, if any
+    pub fn current_return_type(&self) -> Option<&CheckedType> {
+        self.return_types.last()
+    }
+
+    /// Enter a block, in which assignments and declarations are tracked separately
+    /// from the enclosing blocks
+    pub fn enter_block(&mut self) {
+        // A new evaluation starts: The functions it declares are the only ones which
+        // can be reported
+        if self.unused.is_empty() {
+            self.unused_functions.clear();
+        }
+
+        self.stores.push(HashMap::new());
+        self.unused.push(vec![]);
+    }
+
+    /// Exit the block that was entered last. A warning is emitted for each variable
+    /// declared in the block which was never read. Variables of the outermost block
+    /// are global and might be read by later evaluations, such as the next lines of
+    /// a REPL, so they are never reported. Its unused functions are kept for
+    /// [`TypeCtx::report_unused_functions`]
+    pub fn exit_block(&mut self) {
+        self.stores.pop();
+
+        let unused = self.unused.pop().unwrap_or_default();
+        let outermost = self.unused.is_empty();
+
+        unused
+            .into_iter()
+            .for_each(|(declaration, name, location)| match declaration {
+                Declaration::Variable if !outermost => self.error_handler.warn(
+                    Error::new(ErrKind::TypeChecker)
+                        .with_msg(format!("unused variable: `{name}`"))
+                        .with_loc(location),
+                ),
+                Declaration::Function if outermost => self.unused_functions.push((name, location)),
+                _ => {}
+            });
+    }
+
+    /// Record the declaration of a variable in the current block. If the variable
+    /// is never read before the end of the block, a warning is emitted. Variables
+    /// whose name starts with an underscore are never reported
+    pub fn record_declaration(&mut self, name: &str, location: Option<SpanTuple>) {
+        self.track(Declaration::Variable, name, location)
+    }
+
+    /// Keep track of a declaration in the current block until it gets used
+    fn track(&mut self, declaration: Declaration, name: &str, location: Option<SpanTuple>) {
+        if name.starts_with('_') {
+            return;
+        }
+
+        if let Some(unused) = self.unused.last_mut() {
+            unused.push((declaration, name.to_string(), location))
+        }
+    }
+
+    /// Mark the declarations named `name` as used
+    fn record_use(&mut self, declaration: Declaration, name: &str) {
+        self.unused.iter_mut().for_each(|unused| {
+            unused.retain(|(kind, declared, _)| *kind != declaration || declared != name)
+        });
+
+        if declaration == Declaration::Function {
+            self.unused_functions
+                .retain(|(declared, _)| declared != name);
+        }
+    }
+
+    /// Record an assignment to a mutable variable. If a previous assignment to the
+    /// same variable in the current block was never read, it is a dead store and
+    /// a warning is emitted
+    pub fn record_store(&mut self, name: &str, location: Option<SpanTuple>) {
+        let dead_store = match self.stores.last_mut() {
+            Some(stores) => stores.insert(name.to_string(), location.clone()),
+            None => return,
+        };
+
+        if let Some(dead_store) = dead_store {
+            self.error_handler.warn(
+                Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!(
+                        "value assigned to `{name}` is never read before being overwritten"
+                    ))
+                    .with_loc(dead_store)
+                    .with_hint(
+                        Error::hint()
+                            .with_msg(String::from("value is overwritten here"))
+                            .with_loc(location),
+                    ),
+            );
+        }
+    }
+
+    /// Record a read of a variable, which makes its latest assignment live
+    pub fn record_read(&mut self, name: &str) {
+        self.stores.iter_mut().for_each(|stores| {
+            stores.remove(name);
+        });
+        self.record_use(Declaration::Variable, name);
+    }
+
+    /// Consider all assignments as live. This is used when calling functions, as they
+    /// might read any variable
+    pub fn record_read_all(&mut self) {
+        self.stores.iter_mut().for_each(HashMap::clear)
+    }
+
     /// Declare a newly-created variable's type
     pub fn declare_var(&mut self, name: String, ty: CheckedType) -> Result<(), Error> {
         // FIXME: Add hint here too
-        self.types.add_variable(name, ty)
+        self.types.add_variable(self.intern(&name), ty)
+    }
+
+    /// Has a variable named `name` been declared in the current scope already
+    pub(crate) fn is_declared_in_scope(&self, name: &str) -> bool {
+        self.types
+            .scopes()
+            .front()
+            .is_some_and(|scope| scope.variables.contains_key(&self.intern(name)))
+    }
+
+    /// Declare a constant and its value, such as constants defined by the host
+    /// program or declared with `const`. Constants cannot be assigned to after their
+    /// declaration, and their value is known before executing the program
+    pub fn declare_constant(&mut self, name: String, value: ObjectInstance) -> Result<(), Error> {
+        self.declare_var(name.clone(), value.ty().clone())?;
+        self.types.add_constant(self.intern(&name), value)
+    }
+
+    /// Is the given variable a constant or not. A variable shadowing a constant is
+    /// not a constant
+    pub fn is_constant(&self, name: &str) -> bool {
+        self.constant_value(name).is_some()
+    }
+
+    /// Value of the given constant, if the innermost declaration of `name` is one
+    pub fn constant_value(&self, name: &str) -> Option<&ObjectInstance> {
+        self.types.get_constant(&self.intern(name))
     }
 
     /// Declare a newly-created function
     pub fn declare_function(&mut self, name: String, function: FunctionDec) -> Result<(), Error> {
         // FIXME: Remove clones here
         let loc = function.location().cloned();
+        // Specialized functions are only ever generated by a call to them
+        let tracked = function.fn_kind() == FunctionKind::Func
+            && !name.starts_with('_')
+            && generics::demangle(&name) == name;
+        if tracked {
+            self.track(Declaration::Function, &name, loc.clone());
+        }
+
+        let name = self.intern(&name);
         match self.types.add_function(name.clone(), function) {
             Ok(_) => Ok(()),
             Err(err) => {
@@ -166,6 +478,14 @@ impl TypeCtx {
         }
     }
 
+    /// Replace a previously declared function, such as a hoisted function whose
+    /// signature has been resolved since
+    pub fn update_function(&mut self, function: FunctionDec) {
+        if let Some(previous) = self.types.get_function_mut(&self.intern(function.name())) {
+            *previous = function;
+        }
+    }
+
     /// Declare a newly-created generic function
     pub fn declare_generic_function(
         &mut self,
@@ -174,6 +494,7 @@ impl TypeCtx {
     ) -> Result<(), Error> {
         // FIXME: Remove clones here
         let loc = function.location().cloned();
+        let name = self.intern(&name);
         match self.types.add_function(name.clone(), function) {
             Ok(_) => Ok(()),
             Err(err) => {
@@ -190,7 +511,12 @@ impl TypeCtx {
     /// Add a new generated node to the context
     pub fn add_specialized_node(&mut self, mut node: SpecializedNode) -> Result<(), Error> {
         match &mut node {
-            SpecializedNode::Func(f) => f.type_of(self)?,
+            SpecializedNode::Func(f) => {
+                let ty = f.type_of(self)?;
+                // Specialized functions are only generated when they are called
+                self.record_use(Declaration::Function, f.name());
+                ty
+            }
             SpecializedNode::Type(t) => t.type_of(self)?,
         };
 
@@ -230,24 +556,102 @@ impl TypeCtx {
         // FIXME: Add hint here too
     }
 
+    /// Intern a name, returning a [`Symbol`] which is cheap to clone and compare.
+    /// Interning the same name multiple times always yields the same symbol. Names
+    /// are interned in the process-global interner of the `symbol` crate, so symbols
+    /// can be shared between contexts
+    pub fn intern(&self, name: &str) -> Symbol {
+        Symbol::from(name)
+    }
+
     /// Access a previously declared variable's type
     pub fn get_var(&mut self, name: &str) -> Option<&CheckedType> {
-        self.types.get_variable(name)
+        self.types.get_variable(&self.intern(name))
     }
 
     /// Access a previously declared function
     pub fn get_function(&mut self, name: &str) -> Option<&FunctionDec> {
-        self.types.get_function(name)
+        self.record_use(Declaration::Function, name);
+        self.types.get_function(&self.intern(name))
     }
 
     /// Access a previously declared generic function
     pub fn get_generic_function(&mut self, name: &str) -> Option<&FunctionDec> {
-        self.types.get_generic_function(name)
+        self.record_use(Declaration::Function, name);
+        self.types.get_generic_function(&self.intern(name))
     }
 
-    /// Access a previously declared custom type
+    /// Access a previously declared custom type. Aliases are followed, so that
+    /// accessing an alias gives access to the type it refers to
     pub fn get_custom_type(&mut self, name: &str) -> Option<&TypeDec> {
-        self.types.get_type(name)
+        match self.types.get_alias(name) {
+            Some(target) => {
+                let target = target.id().to_string();
+                self.types.get_type(&target)
+            }
+            None => self.types.get_type(name),
+        }
+    }
+
+    /// Declare a new alias for an existing type, such as `type Meters = int`. The
+    /// alias and its target can then be used interchangeably
+    pub fn declare_type_alias(&mut self, name: String, target: CheckedType) -> Result<(), Error> {
+        let target = match target {
+            CheckedType::Resolved(ty) => self.resolve_alias(&ty),
+            other => {
+                return Err(Error::new(ErrKind::TypeChecker)
+                    .with_msg(format!("cannot declare an alias to `{other}`")))
+            }
+        };
+
+        if PRIMITIVE_TYPES.contains(&name.as_str()) {
+            return Err(Error::new(ErrKind::TypeChecker).with_msg(format!(
+                "cannot declare alias `{name}`: it would shadow the primitive type `{name}`"
+            )));
+        }
+
+        if self.types.get_type(&name).is_some() {
+            return Err(Error::new(ErrKind::TypeChecker).with_msg(format!(
+                "cannot declare alias `{name}`: a type with the same name already exists"
+            )));
+        }
+
+        if self.get_custom_type(target.id()).is_none() {
+            return Err(Error::new(ErrKind::TypeChecker).with_msg(format!(
+                "cannot declare alias `{name}` to undeclared type `{target}`"
+            )));
+        }
+
+        self.types.add_alias(name.clone(), target).map_err(|_| {
+            Error::new(ErrKind::TypeChecker)
+                .with_msg(format!("alias `{name}` is already declared in this scope"))
+        })
+    }
+
+    /// Replace all the aliases contained in a [`TypeId`] with the types they refer to.
+    /// Types which are not aliases are returned as is
+    pub fn resolve_alias(&self, ty: &TypeId) -> TypeId {
+        match ty {
+            TypeId::Type { id, path, generics } => match self.types.get_alias(ty.id()) {
+                Some(target) if generics.is_empty() => target.clone(),
+                _ => TypeId::Type {
+                    id: id.clone(),
+                    path: path.clone(),
+                    generics: generics.iter().map(|g| self.resolve_alias(g)).collect(),
+                },
+            },
+            TypeId::Functor {
+                generics,
+                arg_types,
+                return_type,
+            } => TypeId::Functor {
+                generics: generics.iter().map(|g| self.resolve_alias(g)).collect(),
+                arg_types: arg_types.iter().map(|a| self.resolve_alias(a)).collect(),
+                return_type: return_type
+                    .as_ref()
+                    .map(|r| Box::new(self.resolve_alias(r))),
+            },
+        }
     }
 
     /// Access a previously declared generic custom type
@@ -259,6 +663,51 @@ impl TypeCtx {
     pub fn error(&mut self, err: Error) {
         self.error_handler.add(err)
     }
+
+    /// Add a line to the output produced while typechecking
+    pub(crate) fn output_line(&mut self, line: &str) {
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    /// Take the output produced while typechecking so far
+    pub(crate) fn take_output(&mut self) -> String {
+        std::mem::take(&mut self.output)
+    }
+
+    /// Pretty-print the types of all the variables, functions and custom types
+    /// currently visible, starting from the innermost scope
+    pub fn print(&self) -> String {
+        let mut s = String::new();
+
+        for scope in self.types.scopes() {
+            let mut types: Vec<&String> = scope.types.keys().collect();
+            types.sort();
+            types
+                .into_iter()
+                .for_each(|name| s.push_str(&format!("type {name}\n")));
+
+            let mut variables: Vec<(&Symbol, &CheckedType)> = scope.variables.iter().collect();
+            variables.sort_by_key(|(name, _)| *name);
+            variables
+                .into_iter()
+                .for_each(|(name, ty)| s.push_str(&format!("{name}: {ty}\n")));
+
+            let mut functions: Vec<&FunctionDec> = scope.functions.values().collect();
+            functions.sort_by_key(|f| f.name());
+            functions.into_iter().for_each(|f| {
+                let args: Vec<String> = f.args().iter().map(|arg| arg.to_string()).collect();
+                let ret = match f.ty() {
+                    Some(ty) => format!(" -> {ty}"),
+                    None => String::new(),
+                };
+
+                s.push_str(&format!("func {}({}){ret}\n", f.name(), args.join(", ")));
+            });
+        }
+
+        s
+    }
 }
 
 /// The [`TypeCheck`] trait allows an [`Instruction`] to see its type resolved statically.
@@ -313,3 +762,190 @@ pub trait TypeCheck {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_checked_type_ordering() {
+        let mut types = vec![
+            CheckedType::Resolved(TypeId::from("string")),
+            CheckedType::Void,
+            CheckedType::Resolved(TypeId::from("bool")),
+            CheckedType::Error,
+            CheckedType::Later,
+            CheckedType::Resolved(TypeId::from("int")),
+        ];
+
+        types.sort();
+
+        assert_eq!(
+            types,
+            vec![
+                CheckedType::Later,
+                CheckedType::Error,
+                CheckedType::Void,
+                CheckedType::Resolved(TypeId::from("bool")),
+                CheckedType::Resolved(TypeId::from("int")),
+                CheckedType::Resolved(TypeId::from("string")),
+            ]
+        );
+    }
+
+    #[test]
+    fn t_resolved_id_resolved() {
+        let ty = CheckedType::Resolved(TypeId::from("int"));
+
+        assert_eq!(ty.resolved_id(), Some("int"));
+    }
+
+    #[test]
+    fn t_resolved_id_unresolved() {
+        assert_eq!(CheckedType::Void.resolved_id(), None);
+        assert_eq!(CheckedType::Error.resolved_id(), None);
+        assert_eq!(CheckedType::Later.resolved_id(), None);
+    }
+
+    #[test]
+    fn t_assignable_resolved() {
+        let int = CheckedType::Resolved(TypeId::from("int"));
+        let float = CheckedType::Resolved(TypeId::from("float"));
+
+        assert!(int.is_assignable_to(&CheckedType::Resolved(TypeId::from("int"))));
+        assert!(!int.is_assignable_to(&float));
+        assert!(!float.is_assignable_to(&int));
+    }
+
+    #[test]
+    fn t_assignable_void() {
+        let int = CheckedType::Resolved(TypeId::from("int"));
+
+        assert!(CheckedType::Void.is_assignable_to(&CheckedType::Void));
+        assert!(!CheckedType::Void.is_assignable_to(&int));
+        assert!(!int.is_assignable_to(&CheckedType::Void));
+    }
+
+    #[test]
+    fn t_assignable_unknown() {
+        let int = CheckedType::Resolved(TypeId::from("int"));
+
+        assert!(CheckedType::Error.is_assignable_to(&CheckedType::Error));
+        assert!(!CheckedType::Error.is_assignable_to(&int));
+        assert!(!CheckedType::Error.is_assignable_to(&CheckedType::Void));
+        assert!(!int.is_assignable_to(&CheckedType::Error));
+    }
+
+    #[test]
+    fn t_nested_function_return_types() {
+        use crate::io_trait::JkStdReader;
+
+        let int = CheckedType::Resolved(TypeId::from("int"));
+        let bool = CheckedType::Resolved(TypeId::from("bool"));
+        let mut ctx = TypeCtx::new(Box::new(JkStdReader));
+
+        assert_eq!(ctx.current_return_type(), None);
+
+        ctx.enter_function(int.clone());
+        assert_eq!(ctx.current_return_type(), Some(&int));
+
+        ctx.enter_function(bool.clone());
+        assert_eq!(ctx.current_return_type(), Some(&bool));
+        ctx.exit_function();
+
+        assert_eq!(ctx.current_return_type(), Some(&int));
+        ctx.exit_function();
+
+        assert_eq!(ctx.current_return_type(), None);
+    }
+
+    #[test]
+    fn t_builtin_call_typechecks_without_declaration() {
+        use crate::instruction::FunctionCall;
+        use crate::io_trait::JkStdReader;
+        use crate::value::JkString;
+
+        let mut ctx = TypeCtx::new(Box::new(JkStdReader));
+        let mut call = FunctionCall::new(
+            String::from("__builtin_string_len"),
+            vec![],
+            vec![Box::new(JkString::from("jinko"))],
+        );
+
+        assert_eq!(
+            call.type_of(&mut ctx).unwrap(),
+            CheckedType::Resolved(TypeId::from("int"))
+        );
+        assert!(!ctx.error_handler.has_errors());
+
+        assert!(crate::typecheck(r#"__builtin_string_concat("a", 'b')"#).is_err());
+    }
+
+    #[test]
+    fn t_builtins_survive_scopes() {
+        use crate::io_trait::JkStdReader;
+
+        let mut ctx = TypeCtx::new(Box::new(JkStdReader));
+
+        ctx.scope_enter();
+        assert!(ctx.get_function("__builtin_exit").is_some());
+        ctx.scope_exit();
+        assert!(ctx.get_function("__builtin_exit").is_some());
+
+        ctx.reset();
+        assert!(ctx.get_function("__builtin_exit").is_some());
+    }
+
+    #[test]
+    fn t_builtins_can_be_redeclared() {
+        assert!(crate::typecheck("ext func __builtin_exit(exit_code: int);").is_ok());
+    }
+
+    #[test]
+    fn t_builtin_call_executes_without_declaration() {
+        use crate::instance::ToObjectInstance;
+        use crate::value::JkString;
+
+        // The standard library is not included: Nothing declares the builtin
+        let mut ctx = crate::Context::new(Box::new(crate::io_trait::JkStdReader));
+        let input = r#"concatenated = __builtin_string_concat("jin", "ko");"#;
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+
+        assert!(ctx.execute().is_ok());
+
+        assert_eq!(
+            ctx.get_variable(&"concatenated".into()).unwrap().instance(),
+            JkString::from("jinko").to_instance()
+        );
+    }
+
+    #[test]
+    fn t_builtins_match_interpreter() {
+        use crate::io_trait::JkStdReader;
+
+        let mut ctx = TypeCtx::new(Box::new(JkStdReader));
+
+        for name in ["size_of", "type_of", "print", "println", "__builtin_exit"] {
+            assert!(crate::Builtins::new().contains(name));
+            assert!(ctx.get_function(name).is_some());
+        }
+    }
+
+    #[test]
+    fn t_generic_builtins_without_prelude() {
+        use crate::instance::ToObjectInstance;
+        use crate::value::{JkInt, JkString};
+
+        let mut ctx = crate::Context::without_prelude(Box::new(crate::io_trait::JkStdReader));
+
+        assert_eq!(
+            ctx.eval("size_of[int](15)").unwrap(),
+            Some(JkInt::from(8).to_instance())
+        );
+        assert_eq!(
+            ctx.eval("type_of[char]('c')").unwrap(),
+            Some(JkString::from("char").to_instance())
+        );
+        assert!(ctx.eval("print(15)").is_ok());
+    }
+}
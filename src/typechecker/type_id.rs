@@ -21,6 +21,16 @@
 //! let t = t.with_arg(TypeId::new(Symbol::from(String::from("string"))));    // Functor
 //! let t = t.with_return_type(TypeId::new(Symbol::from(String::from("T")))); // Functor
 //! ```
+//! Type names can be qualified by the namespaces they live in, such as `math::Vector`.
+//! The identifier of such a type is its full, qualified name, and its path holds the
+//! namespaces leading to it.
+//! ```
+//! use jinko::typechecker::TypeId;
+//!
+//! let t = TypeId::from("math::Vector");
+//! assert_eq!(t.id(), "math::Vector");
+//! assert_eq!(t.name(), "Vector");
+//! ```
 
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::hash::Hash;
@@ -32,11 +42,20 @@ use crate::symbol::Symbol;
 
 pub const PRIMITIVE_TYPES: [&str; 5] = ["bool", "int", "float", "char", "string"];
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Separator between the segments of a qualified type name
+pub const PATH_SEPARATOR: &str = "::";
+
+/// Types are ordered by their identifier, then by their generics. Function-like types
+/// are ordered after all other types
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 // FIXME: They should probably have location info
 pub enum TypeId {
     Type {
+        /// Full name of the type, including the namespaces it lives in
         id: Symbol,
+        /// Namespaces leading to the type, from the outermost to the innermost one.
+        /// This is empty for unqualified names such as `int`
+        path: Vec<Symbol>,
         generics: Vec<TypeId>,
     },
     Functor {
@@ -48,14 +67,26 @@ pub enum TypeId {
 
 impl TypeId {
     // Create a new, empty [`TypeId`] simply from a symbol. The type id will
-    // always represent a [`TypeId::Type`] at first
+    // always represent a [`TypeId::Type`] at first. Qualified names, such as
+    // `math::Vector`, are split into their namespaces
     pub fn new(id: Symbol) -> TypeId {
+        let path = TypeId::parse_path(id.access());
+
         TypeId::Type {
             id,
+            path,
             generics: vec![],
         }
     }
 
+    /// Split a qualified name into the namespaces leading to it
+    fn parse_path(id: &str) -> Vec<Symbol> {
+        match id.rsplit_once(PATH_SEPARATOR) {
+            Some((path, _)) => path.split(PATH_SEPARATOR).map(Symbol::from).collect(),
+            None => vec![],
+        }
+    }
+
     // Create a new empty function-like [`TypeId`] from a symbol
     pub fn functor() -> TypeId {
         TypeId::Functor {
@@ -68,12 +99,13 @@ impl TypeId {
     /// Add a generic type to a consumed [`TypeId`]'s generic list
     pub fn with_generic(self, generic: TypeId) -> TypeId {
         match self {
-            TypeId::Type { id, generics } => {
+            TypeId::Type { id, path, generics } => {
                 let mut new_generics = generics;
                 new_generics.push(generic);
 
                 TypeId::Type {
                     id,
+                    path,
                     generics: new_generics,
                 }
             }
@@ -143,6 +175,7 @@ impl TypeId {
         }
     }
 
+    /// Full name of the type, including the namespaces it lives in
     pub fn id(&self) -> &str {
         match self {
             TypeId::Type { id, .. } => id.access(),
@@ -151,6 +184,21 @@ impl TypeId {
         }
     }
 
+    /// Name of the type, without the namespaces it lives in
+    pub fn name(&self) -> &str {
+        let id = self.id();
+
+        id.rsplit_once(PATH_SEPARATOR).map_or(id, |(_, name)| name)
+    }
+
+    /// Namespaces leading to the type, from the outermost to the innermost one
+    pub fn path(&self) -> &[Symbol] {
+        match self {
+            TypeId::Type { path, .. } => path,
+            TypeId::Functor { .. } => &[],
+        }
+    }
+
     pub fn void() -> TypeId {
         TypeId::new(Symbol::from(String::from("void")))
     }
@@ -205,3 +253,37 @@ impl Display for TypeId {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualified_name() {
+        let ty = TypeId::from("a::b::C");
+
+        assert_eq!(ty.id(), "a::b::C");
+        assert_eq!(ty.name(), "C");
+        assert_eq!(ty.path(), &[Symbol::from("a"), Symbol::from("b")]);
+        assert_eq!(TypeId::from(ty.id()), ty);
+    }
+
+    #[test]
+    fn simple_name() {
+        let ty = TypeId::from("int");
+
+        assert_eq!(ty.id(), "int");
+        assert_eq!(ty.name(), "int");
+        assert!(ty.path().is_empty());
+        assert_eq!(ty, TypeId::new(Symbol::from("int")));
+    }
+
+    #[test]
+    fn qualified_name_with_generics() {
+        let ty = TypeId::from("math::Vector").with_generic(TypeId::from("int"));
+
+        assert_eq!(ty.id(), "math::Vector");
+        assert_eq!(ty.path(), &[Symbol::from("math")]);
+        assert_ne!(ty, TypeId::from("Vector").with_generic(TypeId::from("int")));
+    }
+}
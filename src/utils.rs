@@ -43,6 +43,25 @@ macro_rules! jinko_fail {
     }
 }
 
+#[macro_export]
+macro_rules! jinko_warn {
+    ($($tokens:tt)*) => {
+        {
+            let mut ctx = $crate::context::Context::new(Box::new($crate::io_trait::JkStdReader));
+            ctx.init_stdlib().unwrap();
+
+            $crate::jk_parse!(&mut ctx, $($tokens)*);
+
+            ctx.emit_errors();
+            assert!(ctx.execute().is_ok());
+            assert!(!ctx.error_handler.has_errors());
+            assert!(!ctx.error_handler.warnings().is_empty());
+
+            ctx
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! jk_execute {
     ($($tokens:tt)*) => {
@@ -64,6 +83,76 @@ macro_rules! jinko_ex {
     }
 }
 
+/// In-memory output sink which can be given to a context while keeping access to
+/// everything written into it
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub(crate) struct SharedBuffer(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+
+#[cfg(test)]
+impl SharedBuffer {
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+#[cfg(test)]
+impl std::io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Temporary directory, unique to each test, which is removed along with its content
+/// once dropped
+#[cfg(test)]
+pub(crate) struct TempDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    /// Create a new, empty temporary directory whose name starts with `name`
+    pub(crate) fn new(name: &str) -> TempDir {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "jinko_{name}_{}_{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        TempDir(dir)
+    }
+
+    pub(crate) fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+
+    /// Write a file at `path`, relative to the directory, creating its parent
+    /// directories if needed. Returns the full path to the file
+    pub(crate) fn write(&self, path: &str, content: &str) -> std::path::PathBuf {
+        let path = self.0.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+
+        path
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -72,7 +161,7 @@ mod tests {
             mut a = 15;
         };
 
-        assert!(ctx.get_variable("a").is_some());
+        assert!(ctx.get_variable(&"a".into()).is_some());
     }
 
     #[test]
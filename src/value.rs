@@ -1,7 +1,7 @@
 //! A `Value` is a number instance in jinko. It refers to arithmetic primtive types, such
 //! as Ints and Floats
 
-use crate::error::Error;
+use crate::error::{ice, Error};
 use crate::instance::ObjectInstance;
 use crate::instruction::Instruction;
 use crate::instruction::Operator;
@@ -16,12 +16,40 @@ pub type JkFloat = JkConstant<f64>;
 pub type JkChar = JkConstant<char>;
 pub type JkString = JkConstant<String>;
 
+/// Behavior of integer arithmetic when the result of an operation cannot be
+/// represented on an `int`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArithmeticMode {
+    /// Overflowing operations are errors
+    #[default]
+    Checked,
+    /// Overflowing operations wrap around the boundaries of `int`
+    Wrapping,
+    /// Overflowing operations are clamped to the minimum or maximum `int`
+    Saturating,
+}
+
+/// How floats are turned into text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatFormat {
+    /// Shortest representation which reads back as the same float. It always
+    /// contains a decimal point or an exponent, so `1.0` is displayed as `1.0`
+    #[default]
+    Shortest,
+    /// Fixed amount of digits after the decimal point
+    Fixed(usize),
+}
+
 /// C is the type contained inside the `Value`
 pub trait Value: Instruction {
     /// Call this function when an operation is not implemented, rather than implementing
     /// your own. This will format the error nicely.
-    fn no_op(&self, _other: &Self, _op: Operator) -> Result<ObjectInstance, Error> {
-        unreachable!("NOP") // FIXME
+    fn no_op(&self, _other: &Self, op: Operator) -> Result<ObjectInstance, Error> {
+        Err(ice(format!(
+            "operator `{}` is not implemented for `{}`",
+            op.as_str(),
+            self.print()
+        )))
     }
 
     /// Realize any operation implemented by the type, and return a new instance
@@ -1,10 +1,10 @@
 use crate::context::Context;
-use crate::error::Error;
+use crate::error::{ErrKind, Error};
 use crate::instance::{FromObjectInstance, ObjectInstance, ToObjectInstance};
 use crate::instruction::{InstrKind, Instruction, Operator};
 use crate::location::SpanTuple;
 use crate::typechecker::{CheckedType, TypeCheck, TypeCtx, TypeId};
-use crate::value::{JkString, Value};
+use crate::value::{ArithmeticMode, FloatFormat, JkString, Value};
 
 use std::convert::TryFrom;
 
@@ -57,6 +57,25 @@ macro_rules! jk_primitive {
             )
         }
     };
+    (@frominstance $i:expr, $t:ty => "int" $size:expr) => {
+        Self::from(<$t>::from_ne_bytes(TryFrom::try_from($i.data()).unwrap()))
+    };
+    (@frominstance $i:expr, $t:ty => "float" $size:expr) => {
+        Self::from(<$t>::from_ne_bytes(TryFrom::try_from($i.data()).unwrap()))
+    };
+    (@frominstance $i:expr, $t:ty => $ty_name:literal $size:expr) => {
+        unsafe {
+            Self::from(std::mem::transmute::<[u8; $size], $t>(
+                TryFrom::try_from($i.data()).unwrap(),
+            ))
+        }
+    };
+    (@print $self:expr, "float") => {
+        $self.format(FloatFormat::default())
+    };
+    (@print $self:expr, $ty_name:literal) => {
+        $self.0.to_string()
+    };
     (@instantiate $t:ty => $ty_name:tt $size:expr) => {
         impl ToObjectInstance for JkConstant<$t> {
             fn to_instance(&self) -> ObjectInstance {
@@ -66,11 +85,11 @@ macro_rules! jk_primitive {
 
         impl FromObjectInstance for JkConstant<$t> {
             fn from_instance(i: &ObjectInstance) -> Self {
-                unsafe {
-                    Self::from(std::mem::transmute::<[u8; $size], $t>(
-                        TryFrom::try_from(i.data()).unwrap(),
-                    ))
-                }
+                jk_primitive!(@frominstance i, $t => $ty_name $size)
+            }
+
+            fn type_name() -> &'static str {
+                $ty_name
             }
         }
     };
@@ -80,11 +99,11 @@ macro_rules! jk_primitive {
 
         impl Instruction for JkConstant<$rust_type> {
             fn kind(&self) -> InstrKind {
-                InstrKind::Expression(None)
+                InstrKind::Expression(Some(self.to_instance()))
             }
 
             fn print(&self) -> String {
-                self.0.to_string()
+                jk_primitive!(@print self, $jk_type_name)
             }
 
             fn execute(&self, _ctx: &mut Context) -> Option<ObjectInstance> {
@@ -121,8 +140,109 @@ jk_primitive!(f64, "float");
 jk_primitive!(char, "char");
 jk_primitive!(bool, "bool");
 
+impl JkConstant<i64> {
+    /// Perform an arithmetic operation, erroring out if the result cannot be
+    /// represented on an integer
+    fn checked_op(
+        &self,
+        other: &Self,
+        op: Operator,
+        f: fn(i64, i64) -> Option<i64>,
+    ) -> Result<ObjectInstance, Error> {
+        match f(self.0, other.0) {
+            Some(result) => Ok(JkConstant::from(result).to_instance()),
+            None => Err(Error::new(ErrKind::Overflow).with_msg(format!(
+                "integer overflow in operation: {} {} {}",
+                self.0,
+                op.as_str(),
+                other.0
+            ))),
+        }
+    }
+
+    /// Realize an operation, handling overflows according to the given
+    /// [`ArithmeticMode`]. Dividing by zero is an error regardless of the mode
+    pub fn do_op_with_mode(
+        &self,
+        other: &Self,
+        op: Operator,
+        mode: ArithmeticMode,
+    ) -> Result<ObjectInstance, Error> {
+        let f: fn(i64, i64) -> Option<i64> = match (op, mode) {
+            (Operator::Div, _) if other.0 == 0 => {
+                return Err(Error::new(ErrKind::DivByZero)
+                    .with_msg(format!("trying to divide {} by zero", self.0)))
+            }
+            (Operator::Add, ArithmeticMode::Checked) => i64::checked_add,
+            (Operator::Sub, ArithmeticMode::Checked) => i64::checked_sub,
+            (Operator::Mul, ArithmeticMode::Checked) => i64::checked_mul,
+            (Operator::Div, ArithmeticMode::Checked) => i64::checked_div,
+            (Operator::Add, ArithmeticMode::Wrapping) => |l, r| Some(l.wrapping_add(r)),
+            (Operator::Sub, ArithmeticMode::Wrapping) => |l, r| Some(l.wrapping_sub(r)),
+            (Operator::Mul, ArithmeticMode::Wrapping) => |l, r| Some(l.wrapping_mul(r)),
+            (Operator::Div, ArithmeticMode::Wrapping) => |l, r| Some(l.wrapping_div(r)),
+            (Operator::Add, ArithmeticMode::Saturating) => |l, r| Some(l.saturating_add(r)),
+            (Operator::Sub, ArithmeticMode::Saturating) => |l, r| Some(l.saturating_sub(r)),
+            (Operator::Mul, ArithmeticMode::Saturating) => |l, r| Some(l.saturating_mul(r)),
+            (Operator::Div, ArithmeticMode::Saturating) => |l, r| Some(l.saturating_div(r)),
+            (Operator::Lt, _) => return Ok(JkConstant::from(self.0 < other.0).to_instance()),
+            (Operator::Gt, _) => return Ok(JkConstant::from(self.0 > other.0).to_instance()),
+            (Operator::LtEq, _) => return Ok(JkConstant::from(self.0 <= other.0).to_instance()),
+            (Operator::GtEq, _) => return Ok(JkConstant::from(self.0 >= other.0).to_instance()),
+            (Operator::Equals, _) => return Ok(JkConstant::from(self.0 == other.0).to_instance()),
+            (Operator::NotEquals, _) => {
+                return Ok(JkConstant::from(self.0 != other.0).to_instance())
+            }
+            (Operator::BitAnd, _) => return Ok(JkConstant::from(self.0 & other.0).to_instance()),
+            (Operator::BitOr, _) => return Ok(JkConstant::from(self.0 | other.0).to_instance()),
+            (Operator::BitXor, _) => return Ok(JkConstant::from(self.0 ^ other.0).to_instance()),
+            (Operator::Shl | Operator::Shr, _) if !(0..64).contains(&other.0) => {
+                return Err(Error::new(ErrKind::Context).with_msg(format!(
+                    "invalid shift amount in operation: {} {} {}: expected a value between 0 and 63",
+                    self.0,
+                    op.as_str(),
+                    other.0
+                )))
+            }
+            (Operator::Shl, _) => return Ok(JkConstant::from(self.0 << other.0).to_instance()),
+            (Operator::Shr, _) => return Ok(JkConstant::from(self.0 >> other.0).to_instance()),
+            _ => return self.no_op(other, op),
+        };
+
+        self.checked_op(other, op, f)
+    }
+}
+
 impl Value for JkConstant<i64> {
     fn do_op(&self, other: &Self, op: Operator) -> Result<ObjectInstance, Error> {
+        self.do_op_with_mode(other, op, ArithmeticMode::Checked)
+    }
+}
+
+impl JkConstant<f64> {
+    /// Turn the float into text according to the given [`FloatFormat`]. Special
+    /// values are displayed as `NaN`, `inf` and `-inf` regardless of the format
+    pub fn format(&self, format: FloatFormat) -> String {
+        match format {
+            FloatFormat::Shortest => format!("{:?}", self.0),
+            FloatFormat::Fixed(precision) => format!("{:.*}", precision, self.0),
+        }
+    }
+
+    /// Realize an operation. When an `epsilon` is given, `==` and `!=` consider two
+    /// floats equal if they are at most `epsilon` apart. Ordering operators such as
+    /// `<` are never affected by the epsilon
+    pub fn do_op_with_epsilon(
+        &self,
+        other: &Self,
+        op: Operator,
+        epsilon: Option<f64>,
+    ) -> Result<ObjectInstance, Error> {
+        let equals = |l: f64, r: f64| match epsilon {
+            Some(epsilon) => (l - r).abs() <= epsilon,
+            None => l == r,
+        };
+
         match op {
             Operator::Add => Ok(JkConstant::from(self.0 + other.0).to_instance()),
             Operator::Sub => Ok(JkConstant::from(self.0 - other.0).to_instance()),
@@ -132,26 +252,31 @@ impl Value for JkConstant<i64> {
             Operator::Gt => Ok(JkConstant::from(self.0 > other.0).to_instance()),
             Operator::LtEq => Ok(JkConstant::from(self.0 <= other.0).to_instance()),
             Operator::GtEq => Ok(JkConstant::from(self.0 >= other.0).to_instance()),
-            Operator::Equals => Ok(JkConstant::from(self.0 == other.0).to_instance()),
-            Operator::NotEquals => Ok(JkConstant::from(self.0 != other.0).to_instance()),
+            Operator::Equals => Ok(JkConstant::from(equals(self.0, other.0)).to_instance()),
+            Operator::NotEquals => Ok(JkConstant::from(!equals(self.0, other.0)).to_instance()),
             _ => self.no_op(other, op),
         }
     }
 }
 
 impl Value for JkConstant<f64> {
+    fn do_op(&self, other: &Self, op: Operator) -> Result<ObjectInstance, Error> {
+        self.do_op_with_epsilon(other, op, None)
+    }
+}
+
+impl Value for JkConstant<bool> {
+    /// Compare two `bool`s. Equality is the only operation available on `bool`s
     fn do_op(&self, other: &Self, op: Operator) -> Result<ObjectInstance, Error> {
         match op {
-            Operator::Add => Ok(JkConstant::from(self.0 + other.0).to_instance()),
-            Operator::Sub => Ok(JkConstant::from(self.0 - other.0).to_instance()),
-            Operator::Mul => Ok(JkConstant::from(self.0 * other.0).to_instance()),
-            Operator::Div => Ok(JkConstant::from(self.0 / other.0).to_instance()),
-            Operator::Lt => Ok(JkConstant::from(self.0 < other.0).to_instance()),
-            Operator::Gt => Ok(JkConstant::from(self.0 > other.0).to_instance()),
-            Operator::LtEq => Ok(JkConstant::from(self.0 <= other.0).to_instance()),
-            Operator::GtEq => Ok(JkConstant::from(self.0 >= other.0).to_instance()),
-            // TODO: Do we want to allow float equality comparison?
-            _ => self.no_op(other, op),
+            Operator::Equals => Ok(JkConstant::from(self.0 == other.0).to_instance()),
+            Operator::NotEquals => Ok(JkConstant::from(self.0 != other.0).to_instance()),
+            _ => Err(Error::new(ErrKind::TypeChecker).with_msg(format!(
+                "invalid operation on `bool`s: {} {} {}",
+                self.0,
+                op.as_str(),
+                other.0
+            ))),
         }
     }
 }
@@ -160,7 +285,7 @@ impl ToObjectInstance for JkString {
     fn to_instance(&self) -> ObjectInstance {
         ObjectInstance::from_bytes(
             CheckedType::Resolved(TypeId::from("string")),
-            self.0.as_bytes().len(),
+            self.0.len(),
             self.0.as_bytes(),
             None,
         )
@@ -172,11 +297,15 @@ impl FromObjectInstance for JkString {
         // unchecked is safe because this instance came from a utf8 string in ToObjectInstance
         unsafe { JkString::from(String::from_utf8_unchecked(i.data().to_vec())) }
     }
+
+    fn type_name() -> &'static str {
+        "string"
+    }
 }
 
 impl Instruction for JkString {
     fn kind(&self) -> InstrKind {
-        InstrKind::Expression(None)
+        InstrKind::Expression(Some(self.to_instance()))
     }
 
     fn print(&self) -> String {
@@ -227,6 +356,31 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn t_literal_kind_carries_instance() {
+        assert_eq!(
+            JkInt::from(15).kind(),
+            InstrKind::Expression(Some(JkInt::from(15).to_instance()))
+        );
+        assert_eq!(
+            JkString::from("jinko").kind(),
+            InstrKind::Expression(Some(JkString::from("jinko").to_instance()))
+        );
+    }
+
+    #[test]
+    fn t_binop_kind_carries_no_instance() {
+        use crate::instruction::{BinaryOp, Operator};
+
+        let binop = BinaryOp::new(
+            Box::new(JkInt::from(1)),
+            Box::new(JkInt::from(2)),
+            Operator::new("+"),
+        );
+
+        assert_eq!(binop.kind(), InstrKind::Expression(None));
+    }
+
     #[test]
     fn tc_string_type() {
         let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
@@ -281,4 +435,232 @@ mod tests {
             s = "jinko";
         };
     }
+
+    #[test]
+    fn t_int_div_by_zero() {
+        let err = JkInt::from(15)
+            .do_op(&JkInt::from(0), Operator::Div)
+            .unwrap_err();
+
+        assert_eq!(err.kind(), ErrKind::DivByZero);
+    }
+
+    #[test]
+    fn t_int_overflow() {
+        let add = JkInt::from(i64::MAX).do_op(&JkInt::from(1), Operator::Add);
+        let sub = JkInt::from(i64::MIN).do_op(&JkInt::from(1), Operator::Sub);
+        let mul = JkInt::from(i64::MAX).do_op(&JkInt::from(2), Operator::Mul);
+        let div = JkInt::from(i64::MIN).do_op(&JkInt::from(-1), Operator::Div);
+
+        assert_eq!(add.unwrap_err().kind(), ErrKind::Overflow);
+        assert_eq!(sub.unwrap_err().kind(), ErrKind::Overflow);
+        assert_eq!(mul.unwrap_err().kind(), ErrKind::Overflow);
+        assert_eq!(div.unwrap_err().kind(), ErrKind::Overflow);
+    }
+
+    fn add_max_with_mode(mode: ArithmeticMode) -> Result<Option<ObjectInstance>, Error> {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        ctx.set_arithmetic_mode(mode);
+
+        let input = "9223372036854775807 + 1";
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+
+        ctx.execute()
+    }
+
+    #[test]
+    fn t_int_overflow_checked_mode() {
+        assert!(add_max_with_mode(ArithmeticMode::Checked).is_err());
+    }
+
+    #[test]
+    fn t_int_overflow_wrapping_mode() {
+        assert_eq!(
+            add_max_with_mode(ArithmeticMode::Wrapping).unwrap(),
+            Some(JkInt::from(i64::MIN).to_instance())
+        );
+    }
+
+    #[test]
+    fn t_int_overflow_saturating_mode() {
+        assert_eq!(
+            add_max_with_mode(ArithmeticMode::Saturating).unwrap(),
+            Some(JkInt::from(i64::MAX).to_instance())
+        );
+    }
+
+    #[test]
+    fn t_int_modes() {
+        let (min, max) = (JkInt::from(i64::MIN), JkInt::from(i64::MAX));
+        let wrapping =
+            min.do_op_with_mode(&JkInt::from(-1), Operator::Div, ArithmeticMode::Wrapping);
+        let saturating =
+            max.do_op_with_mode(&JkInt::from(2), Operator::Mul, ArithmeticMode::Saturating);
+        let div_by_zero =
+            max.do_op_with_mode(&JkInt::from(0), Operator::Div, ArithmeticMode::Wrapping);
+
+        assert_eq!(wrapping.unwrap(), JkInt::from(i64::MIN).to_instance());
+        assert_eq!(saturating.unwrap(), JkInt::from(i64::MAX).to_instance());
+        assert_eq!(div_by_zero.unwrap_err().kind(), ErrKind::DivByZero);
+    }
+
+    fn float_equality(epsilon: Option<f64>) -> (ObjectInstance, ObjectInstance) {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        if let Some(epsilon) = epsilon {
+            ctx.set_float_epsilon(epsilon);
+        }
+
+        let equals = ctx.eval("0.1 + 0.2 == 0.3").unwrap().unwrap();
+        let not_equals = ctx.eval("0.1 + 0.2 != 0.3").unwrap().unwrap();
+
+        (equals, not_equals)
+    }
+
+    #[test]
+    fn t_float_equality_exact() {
+        let (equals, not_equals) = float_equality(None);
+
+        assert_eq!(equals, JkBool::from(false).to_instance());
+        assert_eq!(not_equals, JkBool::from(true).to_instance());
+    }
+
+    #[test]
+    fn t_float_equality_epsilon() {
+        let (equals, not_equals) = float_equality(Some(1e-9));
+
+        assert_eq!(equals, JkBool::from(true).to_instance());
+        assert_eq!(not_equals, JkBool::from(false).to_instance());
+    }
+
+    #[test]
+    fn t_float_epsilon_does_not_affect_ordering() {
+        let (lhs, rhs) = (JkFloat::from(1.0), JkFloat::from(1.05));
+
+        let lt = lhs.do_op_with_epsilon(&rhs, Operator::Lt, Some(0.1));
+        let eq = lhs.do_op_with_epsilon(&rhs, Operator::Equals, Some(0.1));
+
+        assert_eq!(lt.unwrap(), JkBool::from(true).to_instance());
+        assert_eq!(eq.unwrap(), JkBool::from(true).to_instance());
+    }
+
+    #[test]
+    fn t_bool_equality() {
+        let (t, f) = (JkBool::from(true), JkBool::from(false));
+
+        let equals = t.do_op(&f, Operator::Equals);
+        let not_equals = t.do_op(&f, Operator::NotEquals);
+
+        assert_eq!(equals.unwrap(), JkBool::from(false).to_instance());
+        assert_eq!(not_equals.unwrap(), JkBool::from(true).to_instance());
+    }
+
+    #[test]
+    fn t_bool_arithmetic_error() {
+        let (t, f) = (JkBool::from(true), JkBool::from(false));
+
+        for op in [Operator::Add, Operator::Lt, Operator::BitAnd] {
+            assert_eq!(t.do_op(&f, op).unwrap_err().kind(), ErrKind::TypeChecker);
+        }
+    }
+
+    #[test]
+    fn t_float_print() {
+        assert_eq!(JkFloat::from(1.0).print(), "1.0");
+        assert_eq!(JkFloat::from(0.5).print(), "0.5");
+        assert_eq!(JkFloat::from(1e-10).print(), "1e-10");
+        assert_eq!(JkFloat::from(f64::NAN).print(), "NaN");
+        assert_eq!(JkFloat::from(f64::INFINITY).print(), "inf");
+        assert_eq!(JkFloat::from(f64::NEG_INFINITY).print(), "-inf");
+    }
+
+    #[test]
+    fn t_float_format_fixed() {
+        let fixed = FloatFormat::Fixed(3);
+
+        assert_eq!(JkFloat::from(1.0).format(fixed), "1.000");
+        assert_eq!(JkFloat::from(0.5).format(fixed), "0.500");
+        assert_eq!(JkFloat::from(1e-10).format(fixed), "0.000");
+        assert_eq!(JkFloat::from(f64::NAN).format(fixed), "NaN");
+        assert_eq!(JkFloat::from(f64::INFINITY).format(fixed), "inf");
+    }
+
+    /// Convert each value to an instance and back, and check that it is unchanged.
+    /// Values are compared using `same`, so that floats can be compared bitwise
+    fn assert_round_trip<T: Clone + std::fmt::Debug>(values: &[T], same: impl Fn(&T, &T) -> bool)
+    where
+        JkConstant<T>: From<T> + ToObjectInstance + FromObjectInstance,
+    {
+        for value in values {
+            let instance = JkConstant::from(value.clone()).to_instance();
+            let converted = JkConstant::<T>::from_instance(&instance).rust_value();
+
+            assert!(same(value, &converted), "{value:?} became {converted:?}");
+            assert_eq!(instance.size(), instance.data().len());
+            assert_eq!(
+                instance.ty().resolved_id(),
+                Some(JkConstant::<T>::type_name())
+            );
+        }
+    }
+
+    #[test]
+    fn t_int_round_trip() {
+        assert_round_trip(&[0, 1, -1, 42, i64::MIN, i64::MAX], i64::eq);
+    }
+
+    #[test]
+    fn t_float_round_trip() {
+        let values = [
+            0.0,
+            -0.0,
+            1.5,
+            f64::MIN,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            f64::EPSILON,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+        ];
+
+        assert_round_trip(&values, |lhs: &f64, rhs: &f64| {
+            lhs.to_bits() == rhs.to_bits()
+        });
+    }
+
+    #[test]
+    fn t_bool_round_trip() {
+        assert_round_trip(&[true, false], bool::eq);
+    }
+
+    #[test]
+    fn t_char_round_trip() {
+        assert_round_trip(&['a', '\0', '\n', 'é', '🦀', char::MAX], char::eq);
+    }
+
+    #[test]
+    fn t_string_round_trip() {
+        let values = [
+            String::new(),
+            String::from("jinko"),
+            String::from("\0"),
+            String::from("multi\nline"),
+            String::from("🦀 ünïcode"),
+        ];
+
+        assert_round_trip(&values, String::eq);
+    }
+
+    #[test]
+    fn t_int_div_by_zero_runtime_error() {
+        let mut ctx = Context::new(Box::new(crate::io_trait::JkStdReader));
+        let input = "15 / 0";
+        crate::parser::parse(&mut ctx, input, crate::location::Source::Input(input)).unwrap();
+
+        assert!(ctx.execute().is_err());
+
+        let err = &ctx.error_handler.errors()[0];
+        assert_eq!(err.kind(), ErrKind::DivByZero);
+        assert_eq!(err.msg(), Some("trying to divide 15 by zero"));
+    }
 }
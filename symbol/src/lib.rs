@@ -5,18 +5,22 @@
 //! string directly on the stack if it is less than a pointer's width in length. This is
 //! quite useful since most names within a program are less than an usual pointer width.
 
-// FIXME: How do we do that? There's two ways to go around the issue:
-// 1. Keep a `static mut HashSet`
-// 2. Pass a `SymbolCtx` around at all times
-//
-// The first one is more disgusting, the second one more annoying to use.
-// The first one has the advantage of making it easy to initialize with "default" symbols:
-// int, float, string...
+// All symbols are stored in a single, process-global interner rather than in an
+// interner passed around with each context. Symbols are compared by address, so two
+// symbols created from the same string must come from the same interner: Since
+// instructions, types and contexts all create symbols and can be moved from one
+// context to another, a global interner is the only way to keep that true without
+// threading an interner through every constructor. It also makes it easy to
+// initialize with "default" symbols: int, float, string...
+// The interner is never emptied, which is fine since the amount of distinct names in
+// a program is bounded by its source code.
 
 // FIXME: The Symbol API should only take &str parameters, not Strings. Otherwise there's no point
 
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
 
 use lazy_static::lazy_static;
@@ -44,11 +48,48 @@ lazy_static! {
     };
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+/// Since all symbols are interned, two symbols are equal if and only if they share
+/// the same allocation. Comparing or hashing symbols is thus done on their address
+/// rather than on their content, which keeps it cheap regardless of their length.
+/// Symbols are still ordered according to their content, so that sorting them is
+/// deterministic
+#[derive(Debug, Clone)]
 pub struct Symbol(
     Arc<String>, /* FIXME: Switch to SmolStr or equivalent */
 );
 
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Symbol) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state)
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Symbol) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Symbol) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Default for Symbol {
+    fn default() -> Symbol {
+        Symbol::from("")
+    }
+}
+
 impl Symbol {
     /// # Panics
     ///
@@ -116,4 +157,22 @@ mod tests {
             assert!(set.get(&String::from("zip")).is_some());
         }
     }
+
+    #[test]
+    fn equality() {
+        assert_eq!(Symbol::from("zap"), Symbol::new(String::from("zap")));
+        assert_ne!(Symbol::from("zap"), Symbol::from("zop"));
+        assert_eq!(Symbol::default(), Symbol::from(""));
+    }
+
+    #[test]
+    fn ordering() {
+        let mut symbols = vec![Symbol::from("c"), Symbol::from("a"), Symbol::from("b")];
+        symbols.sort();
+
+        assert_eq!(
+            symbols,
+            vec![Symbol::from("a"), Symbol::from("b"), Symbol::from("c")]
+        );
+    }
 }
@@ -0,0 +1,23 @@
+//! Includes are resolved relative to the including file, so running the interpreter
+//! from another directory does not break them. Changing the current directory
+//! affects the whole process, which is why this test lives in its own test binary
+//! instead of running alongside the unit tests.
+
+use std::path::Path;
+
+use jinko::{io_trait::JkStdReader, location::Source, Context, JkInt, ToObjectInstance};
+
+#[test]
+fn include_relative_to_including_file_from_another_directory() {
+    let file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/ft/incl/incl_dir_subfile_includer.jk");
+    let input = std::fs::read_to_string(&file).unwrap();
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+
+    let mut ctx = Context::new(Box::new(JkStdReader));
+    ctx.set_path(Some(file.clone()));
+    jinko::parse(&mut ctx, &input, Source::Path(&file)).unwrap();
+
+    assert_eq!(ctx.execute().unwrap(), Some(JkInt::from(59).to_instance()));
+}
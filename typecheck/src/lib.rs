@@ -551,7 +551,7 @@ mod tests {
             ),
         );
         let single = Type::record(OriginIdx(0));
-        let empty = Type(OriginIdx(7), TypeSet(HashSet::new()));
+        let _empty = Type(OriginIdx(7), TypeSet(HashSet::new()));
 
         // FIXME: Decide on empty's behavior
 
@@ -657,7 +657,9 @@ fn unit_jk_inst(_input: ParseInput, _start_loc: Location) -> ParseResult<ParseIn
     //     Ok(inst) => Ok((input, Box::new(inst))),
     //     Err(err) => Err(NomError(err)),
     // }
-    todo!()
+    Err(NomError(Error::Msg(String::from(
+        "directives are not supported yet",
+    ))))
 }
 
 /// 'func' function_declaration ';'
@@ -1239,6 +1241,31 @@ mod tests {
     use ast::Operator;
     use ast::Value::*;
 
+    #[test]
+    fn directive_is_an_error() {
+        for input in ["@dump(a)", "@\"s\"*", "@"] {
+            assert!(crate::parse(input, location::Source::Input(input)).is_err());
+        }
+    }
+
+    #[test]
+    fn empty_program() {
+        for input in ["", "  \n\t", "// only a comment", "/* block */ # and line"] {
+            let ast = crate::parse(input, location::Source::Input(input)).unwrap();
+
+            match ast.node {
+                Block {
+                    stmts,
+                    last_is_expr,
+                } => {
+                    assert!(stmts.is_empty());
+                    assert!(!last_is_expr);
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
     #[test]
     fn block_expr() {
         let (_, ast) = expr(span!("{ a }")).unwrap();
@@ -42,12 +42,15 @@ pub fn parse<'i>(input: &'i str, source: Source<'i>) -> Result<Ast, Error<'i>> {
     let (input, stmts) = constructs::many_exprs(input)?;
     let (input, end) = position::<ParseInput, Error>(input)?;
 
+    // An empty program is valid, and behaves like an empty block
+    let last_is_expr = !stmts.is_empty();
+
     // TODO: How does this work with the last statement being an expression?
     Ok(Ast {
         location: constructs::pos_to_loc(input, start, end),
         node: Node::Block {
             stmts,
-            last_is_expr: true,
+            last_is_expr,
         }, // FIXME: Is that valid?
     })
 }